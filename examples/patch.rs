@@ -0,0 +1,59 @@
+//! Patch fields distinguish a missing key from an explicit `null`.
+
+use serde::{Deserialize, Serialize};
+use structural_typing::{presence::PatchValue, select, structural};
+
+#[structural]
+#[derive(Debug, Serialize, Deserialize)]
+struct Project {
+    id: u64,
+    name: String,
+    description: String,
+}
+
+type StoredProject = Project<select!(project: id, name, description?)>;
+type ProjectPatch = Project<select!(project: name~, description~)>;
+
+#[derive(Debug, PartialEq)]
+enum PatchError {
+    /// The patch set a required field to `null`.
+    Cleared(&'static str),
+}
+
+fn apply(stored: StoredProject, patch: ProjectPatch) -> Result<StoredProject, PatchError> {
+    // Missing keeps the stored value, null clears it, and a value replaces it. A Patch field
+    // merged over a Present one stays Patch, since the patch may clear it: `name` has to be
+    // required again before the project can be stored.
+    let merged = stored.merge(patch);
+    let (description, rest) = merged.extract::<select!(project: description?)>();
+    let (project, _) = rest
+        .try_extract::<select!(project: id, name)>()
+        .map_err(|_| PatchError::Cleared("name"))?;
+    Ok(project.description(description.description))
+}
+
+fn stored() -> StoredProject {
+    project::empty()
+        .id(1)
+        .name("Todos".to_owned())
+        .description(Some("Things to do".to_owned()))
+}
+
+fn main() {
+    let patch: ProjectPatch = serde_json::from_str(r#"{"description":null}"#).unwrap();
+    assert_eq!(patch.name, PatchValue::Missing);
+    assert_eq!(patch.description, PatchValue::Null);
+
+    let updated = apply(stored(), patch).unwrap();
+    assert_eq!(updated.name, "Todos");
+    assert_eq!(updated.description, None);
+
+    let json = serde_json::to_string(&updated).unwrap();
+    assert_eq!(json, r#"{"id":1,"name":"Todos","description":null}"#);
+
+    let patch: ProjectPatch = serde_json::from_str(r#"{"name":null}"#).unwrap();
+    assert_eq!(
+        apply(stored(), patch).unwrap_err(),
+        PatchError::Cleared("name")
+    );
+}
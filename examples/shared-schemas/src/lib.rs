@@ -0,0 +1,58 @@
+//! Schemas defined in one crate and used from others.
+//!
+//! The crate depends on nothing but `structural-typing`, so it also checks that plain
+//! `#[structural]` structs need no other dependencies. Each schema is exported, so that
+//! `select!` can name it from other crates.
+
+use structural_typing::{select, structural};
+
+/// A registered user.
+#[structural(views, export = "select_shared_user")]
+pub struct User {
+    pub id: u32,
+    pub name: String,
+    pub email: String,
+}
+
+/// A project grouping todos.
+#[structural(export = "select_shared_project")]
+pub struct Project {
+    pub id: u32,
+    pub name: String,
+}
+
+/// A todo item within a project.
+#[structural(export = "select_shared_todo")]
+pub struct Todo<P: project::Fields = select!(project: all)> {
+    pub id: u32,
+    pub title: String,
+    pub project: Project<P>,
+}
+
+/// Schemas whose nested schemas live in other modules.
+pub mod billing {
+    use structural_typing::{select, structural};
+
+    /// An invoice billing a user for one line.
+    #[structural(export = "select_shared_invoice")]
+    pub struct Invoice<
+        C: crate::user::Fields = select!(crate::user: all),
+        L: lines::line::Fields = select!(lines::line: all),
+    > {
+        pub id: u32,
+        pub customer: crate::User<C>,
+        pub line: lines::Line<L>,
+    }
+
+    /// Invoice contents.
+    pub mod lines {
+        use structural_typing::structural;
+
+        /// A billed amount.
+        #[structural(export = "select_shared_line")]
+        pub struct Line {
+            pub description: String,
+            pub cents: u64,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use shared_schemas::{User, user};
+use structural_typing::select;
+
+type Contact = select!(shared_schemas::user: name, email?);
+
+#[test]
+fn select_resolves_schemas_from_another_crate() {
+    let contact: User<Contact> = user::empty().name("Alice".to_owned()).email(None::<String>);
+    assert_eq!(contact.name, "Alice");
+    assert_eq!(contact.email, None);
+
+    let (id, _) = user::empty()
+        .id(1)
+        .name("Bob".to_owned())
+        .extract::<select!(shared_schemas::user: id)>();
+    assert_eq!(id.id, 1);
+}
+
+#[test]
+fn select_resolves_nested_schemas_from_another_crate() {
+    type Summary = select!(shared_schemas::todo: title, project { name });
+
+    let project = shared_schemas::project::empty().name("Home".to_owned());
+    let todo: Summary = shared_schemas::todo::empty()
+        .title("Sweep".to_owned())
+        .project(project);
+    assert_eq!(todo.project.name, "Home");
+}
+
+#[test]
+fn select_resolves_nested_schemas_relative_to_their_module() {
+    use shared_schemas::billing::{invoice, lines::line};
+
+    type Billed = select!(shared_schemas::billing::invoice: id, customer { name }, line { cents });
+
+    let invoice: Billed = invoice::empty()
+        .id(7)
+        .customer(user::empty().name("Alice".to_owned()))
+        .line(line::empty().cents(1250));
+    assert_eq!(invoice.customer.name, "Alice");
+    assert_eq!(invoice.line.cents, 1250);
+}
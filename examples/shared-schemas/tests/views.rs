@@ -0,0 +1,10 @@
+use shared_schemas::user;
+
+#[test]
+fn borrowed_views_are_copy() {
+    let user = user::empty().id(1).name("Alice".to_owned());
+    let view = user.as_ref();
+    let copy = view;
+    assert_eq!(view.name, "Alice");
+    assert_eq!(copy.id, &1);
+}
@@ -1,16 +1,11 @@
-use axum::{
-    Json, Router,
-    extract::{Path, State},
-    http::StatusCode,
-    routing::get,
-};
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
 use serde::{Deserialize, Serialize};
-use sqlx::sqlite::SqlitePool;
-use sqlx_query_as_builder::query_as_builder;
+use sqlx::{FromRow, QueryBuilder, sqlite::SqlitePool};
+use structural_typing::axum::{StructuralJson, StructuralPath};
 use structural_typing::{select, structural};
 
 #[structural]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, FromRow)]
 struct Project<T: todo::Fields = select!(todo: all-)> {
     id: i64,
     name: String,
@@ -19,10 +14,11 @@ struct Project<T: todo::Fields = select!(todo: all-)> {
 }
 
 #[structural]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, FromRow)]
 struct Todo<P: project::Fields = select!(project: all-)> {
     id: i64,
     #[nested(project: id, name, description)]
+    #[sqlx(prefix = "project_")]
     project: Project<P>,
     title: String,
     completed: bool,
@@ -38,21 +34,21 @@ type ProjectBasic = Project<ProjectBasicFields>;
 type CreateTodo = Todo<select!(todo: title, project), select!(project: id)>;
 type UpdateTodo = Todo<select!(todo: title?, completed?)>;
 type TodoId = Todo<select!(todo: id)>;
+type TodoRow = Todo<select!(todo: all), select!(project: id)>;
+type ProjectTodo = Todo<select!(todo: project-, all)>;
 type TodoWithProject = Todo<select!(todo: all), ProjectBasicFields>;
 
 async fn create_project(
     State(pool): State<SqlitePool>,
-    Json(payload): Json<CreateProject>,
+    StructuralJson(payload): StructuralJson<CreateProject>,
 ) -> Result<(StatusCode, Json<ProjectBasic>), StatusCode> {
-    let project = query_as_builder!(
-        project::empty(),
-        "INSERT INTO projects (name, description) VALUES (?, ?) RETURNING *",
-        payload.name,
-        payload.description
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let project: ProjectBasic = payload
+        .insert_query("projects")
+        .push(" RETURNING *")
+        .build_query_as()
+        .fetch_one(&pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     Ok((StatusCode::CREATED, Json(project)))
 }
@@ -63,7 +59,7 @@ struct ListProjects {
 }
 
 async fn list_projects(State(pool): State<SqlitePool>) -> Result<Json<ListProjects>, StatusCode> {
-    let projects = query_as_builder!(project::empty(), "SELECT * FROM projects")
+    let projects = sqlx::query_as("SELECT * FROM projects")
         .fetch_all(&pool)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
@@ -73,21 +69,20 @@ async fn list_projects(State(pool): State<SqlitePool>) -> Result<Json<ListProjec
 
 async fn get_project(
     State(pool): State<SqlitePool>,
-    Path(Project { id, .. }): Path<ProjectId>,
+    StructuralPath(Project { id, .. }): StructuralPath<ProjectId>,
 ) -> Result<Json<ProjectWithTodos>, StatusCode> {
-    let project = query_as_builder!(project::empty(), "SELECT * FROM projects WHERE id = ?", id)
+    let project: ProjectBasic = sqlx::query_as("SELECT * FROM projects WHERE id = ?")
+        .bind(id)
         .fetch_one(&pool)
         .await
         .map_err(|_| StatusCode::NOT_FOUND)?;
 
-    let todos = query_as_builder!(
-        todo::empty(),
-        "SELECT id, title, completed FROM todos WHERE project_id = ?",
-        id
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let todos: Vec<ProjectTodo> =
+        sqlx::query_as("SELECT id, title, completed FROM todos WHERE project_id = ?")
+            .bind(id)
+            .fetch_all(&pool)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     let project_with_todos = project.todos(todos);
 
@@ -96,32 +91,26 @@ async fn get_project(
 
 async fn create_todo(
     State(pool): State<SqlitePool>,
-    Json(payload): Json<CreateTodo>,
+    StructuralJson(payload): StructuralJson<CreateTodo>,
 ) -> Result<(StatusCode, Json<TodoWithProject>), StatusCode> {
     let mut tx = pool
         .begin()
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let inserted = query_as_builder!(
-        todo::empty().project(project::empty()),
-        r#"INSERT INTO todos (title, project_id, completed) VALUES (?, ?, 0)
-           RETURNING id as "id!", project_id, title, completed"#,
-        payload.title,
-        payload.project.id
-    )
-    .fetch_one(&mut *tx)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    let project = query_as_builder!(
-        project::empty(),
-        r#"SELECT * FROM projects WHERE id = ?"#,
-        inserted.project.id
-    )
-    .fetch_one(&mut *tx)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let inserted: TodoRow = payload
+        .insert_query("todos")
+        .push(" RETURNING id, project_id, title, completed")
+        .build_query_as()
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let project: ProjectBasic = sqlx::query_as("SELECT * FROM projects WHERE id = ?")
+        .bind(inserted.project.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     tx.commit()
         .await
@@ -130,83 +119,78 @@ async fn create_todo(
     Ok((StatusCode::CREATED, Json(inserted.project(project))))
 }
 
+/// Selects exactly the columns of `TodoWithProject`, the project's from the joined table.
+fn select_todos_with_project(filter: &str) -> String {
+    let columns = todo::select_list::<select!(todo: all), ProjectBasicFields>()
+        .table("todos")
+        .nested("project_", "projects");
+    format!("SELECT {columns} FROM todos JOIN projects ON todos.project_id = projects.id {filter}")
+}
+
 #[derive(Serialize)]
 struct ListTodos {
     todos: Vec<TodoWithProject>,
 }
 
 async fn list_todos(State(pool): State<SqlitePool>) -> Result<Json<ListTodos>, StatusCode> {
-    let todos = query_as_builder!(
-        todo::empty().project(project::empty()),
-        r#"SELECT
-            todos.*,
-            projects.name as project_name,
-            projects.description as project_description
-         FROM todos
-         JOIN projects ON todos.project_id = projects.id"#
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let todos = sqlx::query_as(&select_todos_with_project(""))
+        .fetch_all(&pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     Ok(Json(ListTodos { todos }))
 }
 
 async fn get_todo(
     State(pool): State<SqlitePool>,
-    Path(Todo { id, .. }): Path<TodoId>,
+    StructuralPath(Todo { id, .. }): StructuralPath<TodoId>,
 ) -> Result<Json<TodoWithProject>, StatusCode> {
-    let todo = query_as_builder!(
-        todo::empty().project(project::empty()),
-        r#"SELECT
-            todos.*,
-            projects.name as project_name,
-            projects.description as project_description
-         FROM todos
-         JOIN projects ON todos.project_id = projects.id
-         WHERE todos.id = ?"#,
-        id
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|_| StatusCode::NOT_FOUND)?;
+    let todo = sqlx::query_as(&select_todos_with_project("WHERE todos.id = ?"))
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
 
     Ok(Json(todo))
 }
 
 async fn update_todo(
     State(pool): State<SqlitePool>,
-    Path(Todo { id, .. }): Path<TodoId>,
-    Json(payload): Json<UpdateTodo>,
+    StructuralPath(Todo { id, .. }): StructuralPath<TodoId>,
+    StructuralJson(payload): StructuralJson<UpdateTodo>,
 ) -> Result<Json<TodoWithProject>, StatusCode> {
     let mut tx = pool
         .begin()
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let updated = query_as_builder!(
-        todo::empty().project(project::empty()),
-        r#"UPDATE todos
-           SET title = COALESCE(?, title),
-               completed = COALESCE(?, completed)
-           WHERE id = ?
-           RETURNING *"#,
-        payload.title,
-        payload.completed,
-        id
-    )
-    .fetch_one(&mut *tx)
-    .await
-    .map_err(|_| StatusCode::NOT_FOUND)?;
-
-    let project = query_as_builder!(
-        project::empty(),
-        r#"SELECT * FROM projects WHERE id = ?"#,
-        updated.project.id
-    )
-    .fetch_one(&mut *tx)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    // Only the provided fields are assigned; with none, the todo is read unchanged.
+    let mut query = match payload.update_query("todos") {
+        Some(mut query) => {
+            query
+                .push(" WHERE id = ")
+                .push_bind(id)
+                .push(" RETURNING id, project_id, title, completed");
+            query
+        }
+        None => {
+            let mut query =
+                QueryBuilder::new("SELECT id, project_id, title, completed FROM todos WHERE id = ");
+            query.push_bind(id);
+            query
+        }
+    };
+    let updated: TodoRow = query
+        .build_query_as()
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let project: ProjectBasic = sqlx::query_as("SELECT * FROM projects WHERE id = ?")
+        .bind(updated.project.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     tx.commit()
         .await
@@ -217,7 +201,7 @@ async fn update_todo(
 
 async fn delete_todo(
     State(pool): State<SqlitePool>,
-    Path(Todo { id, .. }): Path<TodoId>,
+    StructuralPath(Todo { id, .. }): StructuralPath<TodoId>,
 ) -> Result<StatusCode, StatusCode> {
     sqlx::query!("DELETE FROM todos WHERE id = ?", id)
         .execute(&pool)
@@ -1,5 +1,5 @@
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use std::collections::HashMap;
 use syn::Ident;
 
@@ -8,11 +8,19 @@ use crate::codegen::type_subst;
 use crate::parsing::StructInfo;
 
 fn generate_field_types_with_inferred(info: &StructInfo, target_field: &Ident) -> Vec<TokenStream> {
+    generate_field_types_with(info, target_field, &quote! { V::Presence })
+}
+
+fn generate_field_types_with(
+    info: &StructInfo,
+    target_field: &Ident,
+    presence: &TokenStream,
+) -> Vec<TokenStream> {
     info.fields
         .iter()
         .map(|f| {
             if f.name == *target_field {
-                quote! { V::Presence }
+                presence.clone()
             } else {
                 let name = &f.name;
                 quote! { F::#name }
@@ -100,11 +108,68 @@ pub fn generate(info: &StructInfo) -> TokenStream {
         }
     });
 
+    let removers = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let field_ty = &field.ty;
+        let name = field_name.to_string();
+        let name = name.strip_prefix("r#").unwrap_or(&name);
+        let take_name = format_ident!("take_{}", name, span = field_name.span());
+        let without_name = format_ident!("without_{}", name, span = field_name.span());
+
+        let field_types = generate_field_types_with(
+            info,
+            field_name,
+            &quote! { ::structural_typing::presence::Absent },
+        );
+        let return_type_args = type_args_with_f(
+            &info.generics,
+            &user_type_args,
+            quote! { #module_name::FieldSet<#(#field_types),*> },
+        );
+
+        let field_assignments: Vec<_> = info
+            .fields
+            .iter()
+            .map(|f| {
+                let name = &f.name;
+                if f.name == *field_name {
+                    quote! { #name: ::core::marker::PhantomData }
+                } else {
+                    quote! { #name: self.#name }
+                }
+            })
+            .collect();
+
+        let take_doc = format!("Removes `{name}`, returning its value and the rest of the struct.");
+        let without_doc = format!("Removes `{name}`, dropping its value.");
+
+        quote! {
+            #[doc = #take_doc]
+            #[must_use]
+            pub fn #take_name(self) -> (
+                <F::#field_name as ::structural_typing::presence::Presence>::Output<#field_ty>,
+                #struct_name #return_type_args,
+            ) {
+                (self.#field_name, #struct_name {
+                    #(#field_assignments),*
+                })
+            }
+
+            #[doc = #without_doc]
+            #[must_use]
+            pub fn #without_name(self) -> #struct_name #return_type_args {
+                self.#take_name().1
+            }
+        }
+    });
+
     let impl_type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
 
     quote! {
         impl #impl_generics #struct_name #impl_type_args #where_clause {
             #(#methods)*
+
+            #(#removers)*
         }
     }
 }
@@ -0,0 +1,224 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{GenericArgument, PathArguments, Type};
+
+use crate::codegen::generics_utils::{fields_module, impl_generics_with_f, type_args_with_f};
+use crate::codegen::serde_deserialize::input_name;
+use crate::parsing::{FieldInfo, StructInfo};
+
+/// Field attributes replacing a field's `Serialize` or `Deserialize`, which the compact encoding
+/// does not call.
+const CODEC_ATTRS: &[&str] = &["with", "serialize_with", "deserialize_with"];
+
+/// Generates the compact encoding for `#[structural(compact)]`: `CompactSerialize` alongside
+/// `Serialize` and `CompactDeserialize` alongside `Deserialize`.
+pub fn generate(info: &StructInfo) -> syn::Result<TokenStream> {
+    let Some(option) = &info.options.compact else {
+        return Ok(quote! {});
+    };
+    let has_serialize = info.derives.iter().any(|d| d == "Serialize");
+    let has_deserialize = info.derives.iter().any(|d| d == "Deserialize");
+    if !has_serialize && !has_deserialize {
+        return Err(syn::Error::new_spanned(
+            option,
+            "`compact` requires #[derive(Serialize)] or #[derive(Deserialize)]",
+        ));
+    }
+
+    validate_codec_attrs(info)?;
+
+    let serialize = has_serialize.then(|| generate_serialize(info));
+    let deserialize = has_deserialize.then(|| generate_deserialize(info));
+    Ok(quote! {
+        #serialize
+        #deserialize
+    })
+}
+
+fn generate_serialize(info: &StructInfo) -> TokenStream {
+    let struct_name = &info.name;
+    let module_name = &info.module_name;
+    let field_count = info.fields.len();
+
+    let (mut impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
+    let impl_type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
+    let where_clause = impl_generics.make_where_clause();
+    for field in &info.fields {
+        let field_type = &field.ty;
+        where_clause.predicates.push(if is_nested(info, field) {
+            syn::parse_quote! { #field_type: ::structural_typing::serde::compact::CompactSerialize }
+        } else {
+            syn::parse_quote! { #field_type: ::serde::Serialize }
+        });
+    }
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+
+    let states = info.fields.iter().enumerate().map(|(index, field)| {
+        let field_name = &field.name;
+        let field_type = &field.ty;
+        quote! {
+            bitmap.set(#index, ::structural_typing::serde::compact::FieldState::of::<_, #field_type>(&self.#field_name));
+        }
+    });
+    let values = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let field_type = &field.ty;
+        let value = if is_nested(info, field) {
+            quote! { &::structural_typing::serde::compact::Compact(value) }
+        } else {
+            quote! { value }
+        };
+        quote! {
+            if let Some(value) = ::structural_typing::access::Access::<#field_type>::get(&self.#field_name) {
+                tuple.serialize_element(#value)?;
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics ::structural_typing::serde::compact::CompactSerialize for #struct_name #impl_type_args #where_clause {
+            const FIELDS: usize = #field_count;
+
+            fn fill_bitmap(&self, bitmap: &mut ::structural_typing::serde::compact::Bitmap) {
+                #(#states)*
+            }
+
+            fn write_values<S: ::serde::ser::SerializeTuple>(&self, tuple: &mut S) -> ::core::result::Result<(), S::Error> {
+                #(#values)*
+                Ok(())
+            }
+        }
+    }
+}
+
+fn generate_deserialize(info: &StructInfo) -> TokenStream {
+    let struct_name = &info.name;
+    let module_name = &info.module_name;
+    let field_count = info.fields.len();
+
+    let (mut impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
+    let impl_type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
+    impl_generics.params.insert(0, syn::parse_quote! { '__de });
+    let where_clause = impl_generics.make_where_clause();
+    for field in &info.fields {
+        let field_type = &field.ty;
+        where_clause.predicates.push(if is_nested(info, field) {
+            syn::parse_quote! { #field_type: ::structural_typing::serde::compact::CompactDeserialize<'__de> }
+        } else {
+            syn::parse_quote! { #field_type: ::serde::Deserialize<'__de> }
+        });
+    }
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+
+    let conversions = info.fields.iter().enumerate().map(|(index, field)| {
+        let field_name = &field.name;
+        let field_type = &field.ty;
+        let input_name = input_name(info, field);
+        let converted = format_ident!("{}_converted", field_name.unraw());
+        let read = if is_nested(info, field) {
+            quote! { bitmap.read_compact(#index, seq)? }
+        } else {
+            quote! { bitmap.read(#index, seq)? }
+        };
+        quote! {
+            let #converted = match <::structural_typing::presence::Patch as TryExtract<<F as #module_name::Fields>::#field_name>>::try_extract::<#field_type>(#read) {
+                Ok((converted, _)) => Some(converted),
+                Err(_) => {
+                    missing.push(#input_name);
+                    None
+                }
+            };
+        }
+    });
+    let values = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let converted = format_ident!("{}_converted", field_name.unraw());
+        quote! {
+            #field_name: match #converted {
+                Some(converted) => converted,
+                None => unreachable!("missing fields are reported before construction"),
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics ::structural_typing::serde::compact::CompactDeserialize<'__de> for #struct_name #impl_type_args #where_clause {
+            const FIELDS: usize = #field_count;
+
+            fn read_values<A: ::serde::de::SeqAccess<'__de>>(
+                bitmap: &::structural_typing::serde::compact::Bitmap,
+                seq: &mut A,
+            ) -> ::core::result::Result<Self, A::Error> {
+                use ::structural_typing::extract::TryExtract;
+
+                let mut missing = ::std::vec::Vec::new();
+                #(#conversions)*
+                if !missing.is_empty() {
+                    return Err(<A::Error as ::serde::de::Error>::custom(
+                        ::structural_typing::serde::FieldsError::new(missing, ::std::vec::Vec::new()),
+                    ));
+                }
+
+                Ok(Self {
+                    #(#values),*
+                })
+            }
+        }
+    }
+}
+
+/// Whether the field holds a nested structural value, written in the compact encoding too: it is
+/// marked `#[nested(...)]` or its type takes one of the struct's field-set parameters, like
+/// `Project<P>`.
+fn is_nested(info: &StructInfo, field: &FieldInfo) -> bool {
+    if field.nested_fields.is_some() {
+        return true;
+    }
+    let Type::Path(type_path) = &field.ty else {
+        return false;
+    };
+    let Some(PathArguments::AngleBracketed(args)) = type_path
+        .path
+        .segments
+        .last()
+        .map(|segment| &segment.arguments)
+    else {
+        return false;
+    };
+    args.args.iter().any(|arg| {
+        let GenericArgument::Type(Type::Path(arg)) = arg else {
+            return false;
+        };
+        info.generics
+            .type_params()
+            .any(|param| arg.path.is_ident(&param.ident) && fields_module(&param.bounds).is_some())
+    })
+}
+
+/// Rejects `#[serde(with)]`, `serialize_with` and `deserialize_with` on fields, which the compact
+/// encoding would silently bypass.
+fn validate_codec_attrs(info: &StructInfo) -> syn::Result<()> {
+    for attr in info.fields.iter().flat_map(|field| &field.attrs) {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        let mut codec = None;
+        let _ = attr.parse_nested_meta(|meta| {
+            if CODEC_ATTRS.iter().any(|name| meta.path.is_ident(name)) {
+                codec = meta.path.get_ident().cloned();
+            }
+            if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+            Ok(())
+        });
+        if let Some(codec) = codec {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!("`compact` does not support #[serde({codec} = \"...\")] on fields"),
+            ));
+        }
+    }
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
-use crate::codegen::generics_utils::{impl_generics_with_f, remainder_type_args, type_args_with_f};
+use crate::codegen::generics_utils::{impl_generics_with_f, type_args_with_f};
 use crate::parsing::StructInfo;
 
 fn sanitize_ident(ident: &syn::Ident) -> String {
@@ -15,22 +15,20 @@ pub fn generate(info: &StructInfo) -> TokenStream {
 
     let (impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
     let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
-    let remainder_type_args = remainder_type_args(&info.generics, &user_type_args);
     let impl_type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
     let f2_type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F2 });
     let remainder_type_args_full = type_args_with_f(
         &info.generics,
         &user_type_args,
-        quote! { #module_name::Remainder #remainder_type_args },
+        quote! { #module_name::Remainder<F, F2> },
     );
 
     let field_names: Vec<_> = info.fields.iter().map(|f| &f.name).collect();
 
     let extract_where_clauses = info.fields.iter().map(|field| {
         let field_name = &field.name;
-        let field_type = &field.ty;
         quote! {
-            <F::#field_name as ::structural_typing::presence::Presence>::Output<#field_type>: ::structural_typing::extract::Extract<<F2::#field_name as ::structural_typing::presence::Presence>::Output<#field_type>, #field_type>
+            F::#field_name: ::structural_typing::extract::Extract<F2::#field_name>
         }
     });
 
@@ -41,7 +39,7 @@ pub fn generate(info: &StructInfo) -> TokenStream {
         let field_name_r = syn::Ident::new(&format!("{}_r", sanitized), field_name.span());
         let field_name_o = syn::Ident::new(&format!("{}_o", sanitized), field_name.span());
         quote! {
-            let (#field_name_o, #field_name_r) = <<F::#field_name as ::structural_typing::presence::Presence>::Output<#field_type> as ::structural_typing::extract::Extract<<F2::#field_name as ::structural_typing::presence::Presence>::Output<#field_type>, #field_type>>::extract(self.#field_name);
+            let (#field_name_o, #field_name_r) = <F::#field_name as ::structural_typing::extract::Extract<F2::#field_name>>::extract::<#field_type>(self.#field_name);
         }
     });
 
@@ -71,17 +69,11 @@ pub fn generate(info: &StructInfo) -> TokenStream {
         })
         .collect();
 
-    let try_extract_where_clauses = info.fields.iter().map(|field| {
+    let narrow_fields = info.fields.iter().map(|field| {
         let field_name = &field.name;
         let field_type = &field.ty;
         quote! {
-            <F::#field_name as ::structural_typing::presence::Presence>::Output<#field_type>: ::structural_typing::extract::TryExtract<<F2::#field_name as ::structural_typing::presence::Presence>::Output<#field_type>, #field_type>,
-            <<
-                <<<F2::#field_name as ::structural_typing::presence::Presence>::Output<#field_type> as ::structural_typing::access::Access<#field_type>>::RemainderFrom<<F::#field_name as ::structural_typing::presence::Presence>::Output<#field_type>>
-                as ::structural_typing::presence::InferPresence<#field_type>>::Presence
-                as ::structural_typing::presence::Presence
-            >::Or<F2::#field_name>
-            as ::structural_typing::presence::Presence>::Output<#field_type>: ::structural_typing::extract::TryExtract<<F::#field_name as ::structural_typing::presence::Presence>::Output<#field_type>, #field_type>
+            #field_name: <F as #module_name::Satisfies<F2>>::#field_name::<#field_type>(self.#field_name)
         }
     });
 
@@ -101,19 +93,10 @@ pub fn generate(info: &StructInfo) -> TokenStream {
             let prev_o = syn::Ident::new(&format!("{}_o", prev_sanitized), prev_name.span());
             quote! {
                 #prev_name: match <
-                    <<
-                        <<<F2::#prev_name as ::structural_typing::presence::Presence>::Output<#prev_type> as ::structural_typing::access::Access<#prev_type>>::RemainderFrom<<F::#prev_name as ::structural_typing::presence::Presence>::Output<#prev_type>>
-                        as ::structural_typing::presence::InferPresence<#prev_type>>::Presence
-                        as ::structural_typing::presence::Presence
-                    >::Or<F2::#prev_name>
-                    as ::structural_typing::presence::Presence>::Output<#prev_type>
-                    as ::structural_typing::extract::TryExtract<<F::#prev_name as ::structural_typing::presence::Presence>::Output<#prev_type>, #prev_type>
-                >::try_extract(
-                    <
-                        <<<F2::#prev_name as ::structural_typing::presence::Presence>::Output<#prev_type> as ::structural_typing::access::Access<#prev_type>>::RemainderFrom<<F::#prev_name as ::structural_typing::presence::Presence>::Output<#prev_type>>
-                        as ::structural_typing::presence::InferPresence<#prev_type>>::Presence
-                        as ::structural_typing::presence::Presence
-                    >::or(#prev_r, #prev_o)
+                    <<F2::#prev_name as ::structural_typing::presence::Presence>::Remainder<F::#prev_name> as ::structural_typing::presence::Presence>::Or<F2::#prev_name>
+                    as ::structural_typing::extract::TryExtract<F::#prev_name>
+                >::try_extract::<#prev_type>(
+                    <<F2::#prev_name as ::structural_typing::presence::Presence>::Remainder<F::#prev_name> as ::structural_typing::presence::Presence>::or::<#prev_type, F2::#prev_name>(#prev_r, #prev_o)
                 ) {
                     Ok((reconstructed, _)) => reconstructed,
                     Err(_) => unreachable!("reconstruction from extracted parts cannot fail"),
@@ -125,7 +108,7 @@ pub fn generate(info: &StructInfo) -> TokenStream {
         let unconsumed_fields: Vec<_> = field_names.iter().skip(idx + 1).collect();
 
         quote! {
-            let (#field_name_o, #field_name_r) = match <<F::#field_name as ::structural_typing::presence::Presence>::Output<#field_type> as ::structural_typing::extract::TryExtract<<F2::#field_name as ::structural_typing::presence::Presence>::Output<#field_type>, #field_type>>::try_extract(#field_name) {
+            let (#field_name_o, #field_name_r) = match <F::#field_name as ::structural_typing::extract::TryExtract<F2::#field_name>>::try_extract::<#field_type>(#field_name) {
                 Ok(result) => result,
                 Err(original_field) => {
                     return Err(#struct_name {
@@ -155,12 +138,20 @@ pub fn generate(info: &StructInfo) -> TokenStream {
                 })
             }
 
-            /// Extracts selected fields and remainder. Returns `Err(self)` if any Optional field is None but target needs Present.
+            /// Converts to the field set `F2`, dropping every field `F2` does not select. Always succeeds.
             #[must_use]
-            pub fn try_extract<F2: #module_name::Fields>(self) -> Result<(#struct_name #f2_type_args, #struct_name #remainder_type_args_full), Self>
+            pub fn narrow<F2: #module_name::Fields>(self) -> #struct_name #f2_type_args
             where
-                #(#try_extract_where_clauses),*
+                F: #module_name::Satisfies<F2>,
             {
+                #struct_name {
+                    #(#narrow_fields),*
+                }
+            }
+
+            /// Extracts selected fields and remainder. Returns `Err(self)` if any Optional field is None but target needs Present.
+            #[must_use]
+            pub fn try_extract<F2: #module_name::Fields>(self) -> Result<(#struct_name #f2_type_args, #struct_name #remainder_type_args_full), Self> {
                 let #struct_name { #(#field_names),* } = self;
 
                 #(#try_extract_field_extracts)*
@@ -1,8 +1,9 @@
 use proc_macro2::TokenStream;
 use quote::quote;
+use syn::ext::IdentExt;
 use syn::{Expr, GenericArgument, Ident, PathArguments, Type};
 
-use crate::codegen::generics_utils;
+use crate::codegen::{generics_utils, select};
 use crate::parsing::StructInfo;
 
 fn generate_fields_trait_parts(field_names: &[&Ident]) -> Vec<TokenStream> {
@@ -52,18 +53,157 @@ fn generate_merge_fields(field_names: &[&Ident]) -> Vec<TokenStream> {
         .collect()
 }
 
-fn generate_remainder_fields(field_names: &[&Ident], field_types: &[&Type]) -> Vec<TokenStream> {
+fn generate_remainder_fields(field_names: &[&Ident]) -> Vec<TokenStream> {
     field_names
         .iter()
-        .zip(field_types.iter())
-        .map(|(name, ty)| {
+        .map(|name| {
             quote! {
-                <<<<F2 as Fields>::#name as ::structural_typing::presence::Presence>::Output<#ty> as ::structural_typing::access::Access<#ty>>::RemainderFrom<<<F1 as Fields>::#name as ::structural_typing::presence::Presence>::Output<#ty>> as ::structural_typing::presence::InferPresence<#ty>>::Presence
+                <<F2 as Fields>::#name as Presence>::Remainder<<F1 as Fields>::#name>
             }
         })
         .collect()
 }
 
+fn generate_utility_types(field_names: &[&Ident]) -> TokenStream {
+    let partial_fields = field_names
+        .iter()
+        .map(|name| quote! { <<F as Fields>::#name as Presence>::Partial });
+    let required_fields = field_names
+        .iter()
+        .map(|name| quote! { <<F as Fields>::#name as Presence>::Required });
+    let intersect_fields = field_names
+        .iter()
+        .map(|name| quote! { <<F2 as Fields>::#name as Presence>::Keep<<F1 as Fields>::#name> });
+
+    quote! {
+        /// Utilities deriving one field set from another.
+        ///
+        /// They live in their own module so that their names do not shadow user types of the
+        /// same name, which the schema module imports.
+        pub mod ops {
+            use super::*;
+
+            /// Makes every field optional: Present → Optional, other states unchanged.
+            pub type Partial<F> = FieldSet<
+                #(#partial_fields),*
+            >;
+
+            /// Makes every field required: Optional and Patch → Present, other states unchanged.
+            pub type Required<F> = FieldSet<
+                #(#required_fields),*
+            >;
+
+            /// Keeps the fields of `F1` that `F2` also has (with `F1`'s presence); the rest become Absent.
+            ///
+            /// `select!(module: Pick<F>, a, b)` is shorthand for `Intersect<F, select!(module: a, b)>`.
+            pub type Intersect<F1, F2> = FieldSet<
+                #(#intersect_fields),*
+            >;
+
+            /// Keeps the fields of `F1` that `F2` does not have; the rest become Absent.
+            ///
+            /// Same as `Remainder<F1, F2>`. `select!(module: Omit<F>, a)` is shorthand for
+            /// `Difference<F, select!(module: a)>`.
+            pub type Difference<F1, F2> = super::Remainder<F1, F2>;
+        }
+    }
+}
+
+fn generate_field_requirements(info: &StructInfo) -> TokenStream {
+    let struct_name = info.name.to_string();
+
+    let requirement_traits = info.fields.iter().map(|field| {
+        let name = &field.name;
+        let display_name = name.unraw();
+        let message = format!(
+            "field `{display_name}` of `{struct_name}` is required to be `{{Expected}}` but is `{{Actual}}`"
+        );
+        let label = format!("`{display_name}` is `{{Actual}}` here");
+        quote! {
+            #[diagnostic::on_unimplemented(message = #message, label = #label)]
+            #[allow(non_camel_case_types)]
+            pub trait #name<Expected: Presence, Actual: Presence = <Self as Fields>::#name>:
+                Fields<#name = Expected>
+            {
+            }
+
+            // Comparing the presences in the impl header, rather than through `Fields<#name = P>`
+            // alone, keeps a mismatch an unmet trait bound with the message above.
+            #[allow(non_camel_case_types)]
+            impl<F: Fields<#name = P>, P: Presence> self::#name<P, P> for F {}
+        }
+    });
+
+    quote! {
+        /// Per-field presence requirements with readable compile errors.
+        ///
+        /// `F: field::name<Present>` means the same as `F: Fields<name = Present>`, but an unmet
+        /// requirement is reported as "field `name` of `Struct` is required to be `Present` but
+        /// is `Absent`" rather than as an associated type mismatch.
+        pub mod field {
+            use super::*;
+
+            #(#requirement_traits)*
+        }
+    }
+}
+
+fn generate_relations(field_names: &[&Ident]) -> TokenStream {
+    let narrow_signatures: Vec<_> = field_names
+        .iter()
+        .map(|name| {
+            quote! {
+                fn #name<T>(value: <Self::#name as Presence>::Output<T>) -> <G::#name as Presence>::Output<T>
+            }
+        })
+        .collect();
+    // `Extract` alone would let Absent fields satisfy Optional and Patch ones, which accept a
+    // missing value; `Keep` stays Absent only for Absent fields, so it limits them to Absent.
+    let extract_bounds = field_names.iter().map(|name| {
+        quote! {
+            F::#name: ::structural_typing::extract::Extract<G::#name>
+                + ::structural_typing::presence::Presence<Keep<G::#name> = G::#name>
+        }
+    });
+    let narrow_impls = field_names.iter().zip(&narrow_signatures).map(|(name, signature)| {
+        quote! {
+            #[inline]
+            #signature {
+                <F::#name as ::structural_typing::extract::Extract<G::#name>>::extract::<T>(value).0
+            }
+        }
+    });
+
+    quote! {
+        /// Implemented when every field of `Self` can be extracted infallibly into its presence in `G`.
+        ///
+        /// Present satisfies every presence, Optional and Patch satisfy Optional, Patch and Absent,
+        /// and Absent satisfies only Absent. Use `F: Satisfies<G>` to call `narrow::<G>()` on a
+        /// struct with any field set `F` that provides at least what `G` asks for.
+        pub trait Satisfies<G: Fields>: Fields {
+            #(
+                #[doc(hidden)]
+                #narrow_signatures;
+            )*
+        }
+
+        impl<F: Fields, G: Fields> Satisfies<G> for F
+        where
+            #(#extract_bounds),*
+        {
+            #(#narrow_impls)*
+        }
+
+        /// `G: SubsetOf<F>` holds exactly when `F: Satisfies<G>`: `G` asks for no more than `F` provides.
+        ///
+        /// Only `Satisfies` unlocks `narrow`; this trait reads better when stating the relation
+        /// from the smaller side.
+        pub trait SubsetOf<F: Fields>: Fields {}
+
+        impl<F: Satisfies<G>, G: Fields> SubsetOf<F> for G {}
+    }
+}
+
 fn generate_empty_constructor(info: &StructInfo) -> TokenStream {
     let struct_name = &info.name;
     let field_names: Vec<_> = info.fields.iter().map(|f| &f.name).collect();
@@ -366,39 +506,32 @@ fn generate_with_modules(field_names: &[&Ident]) -> TokenStream {
     }
 }
 
-pub fn generate(info: &StructInfo, serde_helper: Option<TokenStream>) -> TokenStream {
+pub fn generate(
+    info: &StructInfo,
+    serde_helper: Option<TokenStream>,
+    sqlx_columns: Option<TokenStream>,
+    component_fields: Option<TokenStream>,
+    ts_interface: Option<TokenStream>,
+) -> TokenStream {
     let module_name = &info.module_name;
     let vis = &info.vis;
     let field_names: Vec<_> = info.fields.iter().map(|f| &f.name).collect();
-    let field_types: Vec<_> = info.fields.iter().map(|f| &f.ty).collect();
 
     let field_type_assocs = generate_fields_trait_parts(&field_names);
     let (fieldset_phantom_types, fieldset_params, fieldset_assocs) =
         generate_fieldset_parts(&field_names);
     let merge_fields = generate_merge_fields(&field_names);
-    let remainder_fields = generate_remainder_fields(&field_names, &field_types);
+    let remainder_fields = generate_remainder_fields(&field_names);
     let type_of_module = generate_type_of_module(info);
     let with_modules = generate_with_modules(&field_names);
     let empty_constructor = generate_empty_constructor(info);
 
-    let remainder_params = if info.generics.params.is_empty() {
-        quote! { F1, F2 }
-    } else {
-        let (lifetimes, others): (Vec<_>, Vec<_>) = info
-            .generics
-            .params
-            .iter()
-            .partition(|p| matches!(p, syn::GenericParam::Lifetime(_)));
-        quote! { #(#lifetimes,)* F1, F2, #(#others),* }
-    };
-
     let remainder_type = quote! {
         /// Computes the field presence state after extracting `F2` from `F1`.
         ///
-        /// - Extracting to Present or Optional → remainder is Absent (value consumed)
+        /// - Extracting to a value-taking presence (Present, Optional, ...) → remainder is Absent
         /// - Extracting to Absent → remainder keeps F1's presence (nothing extracted)
-        #[allow(type_alias_bounds)]
-        pub type Remainder<#remainder_params> = FieldSet<
+        pub type Remainder<F1, F2> = FieldSet<
             #(#remainder_fields),*
         >;
     };
@@ -415,6 +548,16 @@ pub fn generate(info: &StructInfo, serde_helper: Option<TokenStream>) -> TokenSt
         >;
     };
 
+    let utility_types = generate_utility_types(&field_names);
+    let fields_message = format!("`{{Self}}` is not a field set of `{}`", info.name);
+    let fields_label = format!("expected a `{module_name}::FieldSet`");
+    let fields_note = format!(
+        "build field sets with `select!({module_name}: ...)` or the `{module_name}::with` aliases"
+    );
+    let relations = generate_relations(&field_names);
+    let field_requirements = generate_field_requirements(info);
+    let select_macro = select::generate(info);
+
     quote! {
         #vis mod #module_name {
             use super::*;
@@ -423,6 +566,12 @@ pub fn generate(info: &StructInfo, serde_helper: Option<TokenStream>) -> TokenSt
 
             #serde_helper
 
+            #sqlx_columns
+
+            #component_fields
+
+            #ts_interface
+
             mod sealed {
                 pub trait Sealed {}
             }
@@ -431,6 +580,11 @@ pub fn generate(info: &StructInfo, serde_helper: Option<TokenStream>) -> TokenSt
             ///
             /// Use this in trait bounds to require specific fields:
             /// `fn foo<F: Fields<name = Present, id = Present>>(...)`.
+            #[diagnostic::on_unimplemented(
+                message = #fields_message,
+                label = #fields_label,
+                note = #fields_note,
+            )]
             #[allow(non_camel_case_types)]
             pub trait Fields: sealed::Sealed {
                 #(#field_type_assocs)*
@@ -455,7 +609,8 @@ pub fn generate(info: &StructInfo, serde_helper: Option<TokenStream>) -> TokenSt
 
             /// Computes the result of merging two field sets (F2 takes precedence over F1).
             ///
-            /// Present in either → Present. Optional + Absent → Optional.
+            /// Present in either → Present. Optional + Absent → Optional. Patch in F2 → Patch
+            /// (missing keeps F1's value, null clears it).
             pub type Merge<F1, F2> = FieldSet<
                 #(#merge_fields),*
             >;
@@ -464,6 +619,14 @@ pub fn generate(info: &StructInfo, serde_helper: Option<TokenStream>) -> TokenSt
 
             #canonical_type
 
+            #utility_types
+
+            #relations
+
+            #field_requirements
+
+            #select_macro
+
             #type_of_module
 
             #with_modules
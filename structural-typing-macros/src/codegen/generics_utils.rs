@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{GenericParam, Generics, Ident};
+use syn::{GenericParam, Generics, Ident, TypeParamBound};
 
 /// Extracts just the identifiers from generic parameters for use in type arguments
 pub fn extract_type_param_idents(
@@ -70,22 +70,6 @@ pub fn type_args_with_f(
     }
 }
 
-/// Generates remainder type arguments with proper lifetime ordering
-/// Returns <lifetimes, F, F2, other_params> or <F, F2> if no user generics
-pub fn remainder_type_args(generics: &Generics, user_type_args: &[TokenStream]) -> TokenStream {
-    if generics.params.is_empty() {
-        quote! { <F, F2> }
-    } else {
-        let (lifetime_args, other_args): (Vec<_>, Vec<_>) = user_type_args
-            .iter()
-            .zip(generics.params.iter())
-            .partition(|(_, param)| matches!(param, GenericParam::Lifetime(_)));
-        let lifetime_tokens: Vec<_> = lifetime_args.into_iter().map(|(tok, _)| tok).collect();
-        let other_tokens: Vec<_> = other_args.into_iter().map(|(tok, _)| tok).collect();
-        quote! { <#(#lifetime_tokens,)* F, F2, #(#other_tokens),*> }
-    }
-}
-
 /// Returns user generic params that don't have default values (not trailing defaults)
 pub fn non_defaulted_params(generics: &Generics) -> Vec<GenericParam> {
     let params: Vec<_> = generics.params.iter().cloned().collect();
@@ -104,3 +88,36 @@ pub fn non_defaulted_params(generics: &Generics) -> Vec<GenericParam> {
 
     params.into_iter().take(first_default_idx).collect()
 }
+
+/// Generates type arguments for a borrowed view: the view lifetime first, then as `type_args_with_f`
+pub fn view_type_args(
+    generics: &Generics,
+    user_type_args: &[TokenStream],
+    view_lifetime: &syn::Lifetime,
+    f_value: TokenStream,
+) -> TokenStream {
+    let (lifetime_args, other_args): (Vec<_>, Vec<_>) = user_type_args
+        .iter()
+        .zip(generics.params.iter())
+        .partition(|(_, param)| matches!(param, GenericParam::Lifetime(_)));
+    let lifetime_tokens: Vec<_> = lifetime_args.into_iter().map(|(tok, _)| tok).collect();
+    let other_tokens: Vec<_> = other_args.into_iter().map(|(tok, _)| tok).collect();
+    quote! { <#view_lifetime, #(#lifetime_tokens,)* #f_value, #(#other_tokens),*> }
+}
+
+/// Schema module of a field-set parameter bound like `P: project::Fields`.
+pub fn fields_module(
+    bounds: &syn::punctuated::Punctuated<TypeParamBound, syn::Token![+]>,
+) -> Option<syn::Path> {
+    bounds.iter().find_map(|bound| {
+        let TypeParamBound::Trait(bound) = bound else {
+            return None;
+        };
+        let mut module = bound.path.clone();
+        let last = module.segments.pop()?;
+        (last.value().ident == "Fields" && !module.segments.is_empty()).then(|| {
+            module.segments.pop_punct();
+            module
+        })
+    })
+}
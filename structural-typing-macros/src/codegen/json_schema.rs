@@ -0,0 +1,157 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Attribute;
+
+use crate::codegen::generics_utils::{impl_generics_with_f, type_args_with_f};
+use crate::codegen::serde_deserialize::{
+    fills_default, input_name, is_flattened, output_name, params_in_type,
+};
+use crate::parsing::StructInfo;
+
+/// Generates `JsonSchema` for `#[derive(JsonSchema)]`, describing each field selection as its own
+/// schema. Properties take the serialized or the deserialized name of their field, following the
+/// generator's contract; schema names use the deserialized one.
+pub fn generate(info: &StructInfo) -> syn::Result<TokenStream> {
+    if !info.derives.iter().any(|d| d == "JsonSchema") {
+        return Ok(quote! {});
+    }
+    validate_schemars_attrs(info)?;
+
+    let struct_name = &info.name;
+    let struct_name_str = struct_name.to_string();
+    let module_name = &info.module_name;
+
+    let (mut impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
+    let type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
+
+    let mut names = Vec::new();
+    let mut ids = Vec::new();
+    let mut fields = Vec::new();
+    for field in &info.fields {
+        let field_name = &field.name;
+        let field_ty = &field.ty;
+        let input_name = input_name(info, field);
+        let presence = quote! { <F as #module_name::Fields>::#field_name };
+        let container = quote! {
+            <<#presence as ::structural_typing::presence::Presence>::Keep<::structural_typing::presence::Patch> as ::structural_typing::presence::Presence>::Output<#field_ty>
+        };
+        let accepts_missing = quote! {
+            <<#presence as ::structural_typing::presence::Presence>::AcceptsMissing as ::structural_typing::presence::Bool>::VALUE
+        };
+        let default = fills_default(info, field);
+        let generic = !params_in_type(info, field_ty).is_empty();
+        impl_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! { #container: ::structural_typing::schemars::FieldSchema });
+
+        let required = quote! { !#accepts_missing && !#default };
+        names.push(quote! {
+            <#container as ::structural_typing::schemars::FieldSchema>::name_field(&mut name, #input_name, #required, #generic);
+        });
+        ids.push(quote! {
+            <#container as ::structural_typing::schemars::FieldSchema>::id_field(&mut id, #input_name, #required, #generic);
+        });
+        if is_flattened(&field.attrs) {
+            fields.push(quote! {
+                <#container as ::structural_typing::schemars::FieldSchema>::add_flattened(generator, &mut object, !#accepts_missing);
+            });
+        } else {
+            let output_name = output_name(info, field);
+            let property = if input_name == output_name {
+                quote! { #input_name }
+            } else {
+                quote! {
+                    if generator.contract().is_serialize() { #output_name } else { #input_name }
+                }
+            };
+            let description = option_str(docs(&field.attrs));
+            fields.push(quote! {
+                <#container as ::structural_typing::schemars::FieldSchema>::add_field(
+                    generator,
+                    &mut object,
+                    #property,
+                    #required,
+                    #accepts_missing,
+                    #description,
+                );
+            });
+        }
+    }
+    let description = option_str(docs(&info.other_attrs));
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::schemars::JsonSchema for #struct_name #type_args #where_clause {
+            fn schema_name() -> ::std::borrow::Cow<'static, str> {
+                let mut name = ::structural_typing::schemars::SchemaName::new(#struct_name_str);
+                #(#names)*
+                name.finish()
+            }
+
+            fn schema_id() -> ::std::borrow::Cow<'static, str> {
+                let mut id = ::structural_typing::schemars::SchemaName::new(
+                    ::std::concat!(::std::module_path!(), "::", #struct_name_str),
+                );
+                #(#ids)*
+                id.finish()
+            }
+
+            fn json_schema(generator: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+                let mut object = ::structural_typing::schemars::ObjectSchema::new();
+                #(#fields)*
+                object.into_schema(#description)
+            }
+        }
+    })
+}
+
+/// Doc comment of an item, with the leading space of each line removed.
+pub fn docs(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<_> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(doc),
+                        ..
+                    }),
+                ..
+            }) => Some(doc.value()),
+            _ => None,
+        })
+        .collect();
+    let docs = lines
+        .iter()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let docs = docs.trim();
+    (!docs.is_empty()).then(|| docs.to_owned())
+}
+
+pub fn option_str(value: Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::core::option::Option::Some(#value) },
+        None => quote! { ::core::option::Option::None },
+    }
+}
+
+/// Rejects `#[schemars(...)]` attributes, whose options the generated schema does not read.
+fn validate_schemars_attrs(info: &StructInfo) -> syn::Result<()> {
+    let attrs = info
+        .other_attrs
+        .iter()
+        .chain(info.fields.iter().flat_map(|field| &field.attrs));
+    for attr in attrs {
+        if attr.path().is_ident("schemars") {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "structural typing does not support #[schemars] attributes; use #[serde] renames and doc comments instead",
+            ));
+        }
+    }
+    Ok(())
+}
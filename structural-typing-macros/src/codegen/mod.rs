@@ -1,12 +1,20 @@
 mod builders;
+mod compact;
 mod extract;
 mod fields_module;
 mod generics_utils;
+mod json_schema;
 mod merge;
 mod nested_setters;
+mod require;
+mod select;
 mod serde_deserialize;
+mod sqlx_from_row;
 mod struct_def;
+mod to_schema;
+mod ts;
 mod type_subst;
+mod views;
 
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -14,16 +22,30 @@ use syn::DeriveInput;
 
 use crate::parsing;
 
-pub fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
-    let info = parsing::parse_struct(input)?;
+pub fn generate(attr: TokenStream, input: DeriveInput) -> syn::Result<TokenStream> {
+    let options = parsing::parse_options(attr)?;
+    let info = parsing::parse_struct(input, options)?;
 
     let (serde_helper, serde_try_from) = serde_deserialize::generate(&info)?;
-    let fields_mod = fields_module::generate(&info, serde_helper);
+    let (sqlx_columns, from_row) = sqlx_from_row::generate(&info)?;
+    let (component_fields, to_schema) = to_schema::generate(&info)?;
+    let (ts_interface, ts_type) = ts::generate(&info)?;
+    let fields_mod = fields_module::generate(
+        &info,
+        serde_helper,
+        sqlx_columns,
+        component_fields,
+        ts_interface,
+    );
     let struct_def = struct_def::generate(&info);
     let builders = builders::generate(&info);
     let nested_setters = nested_setters::generate(&info);
     let merge = merge::generate(&info);
     let extract = extract::generate(&info);
+    let require = require::generate(&info);
+    let views = views::generate(&info);
+    let compact = compact::generate(&info)?;
+    let json_schema = json_schema::generate(&info)?;
 
     Ok(quote! {
         #fields_mod
@@ -39,5 +61,19 @@ pub fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
         #merge
 
         #extract
+
+        #require
+
+        #views
+
+        #compact
+
+        #from_row
+
+        #json_schema
+
+        #to_schema
+
+        #ts_type
     })
 }
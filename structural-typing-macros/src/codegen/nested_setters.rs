@@ -139,7 +139,7 @@ fn generate_nested_setter(
             #nested_field: V
         ) -> #struct_name #return_type_args
         where
-            F: #module_name::Fields<#field_name = ::structural_typing::presence::Present>
+            F: #module_name::field::#field_name<::structural_typing::presence::Present>
         {
             let (nested_only, rest) = self.extract::<::structural_typing::select!(#module_name: #field_name)>();
             rest.#field_name(nested_only.#field_name.#nested_field(#nested_field))
@@ -0,0 +1,67 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::codegen::generics_utils::{impl_generics_with_f, type_args_with_f};
+use crate::codegen::serde_deserialize::input_name;
+use crate::parsing::StructInfo;
+
+pub fn generate(info: &StructInfo) -> TokenStream {
+    let struct_name = &info.name;
+    let module_name = &info.module_name;
+
+    let (impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    let impl_type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
+    let required_type_args = type_args_with_f(
+        &info.generics,
+        &user_type_args,
+        quote! { #module_name::Merge<#module_name::Remainder<F, F2>, F2> },
+    );
+
+    let missing_checks = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let field_type = &field.ty;
+        let input_name = input_name(info, field);
+        quote! {
+            if !<<F2::#field_name as ::structural_typing::presence::Presence>::AcceptsMissing as ::structural_typing::presence::Bool>::VALUE
+                && ::structural_typing::access::Access::<#field_type>::get(&self.#field_name).is_none()
+            {
+                missing.push(#input_name);
+            }
+        }
+    });
+
+    let field_conversions = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let field_type = &field.ty;
+        quote! {
+            #field_name: match <F::#field_name as ::structural_typing::extract::TryExtract<F2::#field_name>>::try_extract::<#field_type>(self.#field_name) {
+                Ok((selected, rest)) => <F2::#field_name as ::structural_typing::presence::Presence>::or::<#field_type, <F2::#field_name as ::structural_typing::presence::Presence>::Remainder<F::#field_name>>(selected, rest),
+                Err(_) => unreachable!("missing fields are checked before conversion"),
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics #struct_name #impl_type_args #where_clause {
+            /// Converts the fields selected in `F2` to their selected presence, keeping every other field as is.
+            ///
+            /// Typically used to upgrade Optional fields to Present after deserializing.
+            ///
+            /// # Errors
+            ///
+            /// Returns the original value and the name of every selected field that holds no value.
+            pub fn require<F2: #module_name::Fields>(self) -> ::core::result::Result<#struct_name #required_type_args, ::structural_typing::require::RequireError<Self>> {
+                let mut missing = ::std::vec::Vec::new();
+                #(#missing_checks)*
+                if !missing.is_empty() {
+                    return Err(::structural_typing::require::RequireError::new(self, missing));
+                }
+
+                Ok(#struct_name {
+                    #(#field_conversions),*
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use heck::ToSnakeCase;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, format_ident, quote};
+use syn::{GenericArgument, GenericParam, Ident, Path, PathArguments, Type};
+
+use crate::parsing::StructInfo;
+
+/// Finds the nested schema of a field whose type takes one of the struct's type parameters,
+/// like `address: Address<A>`: returns the parameter and the nested schema module.
+///
+/// The module comes from `#[nested(module: ...)]` when present, and otherwise from the type path
+/// with its last segment in snake case (`Address<A>` → `address`).
+fn nested_schema(info: &StructInfo, ty: &Type) -> Option<(Ident, Path)> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let last = type_path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+
+    let param = args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(Type::Path(arg_path)) => {
+            let ident = arg_path.path.get_ident()?;
+            info.generics
+                .type_params()
+                .any(|param| param.ident == *ident)
+                .then(|| ident.clone())
+        }
+        _ => None,
+    })?;
+
+    let mut module = type_path.path.clone();
+    let last = module.segments.last_mut()?;
+    last.ident = Ident::new(&last.ident.to_string().to_snake_case(), last.ident.span());
+    last.arguments = PathArguments::None;
+    Some((param, module))
+}
+
+/// Whether `ty` mentions any of the struct's generic parameters.
+fn mentions_params(info: &StructInfo, ty: &Type) -> bool {
+    let names: Vec<String> = info
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Type(param) => param.ident.to_string(),
+            GenericParam::Lifetime(param) => param.lifetime.ident.to_string(),
+            GenericParam::Const(param) => param.ident.to_string(),
+        })
+        .collect();
+
+    fn visit(tokens: TokenStream, names: &[String]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) => names.contains(&ident.to_string()),
+            proc_macro2::TokenTree::Group(group) => visit(group.stream(), names),
+            _ => false,
+        })
+    }
+    visit(quote! { #ty }, &names)
+}
+
+/// Generates the schema module's `__select!` macro, which hands `select!` the field list and
+/// generic parameters, and aliases for the parameter defaults it may need to spell out.
+pub fn generate(info: &StructInfo) -> TokenStream {
+    let struct_name = &info.name;
+    let field_names: Vec<_> = info.fields.iter().map(|field| &field.name).collect();
+
+    let mut nested = Vec::new();
+    for field in &info.fields {
+        let Some((param, inferred_module)) = nested_schema(info, &field.ty) else {
+            continue;
+        };
+        if nested
+            .iter()
+            .any(|(existing, _, _): &(Ident, _, _)| *existing == param)
+        {
+            continue;
+        }
+        let module = field
+            .nested_fields
+            .as_ref()
+            .map_or(inferred_module, |nested| nested.module_path.clone());
+        nested.push((param, field.name.clone(), module));
+    }
+
+    let mut default_aliases = Vec::new();
+    let mut params = Vec::new();
+    let mut fields_inserted = false;
+    for param in &info.generics.params {
+        if !matches!(param, GenericParam::Lifetime(_)) && !fields_inserted {
+            params.push(quote! { Self });
+            fields_inserted = true;
+        }
+        params.push(match param {
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote! { #lifetime }
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote! { const #ident }
+            }
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                let nested = nested
+                    .iter()
+                    .find(|(nested_param, _, _)| nested_param == ident)
+                    .map(|(_, field, module)| {
+                        let module = macro_path(module);
+                        quote! { in #field: #module }
+                    });
+                let default = param
+                    .default
+                    .as_ref()
+                    .filter(|default| !mentions_params(info, default))
+                    .map(|default| {
+                        let alias = format_ident!("__default_{}", ident);
+                        default_aliases.push(quote! {
+                            #[doc(hidden)]
+                            #[allow(non_camel_case_types)]
+                            pub type #alias = #default;
+                        });
+                        quote! { = #alias }
+                    });
+                quote! { #ident #nested #default }
+            }
+        });
+    }
+    if !fields_inserted {
+        params.push(quote! { Self });
+    }
+
+    // The macro is defined inside the schema module, so without `export` its name never meets
+    // another schema's. `#[macro_export]` places it at the crate root, under the name the user
+    // chose to be unique there.
+    let (name, export, vis) = match &info.options.export {
+        Some(name) => (name.clone(), quote! { #[macro_export] }, quote! { pub }),
+        None => (
+            format_ident!("__structural_select"),
+            quote! {},
+            quote! { pub(crate) },
+        ),
+    };
+    quote! {
+        #(#default_aliases)*
+
+        #[doc(hidden)]
+        #export
+        macro_rules! #name {
+            ($($selection:tt)*) => {
+                ::structural_typing::__select_with_schema! {
+                    struct #struct_name;
+                    fields [#(#field_names),*];
+                    params [#(#params),*];
+                    $($selection)*
+                }
+            };
+        }
+
+        #[doc(hidden)]
+        #vis use #name as __select;
+    }
+}
+
+/// `path` as written inside the generated macro: a leading `crate` becomes `$crate`, so that it
+/// still names this crate when `select!` is called from another one.
+fn macro_path(path: &Path) -> TokenStream {
+    let mut segments = path.segments.iter();
+    match segments.next() {
+        Some(first) if path.leading_colon.is_none() && first.ident == "crate" => {
+            quote! { $crate #(:: #segments)* }
+        }
+        _ => path.to_token_stream(),
+    }
+}
@@ -1,14 +1,28 @@
+use heck::{
+    ToKebabCase, ToLowerCamelCase, ToShoutyKebabCase, ToShoutySnakeCase, ToSnakeCase,
+    ToUpperCamelCase,
+};
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{ToTokens, format_ident, quote};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
 use syn::{Attribute, Ident};
 
-use crate::codegen::generics_utils::{impl_generics_with_f, type_args_with_f};
-use crate::parsing::StructInfo;
+use crate::codegen::generics_utils::{
+    extract_type_param_idents, impl_generics_with_f, type_args_with_f,
+};
+use crate::parsing::{FieldInfo, SerializeNone, StructInfo};
 
 pub fn generate(info: &StructInfo) -> syn::Result<(Option<TokenStream>, Option<TokenStream>)> {
     let has_deserialize = info.derives.iter().any(|d| d == "Deserialize");
 
     if !has_deserialize {
+        if let Some(option) = &info.options.deny_absent_input {
+            return Err(syn::Error::new_spanned(
+                option,
+                "`deny_absent_input` requires #[derive(Deserialize)]",
+            ));
+        }
         return Ok((None, None));
     }
 
@@ -31,10 +45,10 @@ fn validate_serde_attributes(info: &StructInfo) -> syn::Result<()> {
         let _ = attr_clone.parse_nested_meta(|meta| {
             let path = meta.path.get_ident().map(|i| i.to_string());
             match path.as_deref() {
-                Some("default") => {
+                Some("default") if meta.input.peek(syn::Token![=]) => {
                     has_error = Some(syn::Error::new_spanned(
                         attr,
-                        "structural typing does not support #[serde(default)] on containers",
+                        "structural typing only supports a bare #[serde(default)] on containers",
                     ));
                 }
                 Some("transparent") => {
@@ -64,24 +78,12 @@ fn validate_serde_attributes(info: &StructInfo) -> syn::Result<()> {
             let _ = attr_clone.parse_nested_meta(|meta| {
                 let path = meta.path.get_ident().map(|i| i.to_string());
                 match path.as_deref() {
-                    Some("default") => {
-                        has_error = Some(syn::Error::new_spanned(
-                            attr,
-                            "structural typing does not support #[serde(default)] on fields",
-                        ));
-                    }
                     Some("skip") | Some("skip_deserializing") => {
                         has_error = Some(syn::Error::new_spanned(
                             attr,
                             "structural typing does not support #[serde(skip)] or #[serde(skip_deserializing)]",
                         ));
                     }
-                    Some("flatten") => {
-                        has_error = Some(syn::Error::new_spanned(
-                            attr,
-                            "structural typing does not support #[serde(flatten)]",
-                        ));
-                    }
                     _ => {}
                 }
                 Ok(())
@@ -91,6 +93,15 @@ fn validate_serde_attributes(info: &StructInfo) -> syn::Result<()> {
                 return Err(err);
             }
         }
+        flatten_helper(field)?;
+        if let Some(option) = &field.options.deserialize_option {
+            if custom_deserializer(field).is_none() {
+                return Err(syn::Error::new_spanned(
+                    option,
+                    "`deserialize_option` requires #[serde(deserialize_with = \"...\")] or #[serde(with = \"...\")]",
+                ));
+            }
+        }
     }
 
     Ok(())
@@ -98,40 +109,138 @@ fn validate_serde_attributes(info: &StructInfo) -> syn::Result<()> {
 
 fn generate_helper_struct(info: &StructInfo) -> TokenStream {
     let helper_name = helper_struct_name(&info.name);
+    let (generics, user_type_args) = impl_generics_with_f(&info.generics, &info.module_name);
+    let type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
 
-    let params = &info.generics.params;
-    let where_clause = &info.generics.where_clause;
+    let params = &generics.params;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
 
-    let fields = info.fields.iter().map(|field| {
-        let name = &field.name;
-        let ty = &field.ty;
-        let preserved_attrs = filter_deserialize_attrs(&field.attrs);
+    let mut adapters = Vec::new();
+    let fields: Vec<_> = info
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let helper_ty = helper_field_type(info, field);
+            let mut preserved_attrs = filter_deserialize_attrs(&field.attrs);
+            // A flattened helper defaults each of its own fields.
+            let default = (!is_flattened(&field.attrs)).then(|| quote! { #[serde(default)] });
+
+            // Serde only borrows `&str` and `&[u8]` on its own; other fields need the attribute.
+            let borrowed = borrowed_lifetimes(info, field);
+            let borrow = (!borrowed.is_empty() && !has_borrow_attr(field))
+                .then(|| quote! { #[serde(borrow)] });
+
+            // A `T`-producing function goes through an adapter filling in the helper's container,
+            // and a marker standing in for the function, which is generic over the deserializer.
+            let adapter = field_deserializer(field)
+                .map(|with| {
+                    let adapter = format_ident!("__deserialize_with_{}", name.unraw());
+                    let marker = format_ident!("__with_{}", name.unraw());
+                    let ty = &field.ty;
+                    let params = params_in_type(info, ty);
+                    let param_args = params.iter().map(|param| match param {
+                        syn::GenericParam::Type(param) => param.ident.to_token_stream(),
+                        syn::GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+                        syn::GenericParam::Const(param) => param.ident.to_token_stream(),
+                    });
+                    let marker_ty = quote! { #marker<#(#param_args),*> };
+                    adapters.push(quote! {
+                        #[doc(hidden)]
+                        #[allow(non_camel_case_types)]
+                        pub struct #marker<#(#params),*>(::core::marker::PhantomData<fn() -> #ty>);
+
+                        impl<'de, #(#params),*> ::structural_typing::serde::DeserializeFn<'de, #ty> for #marker_ty
+                        where
+                            #('de: #borrowed,)*
+                        {
+                            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> ::core::result::Result<#ty, D::Error> {
+                                #with(deserializer)
+                            }
+                        }
+
+                        #[doc(hidden)]
+                        fn #adapter<'de, #(#params,)* D, C>(deserializer: D) -> ::core::result::Result<C, D::Error>
+                        where
+                            #('de: #borrowed,)*
+                            D: ::serde::Deserializer<'de>,
+                            C: ::structural_typing::serde::DeserializeWith<#ty>,
+                        {
+                            C::deserialize_with::<D, #marker_ty>(deserializer)
+                        }
+                    });
+                    preserved_attrs = preserved_attrs
+                        .iter()
+                        .filter_map(|attr| strip_serde_keys(attr, &["deserialize_with", "with"]))
+                        .collect();
+                    let adapter = adapter.to_string();
+                    quote! { #[serde(deserialize_with = #adapter)] }
+                });
+
+            quote! {
+                #(#preserved_attrs)*
+                #default
+                #borrow
+                #adapter
+                pub #name: #helper_ty
+            }
+        })
+        .collect();
 
-        quote! {
-            #(#preserved_attrs)*
-            #[serde(default)]
-            pub #name: Option<#ty>
+    let given = info.fields.iter().map(|field| {
+        let name = &field.name;
+        let input_name = input_name(info, field);
+        if is_flattened(&field.attrs) {
+            quote! { self.#name.__given_fields(given); }
+        } else {
+            let is_given = if deserializes_option(field) {
+                quote! { self.#name.is_some() }
+            } else {
+                quote! { self.#name.is_given() }
+            };
+            quote! {
+                if #is_given {
+                    given.push(#input_name);
+                }
+            }
         }
     });
 
     let preserved_struct_attrs = filter_container_serde_attrs(&info.other_attrs);
 
-    let field_types: Vec<_> = info
+    let bounds: Vec<_> = info
         .fields
         .iter()
         .map(|field| {
-            let ty = &field.ty;
-            quote! { Option<#ty> }
+            let helper_ty = helper_field_type(info, field);
+            if field_deserializer(field).is_some() {
+                let ty = &field.ty;
+                quote! { #helper_ty: ::structural_typing::serde::DeserializeWith<#ty> }
+            } else {
+                helper_ty
+            }
         })
         .collect();
 
     quote! {
-        #[::derive_where::derive_where(Deserialize; #(#field_types),*)]
+        #(#adapters)*
+
+        #[::derive_where::derive_where(Deserialize; #(#bounds),*)]
         #[doc(hidden)]
         #(#preserved_struct_attrs)*
         pub(super) struct #helper_name<#params> #where_clause {
+            // Fields that are all flattened or custom-deserialized leave `F` otherwise unused.
+            #[serde(skip)]
+            pub __fields: ::core::marker::PhantomData<fn() -> F>,
             #(#fields),*
         }
+
+        impl #impl_generics #helper_name #type_args #where_clause {
+            /// Pushes the input name of every field given in the input, including flattened ones.
+            pub(super) fn __given_fields(&self, given: &mut ::std::vec::Vec<&'static str>) {
+                #(#given)*
+            }
+        }
     }
 }
 
@@ -140,74 +249,624 @@ fn generate_try_from_impl(info: &StructInfo) -> syn::Result<TokenStream> {
     let module_name = &info.module_name;
     let helper_name = helper_struct_name(&info.name);
 
-    let (impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
-    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
-    let (_, user_ty_generics, _) = info.generics.split_for_impl();
+    let (mut impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
     let impl_type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
+    let container_default = has_container_default(&info.other_attrs);
+    let deny_absent_input = info.options.deny_absent_input.is_some();
 
-    let field_conversions = info.fields.iter().map(|field| {
-        let field_name = &field.name;
-        let field_name_str = field_name.to_string();
-
-        quote! {
-            #field_name: value.#field_name
-                .try_extract()
-                .map_err(|_| MissingFieldError::new(#field_name_str))?
-                .0
-        }
-    });
-
-    let where_bounds = info.fields.iter().map(|field| {
+    let mut field_conversions = Vec::new();
+    let mut field_values = Vec::new();
+    for field in &info.fields {
         let field_name = &field.name;
         let field_ty = &field.ty;
-        quote! {
-            Option<#field_ty>: ::structural_typing::extract::TryExtract<<<F as #module_name::Fields>::#field_name as ::structural_typing::presence::Presence>::Output<#field_ty>, #field_ty>
+        let input_name = input_name(info, field);
+        let converted = format_ident!("{}_converted", field_name.unraw());
+        let helper_presence = helper_field_presence(info, field);
+        let presence = quote! { <F as #module_name::Fields>::#field_name };
+        // `Keep<Present>` stays Absent only for Absent fields.
+        let holds_field = quote! {
+            <<<#presence as ::structural_typing::presence::Presence>::Keep<::structural_typing::presence::Present> as ::structural_typing::presence::Presence>::AlwaysPresent as ::structural_typing::presence::Bool>::VALUE
+        };
+
+        if let Some(helper) = flatten_helper(field)? {
+            impl_generics.make_where_clause().predicates.push(syn::parse_quote! {
+                #field_ty: ::core::convert::TryFrom<#helper, Error = ::structural_typing::serde::FieldsError>
+            });
+            let unexpected = deny_absent_input.then(|| {
+                quote! {
+                    if !#holds_field {
+                        unexpected.extend(given);
+                    }
+                }
+            });
+            // The nested struct reports its own fields, under the flat names they have in the
+            // input; keys are only required when something is given or the field needs a value.
+            field_conversions.push(quote! {
+                let #converted = {
+                    let nested = value.#field_name;
+                    let mut given = ::std::vec::Vec::new();
+                    nested.__given_fields(&mut given);
+                    let needs_value = !<<#presence as ::structural_typing::presence::Presence>::AcceptsMissing as ::structural_typing::presence::Bool>::VALUE;
+                    let nested = if (!given.is_empty() && #holds_field) || needs_value {
+                        match <#field_ty as ::core::convert::TryFrom<#helper>>::try_from(nested) {
+                            Ok(nested) => ::structural_typing::presence::PatchValue::Value(nested),
+                            Err(err) => {
+                                missing.extend_from_slice(err.missing());
+                                unexpected.extend_from_slice(err.unexpected());
+                                ::structural_typing::presence::PatchValue::Missing
+                            }
+                        }
+                    } else {
+                        #unexpected
+                        ::structural_typing::presence::PatchValue::Missing
+                    };
+                    <::structural_typing::presence::Patch as TryExtract<#presence>>::try_extract::<#field_ty>(nested)
+                        .ok()
+                        .map(|(converted, _)| converted)
+                };
+            });
+            field_values.push(quote! {
+                #field_name: match #converted {
+                    Some(converted) => converted,
+                    None => unreachable!("missing fields are reported before construction"),
+                }
+            });
+            continue;
         }
-    });
+
+        let parts = if deserializes_option(field) {
+            quote! {
+                let field = value.#field_name;
+                let given = field.is_some();
+            }
+        } else {
+            quote! {
+                let (field, given) = value.#field_name.into_parts::<#field_ty>();
+            }
+        };
+        let unexpected = deny_absent_input.then(|| {
+            quote! {
+                if given && !#holds_field {
+                    unexpected.push(#input_name);
+                }
+            }
+        });
+
+        // Defaults only fill in a missing key when the selected presence needs a value, so
+        // `Default` is only required of fields selected that way.
+        let accepts_missing =
+            quote! { <#presence as ::structural_typing::presence::Presence>::AcceptsMissing };
+        let filled = match field_default(field)?
+            .or(container_default.then_some(FieldDefault::Trait))
+        {
+            Some(default) => {
+                let default = match default {
+                    FieldDefault::Trait => {
+                        impl_generics.make_where_clause().predicates.push(syn::parse_quote! {
+                            #accepts_missing: ::structural_typing::serde::MissingDefault<#field_ty>
+                        });
+                        quote! {
+                            <#accepts_missing as ::structural_typing::serde::MissingDefault<#field_ty>>::missing_default()
+                        }
+                    }
+                    FieldDefault::Path(path) => quote! {
+                        if <#accepts_missing as ::structural_typing::presence::Bool>::VALUE {
+                            ::core::option::Option::None
+                        } else {
+                            ::core::option::Option::Some(#path())
+                        }
+                    },
+                };
+                quote! {
+                    let field = if given {
+                        field
+                    } else {
+                        match #default {
+                            ::core::option::Option::Some(default) => {
+                                ::structural_typing::access::Access::from_value(default)
+                            }
+                            ::core::option::Option::None => field,
+                        }
+                    };
+                }
+            }
+            None => quote! {},
+        };
+
+        field_conversions.push(quote! {
+            let #converted = {
+                #parts
+                #unexpected
+                #filled
+                match <#helper_presence as TryExtract<#presence>>::try_extract::<#field_ty>(field) {
+                    Ok((converted, _)) => Some(converted),
+                    Err(_) => {
+                        missing.push(#input_name);
+                        None
+                    }
+                }
+            };
+        });
+        field_values.push(quote! {
+            #field_name: match #converted {
+                Some(converted) => converted,
+                None => unreachable!("missing fields are reported before construction"),
+            }
+        });
+    }
+    let mut fields_generics = impl_generics.clone();
+    fields_generics
+        .params
+        .insert(0, syn::parse_quote! { '__de });
+    fields_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! {
+            #module_name::#helper_name #impl_type_args: ::serde::Deserialize<'__de>
+        });
+    let (fields_impl_generics, _, fields_where_clause) = fields_generics.split_for_impl();
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
 
     Ok(quote! {
-        impl #impl_generics ::core::convert::TryFrom<#module_name::#helper_name #user_ty_generics> for #name #impl_type_args
+        impl #impl_generics ::core::convert::TryFrom<#module_name::#helper_name #impl_type_args> for #name #impl_type_args
         #where_clause
-        where
-            #(#where_bounds),*
         {
-            type Error = ::structural_typing::serde::MissingFieldError;
+            type Error = ::structural_typing::serde::FieldsError;
 
-            fn try_from(value: #module_name::#helper_name #user_ty_generics) -> ::core::result::Result<Self, Self::Error> {
+            fn try_from(value: #module_name::#helper_name #impl_type_args) -> ::core::result::Result<Self, Self::Error> {
                 use ::structural_typing::extract::TryExtract;
-                use ::structural_typing::serde::MissingFieldError;
+
+                let mut missing = ::std::vec::Vec::new();
+                #[allow(unused_mut)]
+                let mut unexpected = ::std::vec::Vec::new();
+                #(#field_conversions)*
+                if !missing.is_empty() || !unexpected.is_empty() {
+                    return Err(::structural_typing::serde::FieldsError::new(missing, unexpected));
+                }
 
                 Ok(Self {
-                    #(#field_conversions),*
+                    #(#field_values),*
                 })
             }
         }
+
+        impl #fields_impl_generics ::structural_typing::serde::DeserializeFields<'__de> for #name #impl_type_args
+        #fields_where_clause
+        {
+            fn deserialize_fields<__D: ::serde::Deserializer<'__de>>(
+                deserializer: __D,
+            ) -> ::core::result::Result<::core::result::Result<Self, ::structural_typing::serde::FieldsError>, __D::Error> {
+                let value = <#module_name::#helper_name #impl_type_args as ::serde::Deserialize<'__de>>::deserialize(deserializer)?;
+                Ok(<Self as ::core::convert::TryFrom<_>>::try_from(value))
+            }
+        }
     })
 }
 
-fn helper_struct_name(struct_name: &Ident) -> Ident {
-    Ident::new(&format!("__{}Deserialize", struct_name), struct_name.span())
+/// How a field fills in a missing key: `#[serde(default)]` or `#[serde(default = "path")]`.
+enum FieldDefault {
+    Trait,
+    Path(syn::ExprPath),
 }
 
-pub fn helper_path(module_name: &Ident, struct_name: &Ident, generics: &syn::Generics) -> String {
-    let helper_name = helper_struct_name(struct_name);
+fn field_default(field: &FieldInfo) -> syn::Result<Option<FieldDefault>> {
+    let mut default = None;
+    for attr in &field.attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        for meta in serde_metas(attr)? {
+            match meta {
+                syn::Meta::Path(path) if path.is_ident("default") => {
+                    default = Some(FieldDefault::Trait);
+                }
+                syn::Meta::NameValue(nv) if nv.path.is_ident("default") => {
+                    let syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    }) = &nv.value
+                    else {
+                        return Err(syn::Error::new_spanned(
+                            &nv.value,
+                            "expected a string literal naming a default function",
+                        ));
+                    };
+                    default = Some(FieldDefault::Path(lit.parse()?));
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(default)
+}
+
+/// Whether a missing key is filled from a default, given by the field or the struct.
+pub fn fills_default(info: &StructInfo, field: &FieldInfo) -> bool {
+    matches!(field_default(field), Ok(Some(_))) || has_container_default(&info.other_attrs)
+}
+
+fn has_container_default(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| serde_metas(attr).ok())
+        .flatten()
+        .any(|meta| matches!(&meta, syn::Meta::Path(path) if path.is_ident("default")))
+}
+
+fn serde_metas(attr: &Attribute) -> syn::Result<Punctuated<syn::Meta, syn::Token![,]>> {
+    attr.parse_args_with(Punctuated::parse_terminated)
+}
+
+/// Drops the given keys from a serde attribute, or the whole attribute if nothing is left.
+fn strip_serde_keys(attr: &Attribute, keys: &[&str]) -> Option<Attribute> {
+    let Ok(metas) = serde_metas(attr) else {
+        return Some(attr.clone());
+    };
+    let kept: Vec<_> = metas
+        .into_iter()
+        .filter(|meta| !keys.iter().any(|key| meta.path().is_ident(key)))
+        .collect();
+    if kept.is_empty() {
+        None
+    } else {
+        Some(syn::parse_quote! { #[serde(#(#kept),*)] })
+    }
+}
+
+/// Drops `default` from a serde attribute, since the helper struct defaults every field itself.
+fn strip_serde_default(attr: &Attribute) -> Option<Attribute> {
+    strip_serde_keys(attr, &["default"])
+}
+
+/// Helper fields are Patch so that missing keys and explicit `null` stay distinguishable, or
+/// Absent when the selection makes them Absent, so that their type need not be `Deserialize`.
+/// Fields marked `deserialize_option` are Optional, matching the `Option<T>` their function produces.
+fn helper_field_presence(info: &StructInfo, field: &FieldInfo) -> TokenStream {
+    if deserializes_option(field) {
+        quote! { ::structural_typing::presence::Optional }
+    } else {
+        let module_name = &info.module_name;
+        let field_name = &field.name;
+        quote! {
+            <<F as #module_name::Fields>::#field_name as ::structural_typing::presence::Presence>::Keep<::structural_typing::presence::Patch>
+        }
+    }
+}
 
-    if generics.params.is_empty() {
-        format!("{}::{}", module_name, helper_name)
+fn helper_field_type(info: &StructInfo, field: &FieldInfo) -> TokenStream {
+    if let Ok(Some(helper)) = flatten_helper(field) {
+        return helper;
+    }
+    let ty = &field.ty;
+    let presence = helper_field_presence(info, field);
+    let container = quote! { <#presence as ::structural_typing::presence::Presence>::Output<#ty> };
+    if deserializes_option(field) {
+        container
     } else {
-        let params: Vec<String> = generics
-            .params
+        quote! { ::structural_typing::serde::Given<#container> }
+    }
+}
+
+pub fn is_flattened(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| serde_metas(attr).ok())
+        .flatten()
+        .any(|meta| matches!(&meta, syn::Meta::Path(path) if path.is_ident("flatten")))
+}
+
+/// Deserialize helper of a `#[serde(flatten)]` field's structural type: `Address<A>` becomes
+/// `address::__AddressDeserialize<A>`, with the same generic arguments.
+fn flatten_helper(field: &FieldInfo) -> syn::Result<Option<TokenStream>> {
+    if !is_flattened(&field.attrs) {
+        return Ok(None);
+    }
+    let error = || {
+        syn::Error::new_spanned(
+            &field.ty,
+            "#[serde(flatten)] is only supported on fields whose type is another #[structural] struct, like `Address<A>`",
+        )
+    };
+    let syn::Type::Path(type_path) = &field.ty else {
+        return Err(error());
+    };
+    if type_path.qself.is_some() {
+        return Err(error());
+    }
+    let last = type_path.path.segments.last().ok_or_else(error)?;
+    let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+        return Err(error());
+    };
+
+    // The field set argument comes first after any lifetimes.
+    if !args
+        .args
+        .iter()
+        .any(|arg| !matches!(arg, syn::GenericArgument::Lifetime(_)))
+    {
+        return Err(error());
+    }
+
+    let module = match &field.nested_fields {
+        Some(nested) => nested.module_path.clone(),
+        None => {
+            let mut module = type_path.path.clone();
+            let last = module.segments.last_mut().ok_or_else(error)?;
+            last.ident = Ident::new(&last.ident.to_string().to_snake_case(), last.ident.span());
+            last.arguments = syn::PathArguments::None;
+            module
+        }
+    };
+    let helper_name = helper_struct_name(&last.ident);
+    Ok(Some(quote! { #module::#helper_name #args }))
+}
+
+/// The field's `deserialize_with` function, or `deserialize` in its `with` module.
+fn custom_deserializer(field: &FieldInfo) -> Option<syn::ExprPath> {
+    let mut found = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+    {
+        for meta in serde_metas(attr).into_iter().flatten() {
+            let syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    }),
+                ..
+            }) = &meta
+            else {
+                continue;
+            };
+            if path.is_ident("deserialize_with") {
+                found = lit.parse().ok().or(found);
+            } else if path.is_ident("with") {
+                found = lit
+                    .parse::<syn::Path>()
+                    .ok()
+                    .map(|module| syn::parse_quote! { #module::deserialize })
+                    .or(found);
+            }
+        }
+    }
+    found
+}
+
+/// The function the helper deserializes a `T`-producing field with: its `deserialize_with` or
+/// `with`, or one that borrows from the input for `Cow<str>` and `Cow<[u8]>` fields marked
+/// `#[serde(borrow)]`, as serde's derive does.
+fn field_deserializer(field: &FieldInfo) -> Option<syn::ExprPath> {
+    if deserializes_option(field) {
+        return None;
+    }
+    custom_deserializer(field).or_else(|| {
+        if !has_borrow_attr(field) {
+            return None;
+        }
+        let syn::Type::Path(type_path) = &field.ty else {
+            return None;
+        };
+        let last = type_path.path.segments.last()?;
+        let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+            return None;
+        };
+        if last.ident != "Cow" || args.args.len() != 2 {
+            return None;
+        }
+        match args.args.last()? {
+            syn::GenericArgument::Type(ty) if is_str(ty) => {
+                Some(syn::parse_quote! { ::structural_typing::serde::borrow_cow_str })
+            }
+            syn::GenericArgument::Type(ty) if is_bytes(ty) => {
+                Some(syn::parse_quote! { ::structural_typing::serde::borrow_cow_bytes })
+            }
+            _ => None,
+        }
+    })
+}
+
+fn is_str(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(path) if path.qself.is_none() && path.path.is_ident("str"))
+}
+
+fn is_bytes(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Slice(slice) if matches!(&*slice.elem, syn::Type::Path(path) if path.path.is_ident("u8")))
+}
+
+fn has_borrow_attr(field: &FieldInfo) -> bool {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| serde_metas(attr).ok())
+        .flatten()
+        .any(|meta| meta.path().is_ident("borrow"))
+}
+
+/// Lifetimes the field borrows from the input: those named by `#[serde(borrow = "'a + 'b")]`,
+/// every lifetime of the field type with a bare `#[serde(borrow)]`, and the lifetime of a
+/// `&str` or `&[u8]` field, which serde borrows implicitly.
+fn borrowed_lifetimes(info: &StructInfo, field: &FieldInfo) -> Vec<syn::Lifetime> {
+    let mut borrowed = Vec::new();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+    {
+        for meta in serde_metas(attr).into_iter().flatten() {
+            if !meta.path().is_ident("borrow") {
+                continue;
+            }
+            match side_value(&meta, "deserialize") {
+                Some(lifetimes) => borrowed.extend(
+                    syn::parse::Parser::parse_str(
+                        Punctuated::<syn::Lifetime, syn::Token![+]>::parse_separated_nonempty,
+                        &lifetimes,
+                    )
+                    .into_iter()
+                    .flatten(),
+                ),
+                None => borrowed.extend(params_in_type(info, &field.ty).into_iter().filter_map(
+                    |param| match param {
+                        syn::GenericParam::Lifetime(param) => Some(param.lifetime),
+                        _ => None,
+                    },
+                )),
+            }
+        }
+    }
+    if borrowed.is_empty() {
+        if let syn::Type::Reference(reference) = &field.ty {
+            if let Some(lifetime) = &reference.lifetime {
+                if reference.mutability.is_none()
+                    && (is_str(&reference.elem) || is_bytes(&reference.elem))
+                {
+                    borrowed.push(lifetime.clone());
+                }
+            }
+        }
+    }
+    borrowed
+}
+
+/// The struct's generic parameters that `ty` mentions, without defaults, so that an adapter
+/// function can name the field type and still have every parameter inferred.
+pub fn params_in_type(info: &StructInfo, ty: &syn::Type) -> Vec<syn::GenericParam> {
+    fn idents(tokens: TokenStream, found: &mut Vec<String>) {
+        for token in tokens {
+            match token {
+                proc_macro2::TokenTree::Ident(ident) => found.push(ident.to_string()),
+                proc_macro2::TokenTree::Group(group) => idents(group.stream(), found),
+                _ => {}
+            }
+        }
+    }
+    let mut found = Vec::new();
+    idents(quote! { #ty }, &mut found);
+
+    info.generics
+        .params
+        .iter()
+        .filter(|param| {
+            let name = match param {
+                syn::GenericParam::Type(param) => param.ident.to_string(),
+                syn::GenericParam::Lifetime(param) => param.lifetime.ident.to_string(),
+                syn::GenericParam::Const(param) => param.ident.to_string(),
+            };
+            found.contains(&name)
+        })
+        .cloned()
+        .map(|mut param| {
+            match &mut param {
+                syn::GenericParam::Type(param) => param.default = None,
+                syn::GenericParam::Const(param) => param.default = None,
+                syn::GenericParam::Lifetime(_) => {}
+            }
+            param
+        })
+        .collect()
+}
+
+/// Whether the field's custom deserializer produces `Option<T>`, as marked by
+/// `#[structural(deserialize_option)]`.
+fn deserializes_option(field: &FieldInfo) -> bool {
+    field.options.deserialize_option.is_some()
+}
+
+/// Name serde expects for a field in the input, honoring `rename` and container `rename_all`.
+pub fn input_name(info: &StructInfo, field: &FieldInfo) -> String {
+    serde_name(info, field, "deserialize")
+}
+
+/// Name serde writes for a field in the output, honoring `rename` and container `rename_all`.
+pub fn output_name(info: &StructInfo, field: &FieldInfo) -> String {
+    serde_name(info, field, "serialize")
+}
+
+/// Name of a field on one side of serde, `"serialize"` or `"deserialize"`.
+fn serde_name(info: &StructInfo, field: &FieldInfo, side: &str) -> String {
+    let field_name = field.name.to_string();
+    let field_name = field_name.strip_prefix("r#").unwrap_or(&field_name);
+
+    let mut renamed = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+    {
+        for meta in serde_metas(attr).into_iter().flatten() {
+            if meta.path().is_ident("rename") {
+                renamed = side_value(&meta, side).or(renamed);
+            }
+        }
+    }
+    if let Some(renamed) = renamed {
+        return renamed;
+    }
+
+    let mut rename_all = None;
+    for attr in info
+        .other_attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+    {
+        for meta in serde_metas(attr).into_iter().flatten() {
+            if meta.path().is_ident("rename_all") {
+                rename_all = side_value(&meta, side).or(rename_all);
+            }
+        }
+    }
+    match rename_all.as_deref() {
+        Some("lowercase") => field_name.to_lowercase(),
+        Some("UPPERCASE") => field_name.to_uppercase(),
+        Some("PascalCase") => field_name.to_upper_camel_case(),
+        Some("camelCase") => field_name.to_lower_camel_case(),
+        Some("SCREAMING_SNAKE_CASE") => field_name.to_shouty_snake_case(),
+        Some("kebab-case") => field_name.to_kebab_case(),
+        Some("SCREAMING-KEBAB-CASE") => field_name.to_shouty_kebab_case(),
+        _ => field_name.to_owned(),
+    }
+}
+
+/// Reads `key = "value"` or the `side` half of `key(serialize = "..", deserialize = "..")`.
+fn side_value(meta: &syn::Meta, side: &str) -> Option<String> {
+    match meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit),
+                    ..
+                }),
+            ..
+        }) => Some(lit.value()),
+        syn::Meta::List(list) => list
+            .parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)
+            .ok()?
             .iter()
-            .map(|param| match param {
-                syn::GenericParam::Type(type_param) => type_param.ident.to_string(),
-                syn::GenericParam::Lifetime(lifetime_param) => lifetime_param.lifetime.to_string(),
-                syn::GenericParam::Const(const_param) => const_param.ident.to_string(),
-            })
-            .collect();
-        format!("{}::{}<{}>", module_name, helper_name, params.join(", "))
+            .find(|meta| meta.path().is_ident(side))
+            .and_then(|meta| side_value(meta, side)),
+        syn::Meta::NameValue(_) | syn::Meta::Path(_) => None,
     }
 }
 
+fn helper_struct_name(struct_name: &Ident) -> Ident {
+    Ident::new(&format!("__{}Deserialize", struct_name), struct_name.span())
+}
+
+/// Helper type for `#[serde(try_from = "...")]`, taking the same arguments as the struct.
+pub fn helper_path(module_name: &Ident, struct_name: &Ident, generics: &syn::Generics) -> String {
+    let helper_name = helper_struct_name(struct_name);
+    let user_type_args = extract_type_param_idents(&generics.params);
+    let type_args = type_args_with_f(generics, &user_type_args, quote! { F });
+    format!("{}::{}{}", module_name, helper_name, type_args)
+}
+
 fn filter_deserialize_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
     attrs
         .iter()
@@ -229,7 +888,7 @@ fn filter_deserialize_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
 
             should_keep
         })
-        .cloned()
+        .filter_map(strip_serde_default)
         .collect()
 }
 
@@ -237,10 +896,25 @@ fn filter_container_serde_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
     attrs
         .iter()
         .filter(|attr| attr.path().is_ident("serde"))
-        .cloned()
+        .filter_map(strip_serde_default)
         .collect()
 }
 
+/// `skip_serializing_if` for a field: Absent fields are always omitted, and Optional `None` too
+/// with `serialize_none = "omit"` on the field or, failing that, the struct.
+pub fn skip_serializing_attr(info: &StructInfo, field: &FieldInfo) -> TokenStream {
+    let serialize_none = field
+        .options
+        .serialize_none
+        .or(info.options.serialize_none)
+        .unwrap_or(SerializeNone::Null);
+    let skip = match serialize_none {
+        SerializeNone::Omit => "::structural_typing::access::is_absent_or_none",
+        SerializeNone::Null => "::structural_typing::access::is_absent",
+    };
+    quote! { #[serde(skip_serializing_if = #skip)] }
+}
+
 pub fn filter_serialize_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
     attrs
         .iter()
@@ -0,0 +1,282 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{Attribute, LitStr};
+
+use crate::codegen::generics_utils::{impl_generics_with_f, type_args_with_f};
+use crate::codegen::type_subst::is_option;
+use crate::parsing::{FieldInfo, StructInfo};
+
+/// How a field is read from a row.
+enum Column {
+    /// A single column, named after the field unless `#[sqlx(rename = "...")]` is given.
+    Single(String),
+    /// A nested structural struct read from the same row, its columns prefixed with
+    /// `#[sqlx(prefix = "...")]` or unprefixed with `#[sqlx(flatten)]`.
+    Nested(String),
+}
+
+/// Generates `FromPrefixedRow`, `FromRow`, `SelectColumns` and `WriteColumns` for
+/// `#[derive(FromRow)]`, the `insert_query` and `update_query` methods, and the schema module's
+/// `columns` and `select_list` functions.
+pub fn generate(info: &StructInfo) -> syn::Result<(Option<TokenStream>, TokenStream)> {
+    if !info.derives.iter().any(|d| d == "FromRow") {
+        return Ok((None, quote! {}));
+    }
+    validate_container_attrs(&info.other_attrs)?;
+
+    let struct_name = &info.name;
+    let module_name = &info.module_name;
+
+    let (mut impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
+    let type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
+    let mut columns_generics = impl_generics.clone();
+    let (base_impl_generics, _, base_where_clause) = impl_generics.split_for_impl();
+    let base = quote! { impl #base_impl_generics #struct_name #type_args #base_where_clause };
+    let mut write_generics = impl_generics.clone();
+    write_generics.params.insert(0, syn::parse_quote! { 'args });
+    write_generics.params.push(syn::parse_quote! { __DB });
+    write_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! { __DB: ::sqlx::Database });
+    impl_generics.params.insert(0, syn::parse_quote! { 'r });
+    impl_generics.params.push(syn::parse_quote! { __R });
+    impl_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! { __R: ::sqlx::Row });
+    let mut from_row_generics = impl_generics.clone();
+    from_row_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! { Self: ::structural_typing::sqlx::FromPrefixedRow<'r, __R> });
+
+    let mut fields = Vec::new();
+    let mut columns = Vec::new();
+    let mut writes = Vec::new();
+    for field in &info.fields {
+        let field_name = &field.name;
+        let field_ty = &field.ty;
+        let presence = quote! { <F as #module_name::Fields>::#field_name };
+        let container = quote! {
+            <<#presence as ::structural_typing::presence::Presence>::Keep<::structural_typing::presence::Patch> as ::structural_typing::presence::Presence>::Output<#field_ty>
+        };
+        // `Keep<Present>` stays Absent only for Absent fields.
+        let holds_field = quote! {
+            <<<#presence as ::structural_typing::presence::Presence>::Keep<::structural_typing::presence::Present> as ::structural_typing::presence::Presence>::AlwaysPresent as ::structural_typing::presence::Bool>::VALUE
+        };
+        let (read_trait, read, name) = match field_column(field)? {
+            Column::Single(name) => {
+                columns.push(quote! {
+                    if #holds_field {
+                        columns.push(::structural_typing::sqlx::Column::new(prefix, #name));
+                    }
+                });
+                write_generics
+                    .make_where_clause()
+                    .predicates
+                    .push(syn::parse_quote! { #container: ::structural_typing::sqlx::WriteColumn<'args, __DB> });
+                writes.push(quote! {
+                    <#container as ::structural_typing::sqlx::WriteColumn<'args, __DB>>::write_column(
+                        ::structural_typing::sqlx::into_written::<_, _, #field_ty>(self.#field_name),
+                        ::structural_typing::sqlx::Column::new(prefix, #name),
+                        written,
+                    );
+                });
+                let nullable = is_option(field_ty);
+                (
+                    quote! { ::structural_typing::sqlx::ReadColumn<'r, __R> },
+                    quote! { read_column(row, &column, required, #nullable) },
+                    name,
+                )
+            }
+            Column::Nested(name) => {
+                columns_generics.make_where_clause().predicates.push(
+                    syn::parse_quote! { #field_ty: ::structural_typing::sqlx::SelectColumns },
+                );
+                columns.push(quote! {
+                    if #holds_field {
+                        <#field_ty as ::structural_typing::sqlx::SelectColumns>::push_columns(
+                            &::structural_typing::sqlx::column(prefix, #name),
+                            columns,
+                        );
+                    }
+                });
+                write_generics
+                    .make_where_clause()
+                    .predicates
+                    .push(syn::parse_quote! { #container: ::structural_typing::sqlx::WriteNested<'args, __DB> });
+                writes.push(quote! {
+                    <#container as ::structural_typing::sqlx::WriteNested<'args, __DB>>::write_nested(
+                        ::structural_typing::sqlx::into_written::<_, _, #field_ty>(self.#field_name),
+                        &::structural_typing::sqlx::column(prefix, #name),
+                        written,
+                    );
+                });
+                (
+                    quote! { ::structural_typing::sqlx::ReadNested<'r, __R> },
+                    quote! { read_nested(row, &column, required) },
+                    name,
+                )
+            }
+        };
+        impl_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! { #container: #read_trait });
+
+        fields.push(quote! {
+            #field_name: {
+                let column = ::structural_typing::sqlx::column(prefix, #name);
+                let required = !<<#presence as ::structural_typing::presence::Presence>::AcceptsMissing as ::structural_typing::presence::Bool>::VALUE;
+                let value = <#container as #read_trait>::#read?;
+                match <<#presence as ::structural_typing::presence::Presence>::Keep<::structural_typing::presence::Patch> as TryExtract<#presence>>::try_extract::<#field_ty>(value) {
+                    Ok((value, _)) => value,
+                    Err(_) => return Err(::sqlx::Error::ColumnNotFound(column.into_owned())),
+                }
+            }
+        });
+    }
+
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    let (from_row_impl_generics, _, from_row_where_clause) = from_row_generics.split_for_impl();
+    let (columns_impl_generics, _, columns_where_clause) = columns_generics.split_for_impl();
+    let (write_impl_generics, _, write_where_clause) = write_generics.split_for_impl();
+
+    let module_items = quote! {
+        /// Columns read by `FromRow` for the field set `F`: one per non-Absent field, with nested
+        /// fields expanded to their prefixed columns.
+        pub fn columns #columns_impl_generics () -> ::std::vec::Vec<::structural_typing::sqlx::Column> #columns_where_clause {
+            <#struct_name #type_args as ::structural_typing::sqlx::SelectColumns>::columns()
+        }
+
+        /// `SELECT` column list for the field set `F`, matching what `FromRow` reads.
+        pub fn select_list #columns_impl_generics () -> ::structural_typing::sqlx::SelectList #columns_where_clause {
+            ::structural_typing::sqlx::SelectList::new(columns:: #type_args ())
+        }
+    };
+
+    let impls = quote! {
+        impl #impl_generics ::structural_typing::sqlx::FromPrefixedRow<'r, __R> for #struct_name #type_args #where_clause {
+            fn from_prefixed_row(row: &'r __R, prefix: &str) -> ::core::result::Result<Self, ::sqlx::Error> {
+                use ::structural_typing::extract::TryExtract;
+
+                Ok(Self {
+                    #(#fields),*
+                })
+            }
+        }
+
+        impl #from_row_impl_generics ::sqlx::FromRow<'r, __R> for #struct_name #type_args #from_row_where_clause {
+            fn from_row(row: &'r __R) -> ::core::result::Result<Self, ::sqlx::Error> {
+                <Self as ::structural_typing::sqlx::FromPrefixedRow<'r, __R>>::from_prefixed_row(row, "")
+            }
+        }
+
+        impl #columns_impl_generics ::structural_typing::sqlx::SelectColumns for #struct_name #type_args #columns_where_clause {
+            fn push_columns(prefix: &str, columns: &mut ::std::vec::Vec<::structural_typing::sqlx::Column>) {
+                #(#columns)*
+            }
+        }
+
+        impl #write_impl_generics ::structural_typing::sqlx::WriteColumns<'args, __DB> for #struct_name #type_args #write_where_clause {
+            fn write_columns(self, prefix: &str, written: &mut ::structural_typing::sqlx::Written<'args, __DB>) {
+                #(#writes)*
+            }
+        }
+
+        #base {
+            /// `INSERT INTO table (...) VALUES (...)` listing only the fields holding a value or an
+            /// explicit `null`, with their values bound in the same order.
+            ///
+            /// `table` and the column names are written into the SQL unquoted and unescaped, so
+            /// `table` is `'static` to keep it to names fixed in the program.
+            pub fn insert_query<'args, __DB>(self, table: &'static str) -> ::sqlx::QueryBuilder<'args, __DB>
+            where
+                __DB: ::sqlx::Database,
+                <__DB as ::sqlx::Database>::Arguments<'args>: ::core::default::Default,
+                Self: ::structural_typing::sqlx::WriteColumns<'args, __DB>,
+            {
+                let mut written = ::structural_typing::sqlx::Written::new();
+                ::structural_typing::sqlx::WriteColumns::write_columns(self, "", &mut written);
+                let mut builder = ::sqlx::QueryBuilder::new("");
+                written.push_insert(table, &mut builder);
+                builder
+            }
+
+            /// `UPDATE table SET ...` assigning only the fields holding a value or an explicit
+            /// `null`, with their values bound in the same order, or `None` if there are none.
+            ///
+            /// `table` and the column names are written into the SQL unquoted and unescaped, so
+            /// `table` is `'static` to keep it to names fixed in the program. Add the `WHERE`
+            /// clause to the returned builder, binding its values with `push_bind`.
+            pub fn update_query<'args, __DB>(self, table: &'static str) -> ::core::option::Option<::sqlx::QueryBuilder<'args, __DB>>
+            where
+                __DB: ::sqlx::Database,
+                <__DB as ::sqlx::Database>::Arguments<'args>: ::core::default::Default,
+                Self: ::structural_typing::sqlx::WriteColumns<'args, __DB>,
+            {
+                let mut written = ::structural_typing::sqlx::Written::new();
+                ::structural_typing::sqlx::WriteColumns::write_columns(self, "", &mut written);
+                if written.is_empty() {
+                    return None;
+                }
+                let mut builder = ::sqlx::QueryBuilder::new(::std::format!("UPDATE {table} SET "));
+                written.push_set(&mut builder);
+                Some(builder)
+            }
+        }
+    };
+
+    Ok((Some(module_items), impls))
+}
+
+/// Reads a field's column from its `#[sqlx]` attributes, rejecting `rename` together with `prefix`
+/// or `flatten`, since nested fields have no column of their own to rename.
+fn field_column(field: &FieldInfo) -> syn::Result<Column> {
+    let mut rename = None;
+    let mut nested = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("sqlx"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let name: LitStr = meta.value()?.parse()?;
+                rename = Some((meta.path, name.value()));
+            } else if meta.path.is_ident("prefix") {
+                let prefix: LitStr = meta.value()?.parse()?;
+                nested = Some((meta.path, prefix.value()));
+            } else if meta.path.is_ident("flatten") {
+                nested = Some((meta.path, String::new()));
+            } else {
+                return Err(meta.error(
+                    "unsupported #[sqlx] attribute; expected `rename`, `prefix` or `flatten`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    match (rename, nested) {
+        (Some((rename, _)), Some(_)) => Err(syn::Error::new_spanned(
+            rename,
+            "#[sqlx(rename)] cannot be combined with `prefix` or `flatten`; nested fields read the columns of their own fields",
+        )),
+        (_, Some((_, prefix))) => Ok(Column::Nested(prefix)),
+        (Some((_, name)), None) => Ok(Column::Single(name)),
+        (None, None) => Ok(Column::Single(field.name.unraw().to_string())),
+    }
+}
+
+/// Rejects container `#[sqlx(...)]` attributes, none of which are supported.
+fn validate_container_attrs(attrs: &[Attribute]) -> syn::Result<()> {
+    match attrs.iter().find(|attr| attr.path().is_ident("sqlx")) {
+        Some(attr) => Err(syn::Error::new_spanned(
+            attr,
+            "structural typing does not support #[sqlx] attributes on the struct",
+        )),
+        None => Ok(()),
+    }
+}
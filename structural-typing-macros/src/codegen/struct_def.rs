@@ -1,7 +1,10 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
-use crate::codegen::serde_deserialize::{filter_serialize_attrs, helper_path};
+use crate::codegen::generics_utils::{extract_type_param_idents, type_args_with_f};
+use crate::codegen::serde_deserialize::{
+    filter_serialize_attrs, helper_path, skip_serializing_attr,
+};
 use crate::parsing::StructInfo;
 
 pub fn generate(info: &StructInfo) -> TokenStream {
@@ -43,7 +46,7 @@ pub fn generate(info: &StructInfo) -> TokenStream {
 
         let mut serde_attrs = Vec::new();
         if has_serialize {
-            serde_attrs.push(quote! { #[serde(skip_serializing_if = "::structural_typing::access::is_absent")] });
+            serde_attrs.push(skip_serializing_attr(info, field));
         }
 
         let preserved_attrs = filter_serialize_attrs(&field.attrs);
@@ -56,14 +59,16 @@ pub fn generate(info: &StructInfo) -> TokenStream {
     }).collect();
 
     // Separate Deserialize from other derives - it doesn't need bounds with try_from
-    let derives_to_use: Vec<_> = if has_deserialize {
-        info.derives
-            .iter()
-            .filter(|d| *d != "Deserialize")
-            .collect()
-    } else {
-        info.derives.iter().collect()
-    };
+    // FromRow, JsonSchema and ToSchema are generated separately, for every field selection
+    let derives_to_use: Vec<_> = info
+        .derives
+        .iter()
+        .filter(|d| {
+            !["Deserialize", "FromRow", "JsonSchema", "ToSchema"]
+                .iter()
+                .any(|name| d == name)
+        })
+        .collect();
 
     let derive_bounds: Vec<_> = info
         .fields
@@ -85,16 +90,25 @@ pub fn generate(info: &StructInfo) -> TokenStream {
         }
     };
 
-    // Deserialize uses separate derive_where without bounds
-    // If it's the only derive, it needs the :: path qualification
+    // Deserialize goes through the helper, so its bounds are the helper's `Deserialize` and the
+    // conversion from it rather than the field types. When it is the only derive, the attribute
+    // needs the `::derive_where::` path, since no earlier `derive_where` attribute brings it in.
     let deserialize_derive = if has_deserialize {
+        let helper: syn::Type =
+            syn::parse_str(&helper_path_str).expect("helper path is a valid type");
+        let user_type_args = extract_type_param_idents(&info.generics.params);
+        let type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
+        let bounds = quote! {
+            #helper,
+            #struct_name #type_args: ::core::convert::TryFrom<#helper, Error = ::structural_typing::serde::FieldsError>
+        };
         if derives_to_use.is_empty() {
             quote! {
-                #[::derive_where::derive_where(Deserialize)]
+                #[::derive_where::derive_where(Deserialize; #bounds)]
             }
         } else {
             quote! {
-                #[derive_where(Deserialize)]
+                #[derive_where(Deserialize; #bounds)]
             }
         }
     } else {
@@ -0,0 +1,301 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{GenericParam, Type};
+
+use crate::codegen::generics_utils::{fields_module, impl_generics_with_f, type_args_with_f};
+use crate::codegen::json_schema::{docs, option_str};
+use crate::codegen::serde_deserialize::{
+    fills_default, input_name, is_flattened, output_name, params_in_type,
+};
+use crate::parsing::StructInfo;
+
+/// Types utoipa describes in place rather than as a component of their own.
+const INLINE_TYPES: &[&str] = &[
+    "bool",
+    "char",
+    "str",
+    "String",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "usize",
+    "f32",
+    "f64",
+    "Option",
+    "Vec",
+    "VecDeque",
+    "LinkedList",
+    "HashMap",
+    "BTreeMap",
+    "HashSet",
+    "BTreeSet",
+    "Box",
+    "Cow",
+    "RefCell",
+    "Rc",
+    "Arc",
+    "Value",
+];
+
+/// Generates utoipa's `PartialSchema` and `ToSchema` for `#[derive(ToSchema)]`, describing each
+/// field selection as its own component, and the schema module's `__component_fields` function
+/// naming it. Selections listed in `#[structural(components(...))]` are named after their alias,
+/// found by comparing the selection's type with each alias's.
+pub fn generate(info: &StructInfo) -> syn::Result<(Option<TokenStream>, TokenStream)> {
+    if !info.derives.iter().any(|d| d == "ToSchema") {
+        if let Some(alias) = info.options.components.first() {
+            return Err(syn::Error::new_spanned(
+                alias,
+                "`components` names OpenAPI components, so it needs #[derive(ToSchema)]",
+            ));
+        }
+        return Ok((None, quote! {}));
+    }
+    validate_schema_attrs(info)?;
+
+    let struct_name = &info.name;
+    let struct_name_str = struct_name.to_string();
+    let module_name = &info.module_name;
+
+    let (mut impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
+    let type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
+    let components = components(info, &impl_generics, &type_args);
+
+    let mut name_fields = Vec::new();
+    let mut fields = Vec::new();
+    let mut schemas = Vec::new();
+    for field in &info.fields {
+        let field_name = &field.name;
+        let field_ty = &field.ty;
+        let presence = quote! { <F as #module_name::Fields>::#field_name };
+        let container = quote! {
+            <<#presence as ::structural_typing::presence::Presence>::Keep<::structural_typing::presence::Patch> as ::structural_typing::presence::Presence>::Output<#field_ty>
+        };
+        let accepts_missing = quote! {
+            <<#presence as ::structural_typing::presence::Presence>::AcceptsMissing as ::structural_typing::presence::Bool>::VALUE
+        };
+        let default = fills_default(info, field);
+        let required = quote! { !#accepts_missing && !#default };
+        impl_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! { #container: ::structural_typing::utoipa::FieldSchema });
+
+        let field_name_str = field_name.unraw().to_string();
+        let holds_field = holds_field(&presence);
+        name_fields.push(quote! {
+            if #holds_field {
+                name.field(#field_name_str, #required);
+            }
+        });
+
+        let inline = is_flattened(&field.attrs) || is_inline(field_ty);
+        schemas.push(quote! {
+            <#container as ::structural_typing::utoipa::FieldSchema>::add_schemas(schemas, #inline);
+        });
+        if is_flattened(&field.attrs) {
+            fields.push(quote! {
+                <#container as ::structural_typing::utoipa::FieldSchema>::add_flattened(&mut object, !#accepts_missing);
+            });
+        } else {
+            let input_name = input_name(info, field);
+            let description = option_str(docs(&field.attrs));
+            fields.push(quote! {
+                <#container as ::structural_typing::utoipa::FieldSchema>::add_field(
+                    &mut object,
+                    #input_name,
+                    #required,
+                    #accepts_missing,
+                    #inline,
+                    #description,
+                );
+            });
+        }
+    }
+
+    // Selections of the struct's own field-set parameters, named when a field using them is held.
+    let mut name_params = Vec::new();
+    for param in &info.generics.params {
+        let GenericParam::Type(param) = param else {
+            continue;
+        };
+        let ident = &param.ident;
+        let holds_any: Vec<_> = info
+            .fields
+            .iter()
+            .filter(|field| {
+                params_in_type(info, &field.ty)
+                    .iter()
+                    .any(|used| matches!(used, GenericParam::Type(used) if used.ident == *ident))
+            })
+            .map(|field| {
+                let field_name = &field.name;
+                holds_field(&quote! { <F as #module_name::Fields>::#field_name })
+            })
+            .collect();
+        if holds_any.is_empty() {
+            continue;
+        }
+        let add_param = match fields_module(&param.bounds) {
+            Some(module) => quote! { #module::__component_fields::<#ident>(&mut name); },
+            None => {
+                impl_generics
+                    .make_where_clause()
+                    .predicates
+                    .push(syn::parse_quote! { #ident: ::utoipa::ToSchema });
+                quote! { name.type_arg(&<#ident as ::utoipa::ToSchema>::name()); }
+            }
+        };
+        name_params.push(quote! {
+            if #(#holds_any)||* {
+                #add_param
+            }
+        });
+    }
+
+    let description = option_str(docs(&info.other_attrs));
+    let mut name_generics = impl_generics.clone();
+    if !info.options.components.is_empty() {
+        name_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! { Self: 'static });
+    }
+    let (_, _, name_where) = name_generics.split_for_impl();
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+
+    let module_items = quote! {
+        /// Adds the selected fields of `F` to the default OpenAPI component name of a selection.
+        #[doc(hidden)]
+        pub fn __component_fields<F: Fields>(name: &mut ::structural_typing::utoipa::ComponentName) {
+            name.selection();
+            #(#name_fields)*
+        }
+    };
+
+    let impls = quote! {
+
+        impl #impl_generics ::utoipa::PartialSchema for #struct_name #type_args #where_clause {
+            fn schema() -> ::utoipa::openapi::RefOr<::utoipa::openapi::schema::Schema> {
+                let mut object = ::structural_typing::utoipa::ObjectSchema::new();
+                #(#fields)*
+                object.into_schema(#description)
+            }
+        }
+
+        impl #impl_generics ::utoipa::ToSchema for #struct_name #type_args #name_where {
+            fn name() -> ::std::borrow::Cow<'static, str> {
+                #components
+                let mut name = ::structural_typing::utoipa::ComponentName::new(#struct_name_str);
+                #module_name::__component_fields::<F>(&mut name);
+                #(#name_params)*
+                name.finish()
+            }
+
+            fn schemas(
+                schemas: &mut ::std::vec::Vec<(
+                    ::std::string::String,
+                    ::utoipa::openapi::RefOr<::utoipa::openapi::schema::Schema>,
+                )>,
+            ) {
+                #(#schemas)*
+            }
+        }
+    };
+
+    Ok((Some(module_items), impls))
+}
+
+/// Returns the component name of the `#[structural(components(...))]` alias `Self` is, if any,
+/// after checking that each alias is a selection of the struct.
+fn components(
+    info: &StructInfo,
+    impl_generics: &syn::Generics,
+    type_args: &TokenStream,
+) -> TokenStream {
+    let struct_name = &info.name;
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    info.options
+        .components
+        .iter()
+        .map(|alias| {
+            let name = alias
+                .segments
+                .last()
+                .map_or_else(String::new, |segment| segment.ident.unraw().to_string());
+            let check = quote_spanned! {alias.span()=>
+                let _: fn(&#alias) = |alias| selection(alias);
+            };
+            quote! {
+                const _: () = {
+                    fn selection #impl_generics (_: &#struct_name #type_args) #where_clause {}
+                    #check
+                };
+                if ::core::any::TypeId::of::<Self>() == ::core::any::TypeId::of::<#alias>() {
+                    return #name.into();
+                }
+            }
+        })
+        .collect()
+}
+
+/// Whether the presence `presence` holds a value, which only Absent does not: `Keep<Present>`
+/// stays Absent only for Absent fields.
+fn holds_field(presence: &TokenStream) -> TokenStream {
+    quote! {
+        <<<#presence as ::structural_typing::presence::Presence>::Keep<::structural_typing::presence::Present> as ::structural_typing::presence::Presence>::AlwaysPresent as ::structural_typing::presence::Bool>::VALUE
+    }
+}
+
+/// Whether utoipa describes `ty` in place: primitives, strings, collections and smart pointers,
+/// references, slices, arrays and tuples.
+fn is_inline(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| INLINE_TYPES.iter().any(|name| segment.ident == name)),
+        Type::Reference(_) | Type::Slice(_) | Type::Array(_) | Type::Tuple(_) => true,
+        Type::Group(group) => is_inline(&group.elem),
+        Type::Paren(paren) => is_inline(&paren.elem),
+        _ => false,
+    }
+}
+
+/// Rejects `#[schema(...)]` attributes, whose options the generated schema does not read, and
+/// renames that differ between serializing and deserializing, since one component describes both
+/// requests and responses.
+fn validate_schema_attrs(info: &StructInfo) -> syn::Result<()> {
+    for field in &info.fields {
+        if input_name(info, field) != output_name(info, field) {
+            return Err(syn::Error::new_spanned(
+                &field.name,
+                "#[derive(ToSchema)] describes requests and responses with one component, so fields cannot be renamed differently for serialize and deserialize",
+            ));
+        }
+    }
+    let attrs = info
+        .other_attrs
+        .iter()
+        .chain(info.fields.iter().flat_map(|field| &field.attrs));
+    for attr in attrs {
+        if attr.path().is_ident("schema") {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "structural typing does not support #[schema] attributes; use #[serde] renames and doc comments instead",
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::GenericParam;
+
+use crate::codegen::generics_utils::{fields_module, impl_generics_with_f, type_args_with_f};
+use crate::codegen::json_schema::{docs, option_str};
+use crate::codegen::serde_deserialize::{is_flattened, output_name, params_in_type};
+use crate::codegen::type_subst::substitute_type;
+use crate::parsing::StructInfo;
+
+/// Generates the schema module's `ts_interface` function and `TsType` for every field selection,
+/// for `#[structural(ts)]`. Properties take their serialized names, as the declarations describe
+/// what serializing the struct produces.
+pub fn generate(info: &StructInfo) -> syn::Result<(Option<TokenStream>, TokenStream)> {
+    if info.options.ts.is_none() {
+        return Ok((None, quote! {}));
+    }
+
+    let struct_name = &info.name;
+    let struct_name_str = struct_name.to_string();
+    let module_name = &info.module_name;
+
+    // The interface describes nested structural fields by their full schema: each field-set
+    // parameter stands for all of its fields, through a local alias.
+    let mut full_params = HashMap::new();
+    let mut aliases = Vec::new();
+    for param in &info.generics.params {
+        let module = match param {
+            GenericParam::Type(param) => fields_module(&param.bounds),
+            _ => None,
+        };
+        let (GenericParam::Type(param), Some(module)) = (param, module) else {
+            return Err(syn::Error::new_spanned(
+                param,
+                "`ts` supports only field-set generic parameters, like `P: project::Fields`",
+            ));
+        };
+        let alias = format_ident!("__Full{}", param.ident);
+        aliases.push(quote! { type #alias = #module::with::all; });
+        full_params.insert(param.ident.to_string(), alias);
+    }
+
+    let (mut impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
+    let type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
+
+    let mut properties = Vec::new();
+    let mut selected = Vec::new();
+    for field in &info.fields {
+        let field_name = &field.name;
+        let field_ty = &field.ty;
+        let full_ty = substitute_type(field_ty, &full_params);
+        let full = quote! { <#full_ty as ::structural_typing::ts::TsType>::ts_type() };
+        let presence = quote! { <F as #module_name::Fields>::#field_name };
+        let container = quote! {
+            <<#presence as ::structural_typing::presence::Presence>::Keep<::structural_typing::presence::Patch> as ::structural_typing::presence::Presence>::Output<#field_ty>
+        };
+        let required = quote! {
+            !<<#presence as ::structural_typing::presence::Presence>::AcceptsMissing as ::structural_typing::presence::Bool>::VALUE
+        };
+        impl_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! { #container: ::structural_typing::ts::TsField });
+
+        if is_flattened(&field.attrs) {
+            properties.push(quote! {
+                interface.flatten(#full);
+            });
+            selected.push(quote! {
+                <#container as ::structural_typing::ts::TsField>::select_flattened(&mut selection, #required, #full);
+            });
+        } else {
+            let output_name = output_name(info, field);
+            let description = option_str(docs(&field.attrs));
+            properties.push(quote! {
+                interface.property(#output_name, &#full, #description);
+            });
+            // Only fields using the struct's parameters can hold a selection other than the
+            // interface's.
+            let full = if params_in_type(info, field_ty).is_empty() {
+                quote! { ::core::option::Option::None }
+            } else {
+                quote! { ::core::option::Option::Some(#full) }
+            };
+            selected.push(quote! {
+                <#container as ::structural_typing::ts::TsField>::select(&mut selection, #output_name, #required, #full);
+            });
+        }
+    }
+    let description = option_str(docs(&info.other_attrs));
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+
+    let module_items = quote! {
+        /// TypeScript `interface` declaring the full schema, with every field.
+        pub fn ts_interface() -> ::std::string::String {
+            #(#aliases)*
+            let mut interface = ::structural_typing::ts::Interface::new(#struct_name_str, #description);
+            #(#properties)*
+            interface.finish()
+        }
+    };
+
+    let impls = quote! {
+        impl #impl_generics ::structural_typing::ts::TsType for #struct_name #type_args #where_clause {
+            fn ts_type() -> ::std::string::String {
+                #(#aliases)*
+                let mut selection = ::structural_typing::ts::Selection::new(#struct_name_str);
+                #(#selected)*
+                selection.finish()
+            }
+        }
+    };
+
+    Ok((Some(module_items), impls))
+}
@@ -197,3 +197,17 @@ pub fn substitute_type_args(
         })
         .collect()
 }
+
+/// Whether `ty` is spelled as `Option<_>`, by its last path segment.
+pub fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option" && !segment.arguments.is_empty()),
+        Type::Group(group) => is_option(&group.elem),
+        Type::Paren(paren) => is_option(&paren.elem),
+        _ => false,
+    }
+}
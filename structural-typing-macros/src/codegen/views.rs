@@ -0,0 +1,291 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::codegen::generics_utils::{impl_generics_with_f, type_args_with_f, view_type_args};
+use crate::codegen::serde_deserialize::{filter_serialize_attrs, skip_serializing_attr};
+use crate::parsing::StructInfo;
+
+/// Which borrowed view to generate.
+#[derive(Clone, Copy)]
+enum ViewKind {
+    Ref,
+    Mut,
+}
+
+impl ViewKind {
+    fn suffix(self) -> &'static str {
+        match self {
+            ViewKind::Ref => "Ref",
+            ViewKind::Mut => "Mut",
+        }
+    }
+
+    fn reference(self, lifetime: &syn::Lifetime, ty: &syn::Type) -> TokenStream {
+        match self {
+            ViewKind::Ref => quote! { &#lifetime #ty },
+            ViewKind::Mut => quote! { &#lifetime mut #ty },
+        }
+    }
+}
+
+/// Generates the borrowed views and the `as_ref`/`as_mut` methods for `#[structural(views)]`.
+pub fn generate(info: &StructInfo) -> TokenStream {
+    if info.options.views.is_none() {
+        return quote! {};
+    }
+    let ref_view = generate_view(info, ViewKind::Ref);
+    let mut_view = generate_view(info, ViewKind::Mut);
+    let constructors = generate_constructors(info);
+
+    quote! {
+        #ref_view
+
+        #mut_view
+
+        #constructors
+    }
+}
+
+fn view_lifetime() -> syn::Lifetime {
+    syn::Lifetime::new("'__view", proc_macro2::Span::call_site())
+}
+
+fn view_name(info: &StructInfo, kind: ViewKind) -> syn::Ident {
+    format_ident!("{}{}", info.name, kind.suffix())
+}
+
+fn generate_view(info: &StructInfo, kind: ViewKind) -> TokenStream {
+    let struct_name = &info.name;
+    let module_name = &info.module_name;
+    let vis = &info.vis;
+    let view_name = view_name(info, kind);
+    let lifetime = view_lifetime();
+
+    let (mut view_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
+    view_generics.params.insert(0, syn::parse_quote!(#lifetime));
+    let params = &view_generics.params;
+    let where_clause = &view_generics.where_clause;
+
+    let (impl_generics, _, _) = view_generics.split_for_impl();
+    let self_args = view_type_args(&info.generics, &user_type_args, &lifetime, quote! { F });
+    let f2_args = view_type_args(&info.generics, &user_type_args, &lifetime, quote! { F2 });
+    let remainder_args = view_type_args(
+        &info.generics,
+        &user_type_args,
+        &lifetime,
+        quote! { #module_name::Remainder<F, F2> },
+    );
+
+    let has_serialize =
+        matches!(kind, ViewKind::Ref) && info.derives.iter().any(|d| d == "Serialize");
+    let has_debug = info.derives.iter().any(|d| d == "Debug");
+
+    let field_defs = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let field_vis = &field.vis;
+        let reference = kind.reference(&lifetime, &field.ty);
+        let serde_attrs = if has_serialize {
+            let preserved = filter_serialize_attrs(&field.attrs);
+            let skip = skip_serializing_attr(info, field);
+            quote! {
+                #skip
+                #(#preserved)*
+            }
+        } else {
+            quote! {}
+        };
+        quote! {
+            #serde_attrs
+            #field_vis #field_name: <F::#field_name as ::structural_typing::presence::Presence>::Output<#reference>
+        }
+    });
+
+    let derive_bounds: Vec<_> = info
+        .fields
+        .iter()
+        .map(|field| {
+            let field_name = &field.name;
+            let reference = kind.reference(&lifetime, &field.ty);
+            quote! {
+                <F::#field_name as ::structural_typing::presence::Presence>::Output<#reference>
+            }
+        })
+        .collect();
+
+    // `Clone` and `Copy` are written out rather than derived, so views of structs without derives
+    // do not need `derive_where` in scope.
+    let copy_impls = if matches!(kind, ViewKind::Ref) {
+        let mut clone_generics = view_generics.clone();
+        let mut copy_generics = view_generics.clone();
+        for bound in &derive_bounds {
+            clone_generics
+                .make_where_clause()
+                .predicates
+                .push(syn::parse_quote! { #bound: ::core::clone::Clone });
+            copy_generics
+                .make_where_clause()
+                .predicates
+                .push(syn::parse_quote! { #bound: ::core::marker::Copy });
+        }
+        let clone_where = &clone_generics.where_clause;
+        let copy_where = &copy_generics.where_clause;
+        let cloned_fields = info.fields.iter().map(|field| {
+            let field_name = &field.name;
+            quote! { #field_name: ::core::clone::Clone::clone(&self.#field_name) }
+        });
+        quote! {
+            impl #impl_generics ::core::clone::Clone for #view_name #self_args #clone_where {
+                #[inline]
+                fn clone(&self) -> Self {
+                    #view_name {
+                        #(#cloned_fields),*
+                    }
+                }
+            }
+
+            impl #impl_generics ::core::marker::Copy for #view_name #self_args #copy_where {}
+        }
+    } else {
+        quote! {}
+    };
+
+    let mut derives = Vec::new();
+    if has_debug {
+        derives.push(quote! { Debug });
+    }
+    if has_serialize {
+        derives.push(quote! { Serialize });
+    }
+    let derive_clause = if derives.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            #[::derive_where::derive_where(#(#derives),*; #(#derive_bounds),*)]
+        }
+    };
+    let container_serde_attrs = if has_serialize {
+        filter_serialize_attrs(&info.other_attrs)
+    } else {
+        Vec::new()
+    };
+
+    let extract_bounds = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        quote! {
+            F::#field_name: ::structural_typing::extract::Extract<F2::#field_name>
+        }
+    });
+    let extract_parts = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let reference = kind.reference(&lifetime, &field.ty);
+        let extracted = format_ident!("{}_o", field_name.to_string().trim_start_matches("r#"));
+        let remainder = format_ident!("{}_r", field_name.to_string().trim_start_matches("r#"));
+        quote! {
+            let (#extracted, #remainder) = <F::#field_name as ::structural_typing::extract::Extract<F2::#field_name>>::extract::<#reference>(self.#field_name);
+        }
+    });
+    let extracted_fields = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let extracted = format_ident!("{}_o", field_name.to_string().trim_start_matches("r#"));
+        quote! { #field_name: #extracted }
+    });
+    let remainder_fields = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let remainder = format_ident!("{}_r", field_name.to_string().trim_start_matches("r#"));
+        quote! { #field_name: #remainder }
+    });
+
+    let (view_doc, method) = match kind {
+        ViewKind::Ref => (
+            format!(
+                "Borrowed view of [`{struct_name}`] with the same field presence, holding `&T` for each field."
+            ),
+            "as_ref",
+        ),
+        ViewKind::Mut => (
+            format!(
+                "Mutable borrowed view of [`{struct_name}`] with the same field presence, holding `&mut T` for each field."
+            ),
+            "as_mut",
+        ),
+    };
+    let view_doc_detail = format!("Created by [`{struct_name}::{method}`].");
+
+    quote! {
+        #[doc = #view_doc]
+        ///
+        #[doc = #view_doc_detail]
+        #derive_clause
+        #(#container_serde_attrs)*
+        #vis struct #view_name<#params> #where_clause {
+            #(#field_defs),*
+        }
+
+        #copy_impls
+
+        impl #impl_generics #view_name #self_args #where_clause {
+            /// Extracts selected fields and remainder. Always succeeds.
+            #[must_use]
+            pub fn extract<F2: #module_name::Fields>(self) -> (#view_name #f2_args, #view_name #remainder_args)
+            where
+                #(#extract_bounds),*
+            {
+                #(#extract_parts)*
+
+                (#view_name {
+                    #(#extracted_fields),*
+                }, #view_name {
+                    #(#remainder_fields),*
+                })
+            }
+        }
+    }
+}
+
+fn generate_constructors(info: &StructInfo) -> TokenStream {
+    let struct_name = &info.name;
+    let module_name = &info.module_name;
+    let ref_name = view_name(info, ViewKind::Ref);
+    let mut_name = view_name(info, ViewKind::Mut);
+    let elided: syn::Lifetime = syn::parse_quote!('_);
+
+    let (impl_generics, user_type_args) = impl_generics_with_f(&info.generics, module_name);
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    let impl_type_args = type_args_with_f(&info.generics, &user_type_args, quote! { F });
+    let view_args = view_type_args(&info.generics, &user_type_args, &elided, quote! { F });
+
+    let ref_fields = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let field_ty = &field.ty;
+        quote! {
+            #field_name: <F::#field_name as ::structural_typing::presence::Presence>::as_ref::<#field_ty>(&self.#field_name)
+        }
+    });
+    let mut_fields = info.fields.iter().map(|field| {
+        let field_name = &field.name;
+        let field_ty = &field.ty;
+        quote! {
+            #field_name: <F::#field_name as ::structural_typing::presence::Presence>::as_mut::<#field_ty>(&mut self.#field_name)
+        }
+    });
+
+    quote! {
+        impl #impl_generics #struct_name #impl_type_args #where_clause {
+            /// Borrows every field, keeping each field's presence state.
+            #[must_use]
+            pub fn as_ref(&self) -> #ref_name #view_args {
+                #ref_name {
+                    #(#ref_fields),*
+                }
+            }
+
+            /// Mutably borrows every field, keeping each field's presence state.
+            #[must_use]
+            pub fn as_mut(&mut self) -> #mut_name #view_args {
+                #mut_name {
+                    #(#mut_fields),*
+                }
+            }
+        }
+    }
+}
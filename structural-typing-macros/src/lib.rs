@@ -4,6 +4,7 @@ use syn::{DeriveInput, parse_macro_input};
 mod analysis;
 mod codegen;
 mod parsing;
+mod select;
 
 /// Generates type-safe structural typing support for a struct.
 ///
@@ -19,9 +20,18 @@ mod parsing;
 ///
 /// For a struct `MyStruct`, this macro generates:
 ///
-/// - A `my_struct` module with `Fields` trait, `FieldSet<...>` type, and `with::` aliases
-/// - Builder methods: `.field(value)`
-/// - `.merge()`, `.extract()`, `.try_extract()` operations
+/// - A `my_struct` module with `Fields` trait, `FieldSet<...>` type, `with::` aliases, and
+///   `Merge` and `Remainder`, plus `Partial`, `Required`, `Intersect` and `Difference` in `ops`
+/// - `Satisfies` / `SubsetOf` relations between field sets
+/// - A `field` module with one requirement trait per field (`field::name<Present>`)
+/// - Builder methods: `.field(value)`, plus `.take_field()` / `.without_field()` to remove a field
+/// - `.merge()`, `.extract()`, `.try_extract()`, `.narrow()` operations
+/// - `.require()`, which upgrades selected fields in place and reports every missing field
+/// - With `#[structural(views)]`, borrowed views `MyStructRef` / `MyStructMut` from `.as_ref()` /
+///   `.as_mut()`, holding `&T` / `&mut T` with the same presence, with `.extract()` (and
+///   `Serialize` on `MyStructRef`)
+/// - With `#[structural(export = "name")]`, the schema's `select!` helper exported from the crate
+///   root as `name`, so that other crates can select its fields
 ///
 /// Supports user-defined generic type parameters and nested structural types (see `examples/nested.rs`).
 ///
@@ -30,6 +40,7 @@ mod parsing;
 /// - **Present**: Value of type `T`
 /// - **Optional**: `Option<T>`
 /// - **Absent**: `PhantomData<T>`
+/// - **Patch**: `PatchValue<T>` (missing, explicit `null`, or a value)
 ///
 /// # Example
 ///
@@ -57,31 +68,227 @@ mod parsing;
 ///
 /// # Serde Support
 ///
-/// Deserialization uses a helper struct where all fields are `Option<T>` with `#[serde(default)]`,
-/// then converts via `TryFrom`. Present fields error if missing, Optional fields become `Some`/`None`,
-/// Absent fields are ignored.
+/// Deserialization uses a helper struct, generic over the same field set, where each field is
+/// `PatchValue<T>` with `#[serde(default)]`, then converts via `TryFrom`. Present fields error if
+/// missing or `null`, Optional fields become `Some`/`None`, Patch fields keep the distinction
+/// between a missing key and `null`, and Absent fields are skipped without being parsed, so only
+/// the types of selected fields need to implement `Deserialize`.
+///
+/// **Defaults:** `#[serde(default)]` and `#[serde(default = "path")]` on a field fill in a missing key
+/// when the selected presence needs a value (Present, or a custom marker that cannot represent a
+/// missing value). Optional, Patch and Absent fields keep their usual handling of missing keys, and
+/// an explicit `null` is still an error. A bare `#[serde(default)]` on the struct applies
+/// `Default::default()` to every field that has no default of its own. Unlike serde, which takes
+/// those values from `Self::default()`, each field uses its own type's `Default`, and only fields
+/// selected Present need one.
 ///
 /// **Limitations:**
-/// - Present fields cannot use the field type's `Default` impl during deserialization
-/// - Optional fields cannot distinguish between missing and explicit `null` in JSON
-/// - Absent fields silently ignore data if present in input
-/// - All field types must implement `Deserialize` even if marked Absent
+/// - Optional fields cannot distinguish between missing and explicit `null` in JSON (use Patch)
+/// - Absent fields silently ignore data if present in input, unless the struct is marked
+///   `#[structural(deny_absent_input)]`, which rejects it with an "unexpected field" error
+/// - Fields marked `#[structural(deserialize_option)]` always call their function, even if marked Absent
+///
+/// **Serialization:** Absent fields and Patch fields holding `Missing` are skipped. Optional `None`
+/// serializes as `null` unless `#[structural(serialize_none = "omit")]` is set on the struct or the
+/// field; a field-level `serialize_none = "null"` overrides the struct's setting.
+///
+/// **Custom deserializers:** `#[serde(deserialize_with = "path")]` and `#[serde(with = "module")]` take
+/// the same functions as on a plain struct, producing `T`. The function receives every value but
+/// `null`, which Optional and Patch fields read as `None` and `Null` like other fields, and is not
+/// called for Absent fields. A function producing `Option<T>` needs
+/// `#[structural(deserialize_option)]` on the field; such fields are held as `Option<T>` in the helper,
+/// so Patch fields see `None` as `Null`.
+///
+/// **Borrowing:** `&'a str` and `&'a [u8]` fields borrow from the input, and `#[serde(borrow)]` makes
+/// other fields borrow, with `Cow<'a, str>` and `Cow<'a, [u8]>` borrowing when the format allows it
+/// as they do with serde's derive. Custom deserializers of such fields may return borrowed data.
+///
+/// **Flatten:** `#[serde(flatten)]` is supported on fields whose type is another `#[structural]` struct,
+/// like `audit: Audit<A>`. The nested struct reads its fields from the same object, enforces its own
+/// selection and reports its missing fields under their flat names. An Optional or Patch nested field
+/// is missing when none of its keys are given.
+///
+/// **Field errors:** `DeserializeFields` is implemented alongside `Deserialize`, returning the
+/// `FieldsError` of missing and unexpected fields apart from the deserializer's errors, for
+/// integrations like the axum extractors.
+///
+/// **Compact:** `#[structural(compact)]` (with the `compact` feature) also implements
+/// `CompactSerialize` and `CompactDeserialize`, for use through `structural_typing::serde::compact`
+/// with formats that are not self-describing. Field types need `Serialize` and `Deserialize` even
+/// when Absent, since the encoding does not know the reader's selection. Nested structural fields
+/// taking a field-set parameter are encoded compactly too, and field-level `#[serde(with)]`,
+/// `serialize_with` and `deserialize_with` are rejected.
+///
+/// **Incompatible:** `#[serde(default = "path")]` on the struct, `#[serde(skip)]`, `#[serde(skip_deserializing)]`,
+/// `#[serde(flatten)]` on other types.
+/// **Compatible:** `rename`, `alias`, `rename_all`, `default`, `flatten`, `deserialize_with`, `with`, `borrow`.
+///
+/// # JSON Schema Support
+///
+/// `#[derive(JsonSchema)]` (with the `schemars` feature) implements `schemars::JsonSchema` for
+/// every field selection, describing what deserializing it accepts. Present fields are `required`
+/// unless they have a serde default, Optional and Patch fields are nullable, and Absent fields are
+/// left out, so their types need not implement `JsonSchema`. Property names follow `rename` and
+/// `rename_all`, taking the serialize side of split renames when the generator's contract is
+/// `Serialize`, doc comments become descriptions, and `#[serde(flatten)]` fields add their
+/// properties to the object.
+///
+/// **Names:** the schema name lists the selected fields like `select!` does, marking fields that
+/// are not required with `?`, and adds the schema name of fields whose type uses the struct's
+/// generic parameters: `Todo<title, project: Project<id>>`. `#[schemars]` attributes are rejected.
+///
+/// # OpenAPI Support
 ///
-/// **Custom deserializers:** If using `#[serde(deserialize_with)]`, the function must produce `Option<T>`.
+/// `#[derive(ToSchema)]` (with the `utoipa` feature) implements utoipa's `ToSchema` and
+/// `PartialSchema` for every field selection, with the same `required` and nullable markers as
+/// `JsonSchema`. Fields of primitive, string, collection and smart pointer types are described in
+/// place; other fields, nested structural fields included, reference their type's component,
+/// which `ToSchema::schemas` registers.
 ///
-/// **Incompatible:** `#[serde(default)]`, `#[serde(skip)]`, `#[serde(skip_deserializing)]`, `#[serde(flatten)]`.
-/// **Compatible:** `rename`, `alias`, `rename_all`, `deserialize_with` (with `Option<T>` output).
+/// **Names:** the component name lists the selected fields, marking fields that are not required
+/// with `-opt`, followed by the selections of the struct's field-set parameters that are in use:
+/// `Todo-title.project-id`. `#[structural(components(CreateProject))]` names the component of the
+/// selection `type CreateProject = Project<select!(project: name)>` after the alias; the alias
+/// must be in scope of the struct, and such structs implement `ToSchema` for `'static` selections
+/// only. `#[schema]` attributes are rejected, and so are renames that differ between serialize
+/// and deserialize, since one component describes both requests and responses.
+///
+/// # TypeScript Support
+///
+/// `#[structural(ts)]` (with the `ts` feature) adds `ts_interface()` to the schema module,
+/// returning a TypeScript `interface` with every field under its serialized name and doc comments
+/// as `/** ... */` comments, and implements `structural_typing::ts::TsType` for every field
+/// selection: `Pick` (or `Omit`) of the required fields, and an object type with the Optional and
+/// Patch fields as nullable `?` properties and the nested structural fields holding another
+/// selection than the interface's. `#[serde(flatten)]` fields extend the interface. Field types need `TsType`,
+/// and generic parameters must be field sets, like `P: project::Fields`.
+///
+/// # sqlx Support
+///
+/// `#[derive(FromRow)]` (with the `sqlx` feature) implements `sqlx::FromRow` for every field
+/// selection, reading the columns of Present, Optional and Patch fields and skipping Absent ones.
+/// A missing column or `NULL` is an error only for fields that need a value.
+///
+/// **Field attributes:** `#[sqlx(rename = "column")]`, and `#[sqlx(prefix = "prefix_")]` or
+/// `#[sqlx(flatten)]` on a nested `#[structural]` field to read it from the same row. A nested
+/// field cannot also be renamed. An Optional nested field is `None` when its required columns are
+/// missing or `NULL`.
+///
+/// **Projections:** the schema module gets `columns::<F, ..>()`, listing the columns read for a
+/// field set (taking the struct's other generic parameters after `F`), and `select_list::<F, ..>()`,
+/// rendering them as a `SELECT` list.
+///
+/// **Writes:** `insert_query(table)` and `update_query(table)` build a `sqlx::QueryBuilder` for any
+/// database, assigning the columns of Present fields, Optional `Some`, and Patch values or `null`.
+/// `update_query` returns `None` when no column would be assigned. Table and column names are
+/// written unquoted, so `table` is a `&'static str` rather than a name taken from input.
 ///
 /// # Restrictions
 ///
 /// - Named structs only
 /// - At least one field
 #[proc_macro_attribute]
-pub fn structural(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn structural(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
 
-    match codegen::generate(input) {
+    match codegen::generate(attr.into(), input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Construct a `FieldSet` by selecting fields from a schema module.
+///
+/// # Presence States
+///
+/// | Suffix | Presence | Field Type |
+/// |--------|----------|------------|
+/// | (none) | Present | `T` |
+/// | `?` | Optional | `Option<T>` |
+/// | `-` | Absent | `PhantomData<T>` |
+/// | `~` | Patch | `PatchValue<T>` |
+///
+/// Access is always `user.field`. In generic code use `.get()` from the `Access` trait.
+///
+/// # Syntax Reference
+///
+/// | Pattern | Description |
+/// |---------|-------------|
+/// | `select!(mod: field)` | field is Present (required) |
+/// | `select!(mod: field?)` | field is Optional |
+/// | `select!(mod: field-)` | field is Absent |
+/// | `select!(mod: field~)` | field is Patch (missing, null, or value) |
+/// | `select!(mod: field<P>)` | field has custom presence type P |
+/// | `select!(mod: all)` | all fields Present |
+/// | `select!(mod: all?)` | all fields Optional |
+/// | `select!(mod: all-)` | all fields Absent |
+/// | `select!(mod: all~)` | all fields Patch |
+/// | `select!(mod: f1, f2?)` | multiple fields (f1=Present, f2=Optional) |
+/// | `select!(mod: f, ..F)` | spread: f=Present, rest inherited from F |
+/// | `select!(mod: f?, ..F)` | spread: f=Optional, rest inherited from F |
+/// | `select!(mod: f, ..F1, ..F2)` | rest from `Merge<F1, F2>` (later spreads win) |
+/// | `select!(mod: f, all-)` | f=Present, all others Absent |
+/// | `select!(mod: all, f-)` | every field but f Present |
+/// | `select!(mod: Pick<F>, f1, f2)` | only f1 and f2, with their presence in F |
+/// | `select!(mod: Omit<F>, f)` | F with f made Absent |
+/// | `select!(mod: f, nested { g })` | the whole struct type, with `nested`'s parameter selected |
+///
+/// Explicit fields always override `all` and spreads, whatever their order. Field names are
+/// checked against the schema: unknown and repeated fields are compile errors.
+///
+/// The schema comes from the schema module's hidden `__select!` macro, visible within the crate
+/// that defines it. Schemas used from other crates need `#[structural(export = "name")]`, which
+/// exports the macro from the crate root under `name`.
+///
+/// # Nested Selections
+///
+/// A field whose type takes one of the struct's type parameters (like `project: Project<P>`)
+/// accepts a nested selection in braces. The macro then expands to the whole struct type rather
+/// than a field set: `select!(todo: title, project { id, name })` is
+/// `Todo<select!(todo: title, project), select!(project: id, name)>`. Other type parameters take
+/// their defaults. The nested schema module is `#[nested(module: ...)]` when given, otherwise the
+/// field type's name in snake case.
+///
+/// Each schema module's `ops` module also provides `Partial<F>` (Present → Optional),
+/// `Required<F>` (Optional and Patch → Present), `Intersect<F1, F2>` and `Difference<F1, F2>`.
+///
+/// # Examples
+///
+/// ```ignore
+/// // Concrete types for API boundaries
+/// type Create = select!(user: name, email);      // Both required
+/// type Update = select!(user: name?, email?);    // Both optional
+/// type Patch = select!(user: name~, email~);      // Missing keeps, null clears
+///
+/// // Generic: add id field to any existing fields
+/// type WithId<F: user::Fields> = select!(user: id, ..F);
+///
+/// // Derived from other selections
+/// type Edit = user::ops::Partial<Create>;           // name?, email?
+/// type Contact = select!(user: Pick<WithId<Create>>, id, email);
+/// type Anonymous = select!(user: Omit<Create>, name);
+///
+/// // Everything except a field, and a nested selection
+/// type Public = select!(user: all, email-);
+/// type TodoWithProject = select!(todo: title, project { id, name });
+///
+/// // Function using concrete type
+/// fn create(user: User<Create>) -> User<WithId<Create>> { ... }
+/// ```
+#[proc_macro]
+pub fn select(input: TokenStream) -> TokenStream {
+    match select::forward(input.into()) {
         Ok(tokens) => tokens.into(),
         Err(err) => err.to_compile_error().into(),
     }
 }
+
+/// Resolves a `select!` invocation against the schema description from its `__select!` macro.
+#[doc(hidden)]
+#[proc_macro]
+pub fn select_with_schema(input: TokenStream) -> TokenStream {
+    match select::resolve(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => select::error_type(err).into(),
+    }
+}
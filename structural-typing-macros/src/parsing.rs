@@ -60,6 +60,104 @@ fn parse_nested_attribute(attrs: &[Attribute]) -> syn::Result<Option<NestedField
     Ok(None)
 }
 
+/// What an Optional field holding `None` serializes as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerializeNone {
+    Omit,
+    Null,
+}
+
+fn parse_serialize_none(
+    meta: &syn::meta::ParseNestedMeta,
+    current: Option<SerializeNone>,
+) -> syn::Result<SerializeNone> {
+    if current.is_some() {
+        return Err(meta.error("duplicate `serialize_none` option"));
+    }
+    let value: syn::LitStr = meta.value()?.parse()?;
+    match value.value().as_str() {
+        "omit" => Ok(SerializeNone::Omit),
+        "null" => Ok(SerializeNone::Null),
+        _ => Err(syn::Error::new_spanned(
+            value,
+            "expected `serialize_none = \"omit\"` or `serialize_none = \"null\"`",
+        )),
+    }
+}
+
+/// Options given to the attribute itself, as in `#[structural(deny_absent_input)]`.
+#[derive(Debug, Default)]
+pub struct StructOptions {
+    /// Reject input for Absent fields when deserializing; holds the keyword for error spans.
+    pub deny_absent_input: Option<Ident>,
+    /// Default for fields without their own `serialize_none`.
+    pub serialize_none: Option<SerializeNone>,
+    /// Generate the compact encoding; holds the keyword for error spans.
+    pub compact: Option<Ident>,
+    /// Generate TypeScript declarations; holds the keyword for error spans.
+    pub ts: Option<Ident>,
+    /// Generate the borrowed views and `as_ref`/`as_mut`; holds the keyword for error spans.
+    pub views: Option<Ident>,
+    /// Type aliases of selections whose OpenAPI components are named after the alias.
+    pub components: Vec<syn::Path>,
+    /// Crate-root name of the exported `__select!` macro, for `select!` from other crates.
+    pub export: Option<Ident>,
+}
+
+pub fn parse_options(attr: proc_macro2::TokenStream) -> syn::Result<StructOptions> {
+    let mut options = StructOptions::default();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("deny_absent_input") {
+            if options.deny_absent_input.is_some() {
+                return Err(meta.error("duplicate `deny_absent_input` option"));
+            }
+            options.deny_absent_input = meta.path.get_ident().cloned();
+            Ok(())
+        } else if meta.path.is_ident("serialize_none") {
+            options.serialize_none = Some(parse_serialize_none(&meta, options.serialize_none)?);
+            Ok(())
+        } else if meta.path.is_ident("compact") {
+            if options.compact.is_some() {
+                return Err(meta.error("duplicate `compact` option"));
+            }
+            options.compact = meta.path.get_ident().cloned();
+            Ok(())
+        } else if meta.path.is_ident("ts") {
+            if options.ts.is_some() {
+                return Err(meta.error("duplicate `ts` option"));
+            }
+            options.ts = meta.path.get_ident().cloned();
+            Ok(())
+        } else if meta.path.is_ident("views") {
+            if options.views.is_some() {
+                return Err(meta.error("duplicate `views` option"));
+            }
+            options.views = meta.path.get_ident().cloned();
+            Ok(())
+        } else if meta.path.is_ident("export") {
+            if options.export.is_some() {
+                return Err(meta.error("duplicate `export` option"));
+            }
+            let name: syn::LitStr = meta.value()?.parse()?;
+            options.export = Some(name.parse().map_err(|_| {
+                syn::Error::new_spanned(&name, "expected `export = \"name\"` with a macro name")
+            })?);
+            Ok(())
+        } else if meta.path.is_ident("components") {
+            meta.parse_nested_meta(|alias| {
+                options.components.push(alias.path);
+                Ok(())
+            })
+        } else {
+            Err(meta.error(
+                "unknown #[structural] option; expected `deny_absent_input`, `serialize_none`, `compact`, `ts`, `views`, `components` or `export`",
+            ))
+        }
+    });
+    syn::parse::Parser::parse2(parser, attr)?;
+    Ok(options)
+}
+
 #[derive(Debug)]
 pub struct StructInfo {
     pub name: Ident,
@@ -70,6 +168,43 @@ pub struct StructInfo {
     pub other_attrs: Vec<Attribute>,
     pub generics: Generics,
     pub single_field_generics: HashSet<String>,
+    pub options: StructOptions,
+}
+
+/// Options given to a field, as in `#[structural(deserialize_option)]`.
+#[derive(Debug, Clone, Default)]
+pub struct FieldOptions {
+    /// The field's `deserialize_with` function produces `Option<T>` rather than `T`; holds the
+    /// keyword for error spans.
+    pub deserialize_option: Option<Ident>,
+    /// Overrides the struct's `serialize_none` for this field.
+    pub serialize_none: Option<SerializeNone>,
+}
+
+fn parse_field_options(attrs: &[Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("structural"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("deserialize_option") {
+                if options.deserialize_option.is_some() {
+                    return Err(meta.error("duplicate `deserialize_option` option"));
+                }
+                options.deserialize_option = meta.path.get_ident().cloned();
+                Ok(())
+            } else if meta.path.is_ident("serialize_none") {
+                options.serialize_none = Some(parse_serialize_none(&meta, options.serialize_none)?);
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unknown #[structural] field option; expected `deserialize_option` or `serialize_none`",
+                ))
+            }
+        })?;
+    }
+    Ok(options)
 }
 
 #[derive(Debug, Clone)]
@@ -79,9 +214,10 @@ pub struct FieldInfo {
     pub vis: Visibility,
     pub attrs: Vec<Attribute>,
     pub nested_fields: Option<NestedFieldsInfo>,
+    pub options: FieldOptions,
 }
 
-pub fn parse_struct(input: DeriveInput) -> syn::Result<StructInfo> {
+pub fn parse_struct(input: DeriveInput, options: StructOptions) -> syn::Result<StructInfo> {
     let name = input.ident.clone();
     let span = input.span();
 
@@ -108,12 +244,14 @@ pub fn parse_struct(input: DeriveInput) -> syn::Result<StructInfo> {
             let vis = field.vis;
             let attrs = field.attrs;
             let nested_fields = parse_nested_attribute(&attrs)?;
+            let options = parse_field_options(&attrs)?;
             Ok(FieldInfo {
                 name,
                 ty,
                 vis,
                 attrs,
                 nested_fields,
+                options,
             })
         })
         .collect();
@@ -159,6 +297,7 @@ pub fn parse_struct(input: DeriveInput) -> syn::Result<StructInfo> {
         other_attrs,
         generics: input.generics,
         single_field_generics,
+        options,
     })
 }
 
@@ -0,0 +1,589 @@
+//! Expansion of `select!`.
+//!
+//! `select!(path: ...)` cannot see the fields of the schema behind `path`, so it expands to
+//! `path::__select!(path: ...)`, a `macro_rules!` generated by `#[structural]` that prepends a
+//! description of the schema and calls `select_with_schema!`, which validates and resolves the
+//! selection.
+
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, Lifetime, Path, PathSegment, Token, Type, braced, token};
+
+/// First stage: forwards the selection to the schema's `__select!` macro.
+pub fn forward(input: TokenStream) -> syn::Result<TokenStream> {
+    let tokens = input.clone();
+    let module = syn::parse::Parser::parse2(
+        |stream: ParseStream| {
+            let module = Path::parse_mod_style(stream)?;
+            if !stream.peek(Token![:]) || stream.peek(Token![::]) {
+                return Err(stream.error(
+                    "expected `:` after the schema module, e.g. `select!(user: id, name)`",
+                ));
+            }
+            stream.parse::<TokenStream>()?;
+            Ok(module)
+        },
+        input,
+    )?;
+
+    Ok(quote! { #module::__select! { #tokens } })
+}
+
+/// Second stage: resolves a selection against the schema description.
+pub fn resolve(input: TokenStream) -> syn::Result<TokenStream> {
+    let SchemaSelection { schema, selection } = syn::parse2(input)?;
+    let resolver = Resolver::new(&schema, &selection.module);
+    let resolved = resolver.resolve(&selection.items)?;
+
+    if resolved.nested.is_empty() {
+        return Ok(resolved.fields);
+    }
+    resolved.full_type(&schema, &selection.module, resolver.span)
+}
+
+/// Turns resolution errors into a type, so that every error is reported from type position.
+pub fn error_type(error: syn::Error) -> TokenStream {
+    if error.clone().into_iter().count() == 1 {
+        return error.to_compile_error();
+    }
+    let errors = error.into_iter().map(|error| error.to_compile_error());
+    quote! { [(); { #(#errors)* 0 }] }
+}
+
+/// Description of a schema, generated by `#[structural]` into its `__select!` macro.
+///
+/// ```text
+/// struct Todo; fields [id, title, project]; params ['a, Self, P in project: project = __default_P];
+/// ```
+struct Schema {
+    name: Ident,
+    fields: Vec<Ident>,
+    params: Vec<SchemaParam>,
+}
+
+enum SchemaParam {
+    Lifetime,
+    /// The field set parameter that `select!` computes.
+    Fields,
+    Type {
+        ident: Ident,
+        nested: Option<(Ident, Path)>,
+        default: Option<Ident>,
+    },
+    Const(Ident),
+}
+
+impl Parse for Schema {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![struct]>()?;
+        let name = input.parse()?;
+        input.parse::<Token![;]>()?;
+
+        keyword(input, "fields")?;
+        let content;
+        syn::bracketed!(content in input);
+        let fields = content
+            .parse_terminated(Ident::parse_any, Token![,])?
+            .into_iter()
+            .collect();
+        input.parse::<Token![;]>()?;
+
+        keyword(input, "params")?;
+        let content;
+        syn::bracketed!(content in input);
+        let params = content
+            .parse_terminated(SchemaParam::parse, Token![,])?
+            .into_iter()
+            .collect();
+        input.parse::<Token![;]>()?;
+
+        Ok(Schema {
+            name,
+            fields,
+            params,
+        })
+    }
+}
+
+impl Parse for SchemaParam {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Lifetime) {
+            input.parse::<Lifetime>()?;
+            return Ok(SchemaParam::Lifetime);
+        }
+        if input.peek(Token![Self]) {
+            input.parse::<Token![Self]>()?;
+            return Ok(SchemaParam::Fields);
+        }
+        if input.parse::<Option<Token![const]>>()?.is_some() {
+            return Ok(SchemaParam::Const(input.parse()?));
+        }
+
+        let ident = input.parse()?;
+        let nested = if input.parse::<Option<Token![in]>>()?.is_some() {
+            let field = input.call(Ident::parse_any)?;
+            input.parse::<Token![:]>()?;
+            Some((field, Path::parse_mod_style(input)?))
+        } else {
+            None
+        };
+        let default = if input.parse::<Option<Token![=]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(SchemaParam::Type {
+            ident,
+            nested,
+            default,
+        })
+    }
+}
+
+fn keyword(input: ParseStream, expected: &str) -> syn::Result<()> {
+    let ident = input.call(Ident::parse_any)?;
+    if ident == expected {
+        Ok(())
+    } else {
+        Err(syn::Error::new(
+            ident.span(),
+            format!("expected `{expected}`"),
+        ))
+    }
+}
+
+struct SchemaSelection {
+    schema: Schema,
+    selection: Selection,
+}
+
+impl Parse for SchemaSelection {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(SchemaSelection {
+            schema: input.parse()?,
+            selection: input.parse()?,
+        })
+    }
+}
+
+/// `path: item, item, ...`
+struct Selection {
+    module: Path,
+    items: Vec<Item>,
+}
+
+impl Parse for Selection {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let module = Path::parse_mod_style(input)?;
+        input.parse::<Token![:]>()?;
+
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            items.push(Item::parse(input, items.is_empty())?);
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(Selection { module, items })
+    }
+}
+
+enum Item {
+    Field {
+        name: Ident,
+        presence: PresenceSpec,
+        nested: Option<(token::Brace, TokenStream)>,
+    },
+    All {
+        token: Ident,
+        presence: PresenceSpec,
+    },
+    Spread(Type),
+    Pick(Ident, Type),
+    Omit(Ident, Type),
+}
+
+enum PresenceSpec {
+    Present,
+    Optional,
+    Absent,
+    Patch,
+    Custom(Box<Type>),
+}
+
+impl Item {
+    fn parse(input: ParseStream, first: bool) -> syn::Result<Self> {
+        if input.parse::<Option<Token![..]>>()?.is_some() {
+            return Ok(Item::Spread(input.parse()?));
+        }
+        if !input.peek(Ident) {
+            return Err(
+                input.error("expected a field name, `all`, `..Spread`, `Pick<F>` or `Omit<F>`")
+            );
+        }
+
+        let name: Ident = input.parse()?;
+        if first && (name == "Pick" || name == "Omit") && input.peek(Token![<]) {
+            input.parse::<Token![<]>()?;
+            let ty = input.parse()?;
+            input.parse::<Token![>]>()?;
+            return Ok(if name == "Pick" {
+                Item::Pick(name, ty)
+            } else {
+                Item::Omit(name, ty)
+            });
+        }
+
+        let presence = PresenceSpec::parse(input)?;
+        if name == "all" {
+            return Ok(Item::All {
+                token: name,
+                presence,
+            });
+        }
+
+        let nested = if input.peek(token::Brace) {
+            let content;
+            let brace = braced!(content in input);
+            Some((brace, content.parse()?))
+        } else {
+            None
+        };
+        Ok(Item::Field {
+            name,
+            presence,
+            nested,
+        })
+    }
+}
+
+impl Parse for PresenceSpec {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.parse::<Option<Token![?]>>()?.is_some() {
+            Ok(PresenceSpec::Optional)
+        } else if input.parse::<Option<Token![-]>>()?.is_some() {
+            Ok(PresenceSpec::Absent)
+        } else if input.parse::<Option<Token![~]>>()?.is_some() {
+            Ok(PresenceSpec::Patch)
+        } else if input.parse::<Option<Token![<]>>()?.is_some() {
+            let ty = input.parse()?;
+            input.parse::<Token![>]>()?;
+            Ok(PresenceSpec::Custom(ty))
+        } else {
+            Ok(PresenceSpec::Present)
+        }
+    }
+}
+
+impl ToTokens for PresenceSpec {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            PresenceSpec::Present => quote! { ::structural_typing::presence::Present },
+            PresenceSpec::Optional => quote! { ::structural_typing::presence::Optional },
+            PresenceSpec::Absent => quote! { ::structural_typing::presence::Absent },
+            PresenceSpec::Patch => quote! { ::structural_typing::presence::Patch },
+            PresenceSpec::Custom(ty) => ty.to_token_stream(),
+        });
+    }
+}
+
+struct Resolver<'a> {
+    schema: &'a Schema,
+    module: &'a Path,
+    /// Span of the schema module in the user's `select!`, for errors and the expansion.
+    span: Span,
+}
+
+/// A resolved selection: the field set, plus the nested selections made with `field { ... }`.
+struct Resolved {
+    fields: TokenStream,
+    nested: HashMap<String, TokenStream>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(schema: &'a Schema, module: &'a Path) -> Self {
+        let span = module
+            .segments
+            .first()
+            .map_or_else(Span::call_site, |segment| segment.ident.span());
+        Self {
+            schema,
+            module,
+            span,
+        }
+    }
+
+    fn resolve(&self, items: &[Item]) -> syn::Result<Resolved> {
+        let module = self.module;
+        match items.first() {
+            None => Err(syn::Error::new(
+                self.span,
+                "select! requires at least one field",
+            )),
+            Some(Item::Pick(name, ty) | Item::Omit(name, ty)) => {
+                if items.len() == 1 {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!("`{name}<F>` must be followed by at least one field"),
+                    ));
+                }
+                let rest = self.resolve(&items[1..])?;
+                if !rest.nested.is_empty() {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!("nested selections cannot be combined with `{name}<F>`"),
+                    ));
+                }
+                let alias = if name == "Pick" {
+                    quote! { Intersect }
+                } else {
+                    quote! { Difference }
+                };
+                let rest = rest.fields;
+                Ok(Resolved {
+                    fields: quote_spanned! {self.span=> #module::ops::#alias<#ty, #rest> },
+                    nested: HashMap::new(),
+                })
+            }
+            Some(_) => self.resolve_fields(items),
+        }
+    }
+
+    fn resolve_fields(&self, items: &[Item]) -> syn::Result<Resolved> {
+        let mut errors: Option<syn::Error> = None;
+        let mut push_error = |error: syn::Error| match &mut errors {
+            Some(errors) => errors.combine(error),
+            None => errors = Some(error),
+        };
+
+        let known: Vec<String> = self
+            .schema
+            .fields
+            .iter()
+            .map(|field| field.unraw().to_string())
+            .collect();
+
+        let mut explicit: HashMap<String, &PresenceSpec> = HashMap::new();
+        let mut nested = HashMap::new();
+        let mut bases = Vec::new();
+        let mut seen_all = false;
+
+        for item in items {
+            match item {
+                Item::Field {
+                    name,
+                    presence,
+                    nested: nested_selection,
+                } => {
+                    let key = name.unraw().to_string();
+                    if !known.contains(&key) {
+                        push_error(syn::Error::new(
+                            name.span(),
+                            format!(
+                                "`{}` has no field `{key}`; expected one of {}",
+                                self.schema.name,
+                                known
+                                    .iter()
+                                    .map(|field| format!("`{field}`"))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        ));
+                        continue;
+                    }
+                    if explicit.insert(key.clone(), presence).is_some() {
+                        push_error(syn::Error::new(
+                            name.span(),
+                            format!("field `{key}` is selected more than once"),
+                        ));
+                        continue;
+                    }
+                    if let Some((brace, selection)) = nested_selection {
+                        if self.nested_param(&key).is_none() {
+                            push_error(syn::Error::new(
+                                brace.span.join(),
+                                format!(
+                                    "`{key}` is not a nested schema; `{{ ... }}` needs a field whose type \
+                                     takes one of `{}`'s type parameters, like `{key}: Inner<I>`",
+                                    self.schema.name
+                                ),
+                            ));
+                            continue;
+                        }
+                        nested.insert(key, selection.clone());
+                    }
+                }
+                Item::All { token, presence } => {
+                    if seen_all {
+                        push_error(syn::Error::new(
+                            token.span(),
+                            "`all` is selected more than once",
+                        ));
+                    }
+                    seen_all = true;
+                    bases.push(Base::All(presence));
+                }
+                Item::Spread(ty) => bases.push(Base::Spread(ty)),
+                Item::Pick(name, _) | Item::Omit(name, _) => push_error(syn::Error::new(
+                    name.span(),
+                    format!("`{name}<F>` must come first in select!"),
+                )),
+            }
+        }
+
+        if let Some(errors) = errors {
+            return Err(errors);
+        }
+
+        let module = self.module;
+        let presences = self.schema.fields.iter().zip(&known).map(|(field, key)| {
+            if let Some(presence) = explicit.get(key) {
+                return presence.to_token_stream();
+            }
+            let mut base_presences = bases.iter().map(|base| match base {
+                Base::All(presence) => presence.to_token_stream(),
+                Base::Spread(ty) => quote! { <#ty as #module::Fields>::#field },
+            });
+            let Some(first) = base_presences.next() else {
+                return PresenceSpec::Absent.to_token_stream();
+            };
+            base_presences.fold(first, |merged, later| {
+                quote! { <#later as ::structural_typing::presence::Presence>::Or<#merged> }
+            })
+        });
+
+        Ok(Resolved {
+            fields: quote_spanned! {self.span=> #module::FieldSet<#(#presences),*> },
+            nested,
+        })
+    }
+
+    fn nested_param(&self, field: &str) -> Option<&Path> {
+        self.schema.params.iter().find_map(|param| match param {
+            SchemaParam::Type {
+                nested: Some((nested_field, path)),
+                ..
+            } if nested_field.unraw() == field => Some(path),
+            _ => None,
+        })
+    }
+}
+
+enum Base<'a> {
+    All(&'a PresenceSpec),
+    Spread(&'a Type),
+}
+
+impl Resolved {
+    /// Spells out the whole struct type, filling nested parameters from their selections and every
+    /// other parameter from its default.
+    fn full_type(self, schema: &Schema, module: &Path, span: Span) -> syn::Result<TokenStream> {
+        let prefix = parent_path(module);
+        let name = &schema.name;
+        let fields = self.fields;
+
+        let mut args = Vec::new();
+        for param in &schema.params {
+            args.push(match param {
+                SchemaParam::Lifetime => quote! { '_ },
+                SchemaParam::Fields => fields.clone(),
+                SchemaParam::Type {
+                    ident,
+                    nested,
+                    default,
+                } => {
+                    let selection = nested.as_ref().and_then(|(field, path)| {
+                        self.nested
+                            .get(&field.unraw().to_string())
+                            .map(|selection| (path, selection))
+                    });
+                    match (selection, default) {
+                        (Some((path, selection)), _) => {
+                            let path = relative_to(module, path);
+                            quote! { ::structural_typing::select!(#path: #selection) }
+                        }
+                        (None, Some(default)) => quote! { #module::#default },
+                        (None, None) => {
+                            return Err(syn::Error::new(
+                                span,
+                                format!(
+                                    "select! cannot fill the type parameter `{ident}` of `{name}`; \
+                                     select it with `field {{ ... }}` or give it a default"
+                                ),
+                            ));
+                        }
+                    }
+                }
+                SchemaParam::Const(ident) => {
+                    return Err(syn::Error::new(
+                        span,
+                        format!(
+                            "select! cannot fill the const parameter `{ident}` of `{name}`; \
+                             write the struct type out instead"
+                        ),
+                    ));
+                }
+            });
+        }
+
+        Ok(quote_spanned! {span=> #prefix #name<#(#args),*> })
+    }
+}
+
+/// The path of the module containing the schema module, with a trailing `::`, or nothing.
+fn parent_path(module: &Path) -> TokenStream {
+    let leading = &module.leading_colon;
+    let segments: Vec<_> = module.segments.iter().collect();
+    let parents = &segments[..segments.len() - 1];
+    if parents.is_empty() && leading.is_none() {
+        quote! {}
+    } else {
+        quote! { #leading #(#parents::)* }
+    }
+}
+
+/// Resolves a nested schema path, written relative to the struct definition, from the call site.
+///
+/// Only paths starting with `::` or `$crate` (which `#[structural]` writes for `crate`) are
+/// absolute. Any other path starts in the module containing `module`, and each leading `super`
+/// steps out of it.
+fn relative_to(module: &Path, path: &Path) -> TokenStream {
+    let absolute = path.leading_colon.is_some()
+        || path
+            .segments
+            .first()
+            .is_some_and(|segment| segment.ident == "$crate");
+    if absolute {
+        return path.to_token_stream();
+    }
+
+    let mut resolved: Vec<&PathSegment> = module.segments.iter().collect();
+    resolved.pop();
+    let mut segments = path.segments.iter().peekable();
+    while let Some(segment) =
+        segments.next_if(|segment| segment.ident == "self" || segment.ident == "super")
+    {
+        if segment.ident == "self" {
+            continue;
+        }
+        match resolved.last() {
+            Some(last)
+                if !["self", "super", "crate", "$crate"]
+                    .iter()
+                    .any(|keyword| last.ident == keyword) =>
+            {
+                resolved.pop();
+            }
+            _ => resolved.push(segment),
+        }
+    }
+    resolved.extend(segments);
+
+    let leading = &module.leading_colon;
+    quote! { #leading #(#resolved)::* }
+}
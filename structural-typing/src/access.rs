@@ -1,51 +1,97 @@
 //! Runtime field access for structural types.
 use core::marker::PhantomData;
 
-use crate::presence::InferPresence;
-
-/// Uniform field access for Present, Optional, or Absent.
-pub trait Access<T>: InferPresence<T> {
+use crate::presence::PatchValue;
+
+/// Uniform field access for Present, Optional, Absent, Patch, or custom containers.
+///
+/// Besides reading fields, this is the runtime half of the presence algebra: converting a
+/// container to and from `Option<T>` is all `extract`, `try_extract` and `merge` need. Field types
+/// must be `Sized`, since containers hand back an owned `T`; store unsized data in `Box<str>` or
+/// `Box<[u8]>` fields instead.
+pub trait Access<T>: Sized {
     /// Compile-time constant indicating if this field is absent.
     const IS_ABSENT: bool;
 
-    /// Remainder type when extracting Self from Source.
-    type RemainderFrom<Source: Access<T>>: Access<T>;
-
     /// Get field reference if present.
     fn get(&self) -> Option<&T>;
     /// Get mutable field reference if present.
     fn get_mut(&mut self) -> Option<&mut T>;
+    /// Whether this value is absent at runtime.
+    ///
+    /// Always true for Absent fields, and true for Patch fields holding `PatchValue::Missing`.
+    #[inline]
+    fn is_absent(&self) -> bool {
+        Self::IS_ABSENT
+    }
+    /// Whether this value is an explicit `null` that serialization must keep.
+    ///
+    /// Only true for Patch fields holding `PatchValue::Null`.
+    #[inline]
+    fn is_explicit_null(&self) -> bool {
+        false
+    }
+    /// Take the value out, or hand the container back if it holds none.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the container unchanged if it holds no value.
+    fn into_value(self) -> Result<T, Self>;
     /// Consume and convert to Option.
-    fn into_option(self) -> Option<T>;
-
-    /// Construct from a value.
-    fn from_value(value: T) -> (Self, Self::RemainderFrom<T>)
-    where
-        Self: Sized;
+    #[inline]
+    fn into_option(self) -> Option<T> {
+        self.into_value().ok()
+    }
 
+    /// Construct a container holding `value`. Every container can hold a value, so this cannot
+    /// fail; Absent containers drop it.
+    fn from_value(value: T) -> Self;
     /// Try to construct from an option.
     ///
+    /// The default accepts only `Some`, through [`Access::from_value`]; containers that can
+    /// represent a missing value override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the original `Option<T>` if this container cannot represent it
+    /// (e.g., `None` for a Present field).
+    #[inline]
+    fn from_option(value: Option<T>) -> Result<Self, Option<T>> {
+        value.map(Self::from_value).ok_or(None)
+    }
+
+    /// Try to construct from another container.
+    ///
+    /// The default goes through [`Access::into_value`] and [`Access::from_option`]; override it to
+    /// keep information an `Option<T>` cannot carry, as `PatchValue` does for missing versus null.
+    ///
     /// # Errors
     ///
-    /// Returns `Err` with the original `Option<T>` if the conversion cannot be performed.
-    #[allow(clippy::type_complexity)]
-    fn try_from_option(
-        value: Option<T>,
-    ) -> Result<(Self, Self::RemainderFrom<Option<T>>), Option<T>>
-    where
-        Self: Sized;
+    /// Returns `Err` with the original container if it holds no value and this container cannot
+    /// represent a missing one.
+    #[inline]
+    fn from_access<S: Access<T>>(source: S) -> Result<Self, S> {
+        match source.into_value() {
+            Ok(value) => Ok(Self::from_value(value)),
+            Err(source) => Self::from_option(None).map_err(|_| source),
+        }
+    }
 }
 
 /// Helper function to check if a field is absent (used by serde).
-pub fn is_absent<A: Access<T>, T>(_value: &A) -> bool {
-    A::IS_ABSENT
+pub fn is_absent<A: Access<T>, T>(value: &A) -> bool {
+    value.is_absent()
+}
+
+/// Helper function to check if a field is absent or holds no value other than an explicit `null`
+/// (used by serde with `serialize_none = "omit"`).
+pub fn is_absent_or_none<A: Access<T>, T>(value: &A) -> bool {
+    value.get().is_none() && !value.is_explicit_null()
 }
 
 impl<T> Access<T> for PhantomData<T> {
     const IS_ABSENT: bool = true;
 
-    type RemainderFrom<Source: Access<T>> = Source;
-
     #[inline]
     fn get(&self) -> Option<&T> {
         None
@@ -55,28 +101,23 @@ impl<T> Access<T> for PhantomData<T> {
         None
     }
     #[inline]
-    fn into_option(self) -> Option<T> {
-        None
+    fn into_value(self) -> Result<T, Self> {
+        Err(self)
     }
 
     #[inline]
-    fn from_value(value: T) -> (Self, Self::RemainderFrom<T>) {
-        (PhantomData, value)
+    fn from_value(_value: T) -> Self {
+        PhantomData
     }
-
     #[inline]
-    fn try_from_option(
-        value: Option<T>,
-    ) -> Result<(Self, Self::RemainderFrom<Option<T>>), Option<T>> {
-        Ok((PhantomData, value))
+    fn from_option(_value: Option<T>) -> Result<Self, Option<T>> {
+        Ok(PhantomData)
     }
 }
 
 impl<T: Sized> Access<T> for T {
     const IS_ABSENT: bool = false;
 
-    type RemainderFrom<Source: Access<T>> = PhantomData<T>;
-
     #[inline]
     fn get(&self) -> Option<&T> {
         Some(self)
@@ -86,31 +127,19 @@ impl<T: Sized> Access<T> for T {
         Some(self)
     }
     #[inline]
-    fn into_option(self) -> Option<T> {
-        Some(self)
+    fn into_value(self) -> Result<T, Self> {
+        Ok(self)
     }
 
     #[inline]
-    fn from_value(value: T) -> (Self, Self::RemainderFrom<T>) {
-        (value, PhantomData)
-    }
-
-    #[inline]
-    fn try_from_option(
-        value: Option<T>,
-    ) -> Result<(Self, Self::RemainderFrom<Option<T>>), Option<T>> {
-        match value {
-            Some(v) => Ok((v, PhantomData)),
-            None => Err(None),
-        }
+    fn from_value(value: T) -> Self {
+        value
     }
 }
 
 impl<T> Access<T> for Option<T> {
     const IS_ABSENT: bool = false;
 
-    type RemainderFrom<Source: Access<T>> = PhantomData<T>;
-
     #[inline]
     fn get(&self) -> Option<&T> {
         self.as_ref()
@@ -120,19 +149,84 @@ impl<T> Access<T> for Option<T> {
         self.as_mut()
     }
     #[inline]
+    fn into_value(self) -> Result<T, Self> {
+        self.ok_or(None)
+    }
+    #[inline]
     fn into_option(self) -> Option<T> {
         self
     }
 
     #[inline]
-    fn from_value(value: T) -> (Self, Self::RemainderFrom<T>) {
-        (Some(value), PhantomData)
+    fn from_value(value: T) -> Self {
+        Some(value)
+    }
+    #[inline]
+    fn from_option(value: Option<T>) -> Result<Self, Option<T>> {
+        Ok(value)
     }
+}
+
+impl<T> Access<T> for PatchValue<T> {
+    const IS_ABSENT: bool = false;
 
     #[inline]
-    fn try_from_option(
-        value: Option<T>,
-    ) -> Result<(Self, Self::RemainderFrom<Option<T>>), Option<T>> {
-        Ok((value, PhantomData))
+    fn get(&self) -> Option<&T> {
+        match self {
+            PatchValue::Value(value) => Some(value),
+            PatchValue::Missing | PatchValue::Null => None,
+        }
+    }
+    #[inline]
+    fn get_mut(&mut self) -> Option<&mut T> {
+        match self {
+            PatchValue::Value(value) => Some(value),
+            PatchValue::Missing | PatchValue::Null => None,
+        }
+    }
+    #[inline]
+    fn is_absent(&self) -> bool {
+        self.is_missing()
+    }
+    #[inline]
+    fn is_explicit_null(&self) -> bool {
+        self.is_null()
+    }
+    #[inline]
+    fn into_value(self) -> Result<T, Self> {
+        match self {
+            PatchValue::Value(value) => Ok(value),
+            patch @ (PatchValue::Missing | PatchValue::Null) => Err(patch),
+        }
+    }
+
+    #[inline]
+    fn from_value(value: T) -> Self {
+        PatchValue::Value(value)
+    }
+    #[inline]
+    fn from_option(value: Option<T>) -> Result<Self, Option<T>> {
+        Ok(match value {
+            Some(value) => PatchValue::Value(value),
+            None => PatchValue::Null,
+        })
+    }
+
+    #[inline]
+    fn from_access<S: Access<T>>(source: S) -> Result<Self, S> {
+        Ok(patch_from(source))
+    }
+}
+
+/// Convert any container to a `PatchValue`, keeping a missing value apart from an explicit `null`.
+///
+/// Only an explicit `null` becomes `PatchValue::Null`; an Optional `None` is Missing, so an unset
+/// field does not turn into one that clears the value when the patch is applied.
+#[inline]
+pub(crate) fn patch_from<S: Access<T>, T>(source: S) -> PatchValue<T> {
+    match source.into_value() {
+        Ok(value) => PatchValue::Value(value),
+        Err(source) if source.is_explicit_null() => PatchValue::Null,
+        Err(_) => PatchValue::Missing,
     }
 }
@@ -0,0 +1,299 @@
+//! axum extractors that report which fields of a request were rejected.
+//!
+//! [`StructuralJson`](crate::axum::StructuralJson),
+//! [`StructuralQuery`](crate::axum::StructuralQuery) and
+//! [`StructuralPath`](crate::axum::StructuralPath) deserialize a structural type like axum's
+//! `Json`, `Query` and `Path`, but reject the request with a JSON body listing the fields that
+//! failed instead of a plain-text message:
+//!
+//! ```json
+//! {
+//!   "message": "missing fields `name`, `email`",
+//!   "missing": ["name", "email"],
+//!   "unexpected": [],
+//!   "invalid": []
+//! }
+//! ```
+//!
+//! `missing` and `unexpected` come from the [`FieldsError`](crate::serde::FieldsError) of the
+//! generated deserialize helper, listing every missing required field and, with
+//! `#[structural(deny_absent_input)]`, every field given although it is Absent. `invalid` holds a
+//! value of the wrong type, with the path to it; deserialization stops there, so it lists at most
+//! one field.
+//!
+//! Only the fields of the extracted type itself, and of nested fields marked `#[serde(flatten)]`,
+//! are listed in `missing` and `unexpected`. Other nested structural values are deserialized on
+//! their own, so their missing fields reach the extractor as a message only: they are reported in
+//! `invalid` under the nested field, like `author` with ``missing field `name` ``.
+
+use std::fmt;
+
+use ::axum::Json;
+use ::axum::body::Bytes;
+use ::axum::extract::{FromRequest, FromRequestParts, RawPathParams, Request};
+use ::axum::http::request::Parts;
+use ::axum::http::{HeaderMap, StatusCode, header};
+use ::axum::response::{IntoResponse, Response};
+use serde::{Deserializer, Serialize};
+
+use crate::serde::{DeserializeFields, FieldsError};
+
+/// Extracts a structural type from a JSON request body.
+///
+/// Requires `Content-Type: application/json` (or another `+json` type). Malformed JSON is
+/// rejected with `400 Bad Request`, and input not matching the type with
+/// `422 Unprocessable Entity`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StructuralJson<T>(pub T);
+
+/// Extracts a structural type from the query string.
+///
+/// Input not matching the type is rejected with `400 Bad Request`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StructuralQuery<T>(pub T);
+
+/// Extracts a structural type from the path parameters, matched by name.
+///
+/// Input not matching the type is rejected with `400 Bad Request`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StructuralPath<T>(pub T);
+
+/// A value of the wrong type, and where it is in the input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InvalidField {
+    field: String,
+    message: String,
+}
+
+impl InvalidField {
+    /// Get the path to the value, like `project.name` or `tags[1]`.
+    #[must_use]
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Get the deserializer's message.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Rejection of the structural extractors, answered with a JSON body listing the failed fields.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StructuralRejection {
+    #[serde(skip)]
+    status: StatusCode,
+    message: String,
+    missing: Vec<&'static str>,
+    unexpected: Vec<&'static str>,
+    invalid: Vec<InvalidField>,
+}
+
+impl StructuralRejection {
+    /// Rejection of a request that could not be read, so no field is listed.
+    fn request(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            invalid: Vec::new(),
+        }
+    }
+
+    fn fields(status: StatusCode, err: &FieldsError) -> Self {
+        Self {
+            status,
+            message: err.to_string(),
+            missing: err.missing().to_vec(),
+            unexpected: err.unexpected().to_vec(),
+            invalid: Vec::new(),
+        }
+    }
+
+    fn invalid_field(status: StatusCode, field: String, message: String) -> Self {
+        Self {
+            status,
+            message: format!("invalid field `{field}`: {message}"),
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            invalid: vec![InvalidField { field, message }],
+        }
+    }
+
+    /// Get the status code of the response.
+    #[must_use]
+    pub const fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Get the message summarizing the rejection.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the names of the missing required fields. Fields missing from a nested structural
+    /// value that is not flattened are reported in [`invalid`](Self::invalid) instead.
+    #[must_use]
+    pub fn missing(&self) -> &[&'static str] {
+        &self.missing
+    }
+
+    /// Get the names of the fields given in the input although they are Absent.
+    #[must_use]
+    pub fn unexpected(&self) -> &[&'static str] {
+        &self.unexpected
+    }
+
+    /// Get the values of the wrong type.
+    #[must_use]
+    pub fn invalid(&self) -> &[InvalidField] {
+        &self.invalid
+    }
+}
+
+impl fmt::Display for StructuralRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for StructuralRejection {}
+
+impl IntoResponse for StructuralRejection {
+    fn into_response(self) -> Response {
+        (self.status, Json(self)).into_response()
+    }
+}
+
+/// Deserializes `T`, rejecting with `status` when the input does not match it.
+///
+/// `is_malformed` picks out errors of the input as a whole, like a JSON syntax error, which are
+/// rejected with `400 Bad Request` without naming a field.
+fn deserialize<'de, T, D>(
+    deserializer: D,
+    status: StatusCode,
+    is_malformed: impl FnOnce(&D::Error) -> bool,
+) -> Result<T, StructuralRejection>
+where
+    T: DeserializeFields<'de>,
+    D: Deserializer<'de>,
+    D::Error: fmt::Display,
+{
+    let mut track = serde_path_to_error::Track::new();
+    let deserializer = serde_path_to_error::Deserializer::new(deserializer, &mut track);
+    match T::deserialize_fields(deserializer) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(err)) => Err(StructuralRejection::fields(status, &err)),
+        Err(err) => {
+            let path = track.path();
+            if is_malformed(&err) {
+                Err(StructuralRejection::request(
+                    StatusCode::BAD_REQUEST,
+                    err.to_string(),
+                ))
+            } else if path.iter().next().is_none() {
+                Err(StructuralRejection::request(status, err.to_string()))
+            } else {
+                Err(StructuralRejection::invalid_field(
+                    status,
+                    path.to_string(),
+                    err.to_string(),
+                ))
+            }
+        }
+    }
+}
+
+fn deserialize_urlencoded<T>(input: &str) -> Result<T, StructuralRejection>
+where
+    T: for<'de> DeserializeFields<'de>,
+{
+    let deserializer =
+        serde_urlencoded::Deserializer::new(form_urlencoded::parse(input.as_bytes()));
+    deserialize(deserializer, StatusCode::BAD_REQUEST, |_| false)
+}
+
+/// Whether the request declares a JSON body: `application/json` or `application/*+json`.
+fn json_content_type(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    essence
+        .strip_prefix("application/")
+        .is_some_and(|subtype| subtype == "json" || subtype.ends_with("+json"))
+}
+
+impl<T, S> FromRequest<S> for StructuralJson<T>
+where
+    T: for<'de> DeserializeFields<'de>,
+    S: Send + Sync,
+{
+    type Rejection = StructuralRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !json_content_type(req.headers()) {
+            return Err(StructuralRejection::request(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Expected request with `Content-Type: application/json`",
+            ));
+        }
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|err| StructuralRejection::request(err.status(), err.body_text()))?;
+
+        let mut deserializer = serde_json::Deserializer::from_slice(&bytes);
+        let value = deserialize(
+            &mut deserializer,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            |err: &serde_json::Error| !err.is_data(),
+        )?;
+        deserializer.end().map_err(|err| {
+            StructuralRejection::request(StatusCode::BAD_REQUEST, err.to_string())
+        })?;
+        Ok(Self(value))
+    }
+}
+
+impl<T, S> FromRequestParts<S> for StructuralQuery<T>
+where
+    T: for<'de> DeserializeFields<'de>,
+    S: Send + Sync,
+{
+    type Rejection = StructuralRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        deserialize_urlencoded(parts.uri.query().unwrap_or_default()).map(Self)
+    }
+}
+
+impl<T, S> FromRequestParts<S> for StructuralPath<T>
+where
+    T: for<'de> DeserializeFields<'de>,
+    S: Send + Sync,
+{
+    type Rejection = StructuralRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let params = RawPathParams::from_request_parts(parts, state)
+            .await
+            .map_err(|err| StructuralRejection::request(err.status(), err.body_text()))?;
+        // Encoded as a query string so that values are parsed into numbers and booleans.
+        let mut encoded = form_urlencoded::Serializer::new(String::new());
+        for (key, value) in &params {
+            encoded.append_pair(key, value);
+        }
+        deserialize_urlencoded(&encoded.finish()).map(Self)
+    }
+}
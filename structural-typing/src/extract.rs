@@ -2,93 +2,67 @@
 //!
 //! Use `extract()` when extraction always succeeds (e.g., `Present` → `Optional`).
 //! Use `try_extract()` when extraction may fail (e.g., `Optional` → `Present` can fail if `None`).
+//!
+//! Both traits are implemented for every pair of [`Presence`](crate::presence::Presence) markers,
+//! so custom markers take part without any impls of their own.
 
-use core::marker::PhantomData;
-
-use crate::access::Access;
+use crate::presence::{Bool, Extracted, Presence, True};
 
-/// Trait for compile-time checked extraction from one access type to another.
+/// Trait for compile-time checked extraction from one presence to another.
+///
+/// Implemented when the source always holds a value or the target can represent a missing one,
+/// as declared by [`Presence::AlwaysPresent`] and [`Presence::AcceptsMissing`].
 ///
-/// Returns both the extracted value (in target access type) and the remainder.
-pub trait Extract<A: Access<T>, T>: Access<T> + Sized + TryExtract<A, T> {
-    /// Extract a value into target type and remainder parts.
-    fn extract(self) -> (A, <A as Access<T>>::RemainderFrom<Self>);
+/// Returns both the extracted value (in the target container) and the remainder. The flags are
+/// backed by [`Presence::take`] and the target's infallible construction, so extraction never
+/// checks for a value at runtime.
+///
+/// `Proof` is computed from the markers and should be left at its default; it is `True` exactly
+/// when extraction cannot fail, so unsupported pairs are reported as a missing impl. Bounds like
+/// `S: Extract<A>` leave it out.
+#[diagnostic::on_unimplemented(
+    message = "cannot extract a `{Self}` field as `{A}` without checking for a value",
+    label = "`{Self}` may hold no value, but `{A}` requires one",
+    note = "use `try_extract` or `require` to check for the value at runtime"
+)]
+pub trait Extract<
+    A: Presence,
+    Proof: Bool = <<A as Presence>::AcceptsMissing as Bool>::Or<<Self as Presence>::AlwaysPresent>,
+>: TryExtract<A>
+{
+    /// Extract a value into target container and remainder parts.
+    fn extract<T>(value: Self::Output<T>) -> Extracted<A, Self, T>;
 }
 
 /// Trait for runtime checked extraction that may fail.
 ///
 /// Returns Ok with (extracted, remainder) on success, or Err with original value on failure.
-///
-/// Most conversions delegate to `Extract` and always succeed; only `Option<T>` → `T` can fail when the value is `None`.
-pub trait TryExtract<A: Access<T>, T>: Access<T> + Sized {
-    /// Try to extract a value into target type and remainder parts.
+pub trait TryExtract<A: Presence>: Presence + Sized {
+    /// Try to extract a value into target container and remainder parts.
     ///
     /// # Errors
     ///
     /// Returns `Err` with the original value if the extraction cannot be performed
     /// (e.g., when trying to convert `Option<T>` to `T` but the value is `None`).
-    fn try_extract(self) -> Result<(A, <A as Access<T>>::RemainderFrom<Self>), Self>;
+    fn try_extract<T>(value: Self::Output<T>) -> Result<Extracted<A, Self, T>, Self::Output<T>>;
 }
 
-impl<T, A: Access<T>> Extract<PhantomData<T>, T> for A
-where
-    Self: TryExtract<PhantomData<T>, T>,
-{
-    fn extract(
-        self,
-    ) -> (
-        PhantomData<T>,
-        <PhantomData<T> as Access<T>>::RemainderFrom<Self>,
-    ) {
-        (PhantomData, self)
+impl<S: Presence, A: Presence> TryExtract<A> for S {
+    #[inline]
+    fn try_extract<T>(value: Self::Output<T>) -> Result<Extracted<A, Self, T>, Self::Output<T>> {
+        A::split::<T, S>(value)
     }
 }
 
-impl<T, A: Access<T>> Extract<Option<T>, T> for A
+impl<S: Presence, A: Presence> Extract<A, True> for S
 where
-    Self: TryExtract<Option<T>, T>,
+    A::AcceptsMissing: Bool<Or<S::AlwaysPresent> = True>,
 {
-    fn extract(self) -> (Option<T>, <Option<T> as Access<T>>::RemainderFrom<Self>) {
-        (self.into_option(), PhantomData)
-    }
-}
-
-impl<T> Extract<T, T> for T {
-    fn extract(self) -> (T, <T as Access<T>>::RemainderFrom<Self>) {
-        (self, PhantomData)
-    }
-}
-
-impl<T, A: Access<T>> TryExtract<A, T> for Option<T> {
-    fn try_extract(self) -> Result<(A, <A as Access<T>>::RemainderFrom<Self>), Self> {
-        A::try_from_option(self)
-    }
-}
-
-impl<T, A: Access<T>> TryExtract<A, T> for T {
-    fn try_extract(self) -> Result<(A, <A as Access<T>>::RemainderFrom<Self>), Self> {
-        Ok(A::from_value(self))
-    }
-}
-
-impl<T> TryExtract<Option<T>, T> for PhantomData<T> {
-    fn try_extract(
-        self,
-    ) -> Result<(Option<T>, <Option<T> as Access<T>>::RemainderFrom<Self>), Self> {
-        Ok((None, self))
-    }
-}
-
-impl<T> TryExtract<PhantomData<T>, T> for PhantomData<T> {
-    fn try_extract(
-        self,
-    ) -> Result<
-        (
-            PhantomData<T>,
-            <PhantomData<T> as Access<T>>::RemainderFrom<Self>,
-        ),
-        Self,
-    > {
-        Ok((PhantomData, self))
+    #[inline]
+    fn extract<T>(value: Self::Output<T>) -> Extracted<A, Self, T> {
+        A::split_proven::<T, S>(
+            value,
+            <A::AcceptsMissing as Bool>::either::<S::AlwaysPresent>(()),
+        )
     }
 }
@@ -39,7 +39,8 @@
 //! ### Generic functions with field requirements
 //!
 //! Functions can require specific fields through trait bounds using the generated `Fields` trait.
-//! The `Presence` type can be `Present` (`T`), `Optional` (`Option<T>`), or `Absent` (`PhantomData<T>`):
+//! The `Presence` type can be `Present` (`T`), `Optional` (`Option<T>`), `Absent` (`PhantomData<T>`),
+//! or `Patch` (`PatchValue<T>`, which distinguishes a missing value from an explicit `null`):
 //!
 //! ```ignore
 //! # use structural_typing::{structural, presence::Present};
@@ -81,6 +82,30 @@
 //! }
 //! ```
 //!
+//! The `field` module offers the same requirements one field at a time.
+//! `F: user::field::name<Present>` is equivalent to `F: user::Fields<name = Present>`, but a caller
+//! passing the wrong presence gets "field `name` of `User` is required to be `Present` but is
+//! `Absent`" instead of an associated type mismatch. Compilers cannot customize the errors for
+//! `Fields<name = Present>` bounds, so switch to `field::` bounds wherever callers should see the
+//! readable message:
+//!
+//! ```ignore
+//! fn greet<F: user::field::id<Present> + user::field::name<Present>>(user: &User<F>) {
+//!     println!("Hello, {}!", user.name);
+//! }
+//! ```
+//!
+//! For methods, put the bound on the method's `where` clause rather than on the `impl` header:
+//! an unmet bound on the header makes the method not found, which reports the raw trait bound.
+//!
+//! ```ignore
+//! impl<F: user::Fields> User<F> {
+//!     fn greet(&self) where F: user::field::name<Present> {
+//!         println!("Hello, {}!", self.name);
+//!     }
+//! }
+//! ```
+//!
 //! ### Build instances incrementally
 //!
 //! The builder API infers field presence from the value type:
@@ -109,11 +134,21 @@
 //! let bob = bob.name("Bob".to_owned());
 //! ```
 //!
+//! `take_<field>()` removes a field and returns its value alongside the rest, and
+//! `without_<field>()` just drops it:
+//!
+//! ```ignore
+//! let (name, bob) = bob.take_name(); // bob: User<select!(user: id)>
+//! let anonymous = user::empty().id(1).name("Eve".to_owned()).without_name();
+//! ```
+//!
 //! ### Serde integration
 //!
 //! Enable the `serde` feature to use structural types with serde.
 //!
-//! The `select!` macro creates concrete types, useful for serialization boundaries:
+//! The `select!` macro creates concrete types, useful for serialization boundaries. Field names are
+//! checked against the schema, and explicit fields override `all` and `..F` spreads, so
+//! `select!(user: all, email-)` selects everything but `email`:
 //!
 //! ```ignore
 //! use serde::{Deserialize, Serialize};
@@ -142,6 +177,96 @@
 //! // => {"id":42, "name": "Alice", "email": "alice@example.com"}
 //! ```
 //!
+//! `select!` reads the schema's fields from a hidden macro that `#[structural]` defines in the
+//! schema module, visible within its crate. To select fields of a schema from other crates, export
+//! that macro under a name unique among the crate's exported macros, like
+//! `#[structural(export = "select_user")]`.
+//!
+//! Fields marked `#[serde(default)]` or `#[serde(default = "path")]` are filled from their default
+//! when the key is missing and the selected presence needs a value; a bare `#[serde(default)]` on
+//! the struct does this for every field. Unlike serde, which takes missing fields from the struct's
+//! own `Default` impl, the struct default fills each field from its type's `Default`, and only
+//! requires it of fields selected Present.
+//!
+//! Absent fields are never serialized, and Optional fields holding `None` serialize as `null`. Use
+//! `#[structural(serialize_none = "omit")]` on the struct or a field to leave them out instead, and
+//! `serialize_none = "null"` on a field to opt back in. Patch fields keep writing `null` for
+//! `PatchValue::Null`.
+//!
+//! Fields with `#[serde(deserialize_with = "path")]` or `#[serde(with = "module")]` take the same
+//! functions as on a plain struct, producing `T`; `null` is read as for other fields, without
+//! calling the function. For a function producing `Option<T>`, mark the field
+//! `#[structural(deserialize_option)]`.
+//!
+//! Fields can borrow from the input as with plain serde: `&'a str` and `&'a [u8]` borrow on their
+//! own, and `Cow<'a, str>`, `Cow<'a, [u8]>` or other types with lifetimes borrow when marked
+//! `#[serde(borrow)]`.
+//!
+//! When required fields are missing, deserialization fails with a
+//! [`FieldsError`](serde::FieldsError) that names all of them, using their serialized names:
+//! ``missing fields `name`, `email` ``.
+//!
+//! Absent fields ignore any data given for them, and their types need not implement `Deserialize`,
+//! so a schema can hold values that never go over the wire, like connection handles. Add
+//! `#[structural(deny_absent_input)]` to reject such input instead, for example a client sending
+//! `id` or `role` to a create endpoint. The check uses the same names as serde, including `rename`
+//! and `alias`.
+//!
+//! Nested structural fields can be marked `#[serde(flatten)]` to read and write them on the same
+//! object. The nested selection is still enforced, missing nested fields are reported under their
+//! flat names, and an Optional nested field is `None` when none of its keys are given.
+//!
+//! Formats that are not self-describing, like bincode or postcard, cannot tell which fields were
+//! skipped. With the `compact` feature, `#[structural(compact)]` adds an encoding that writes a
+//! presence bitmap followed by the values that are there; see `structural_typing::serde::compact`.
+//!
+//! ### axum extractors
+//!
+//! With the `axum` feature, [`StructuralJson`](axum::StructuralJson),
+//! [`StructuralQuery`](axum::StructuralQuery) and [`StructuralPath`](axum::StructuralPath)
+//! extract a structural type like axum's `Json`, `Query` and `Path`, rejecting a request with a
+//! JSON body that lists its missing, unexpected and invalid fields.
+//!
+//! ### JSON Schema
+//!
+//! With the `schemars` feature, `#[derive(JsonSchema)]` implements `schemars::JsonSchema` for every
+//! field selection: Present fields are `required`, Optional and Patch fields are listed but
+//! nullable, and Absent fields are left out. Fields use their serde names, and each selection
+//! gets a schema of its own, named like `Project<name, description?>`.
+//!
+//! ### utoipa components
+//!
+//! With the `utoipa` feature, `#[derive(ToSchema)]` implements `utoipa::ToSchema` for every field
+//! selection, marking fields `required` and nullable like the JSON Schema does. Each selection is
+//! a component named like `Project-name.description-opt`, unless its type alias, like
+//! `type CreateProject = Project<...>`, is listed in `#[structural(components(CreateProject))]`,
+//! which names it after the alias.
+//!
+//! ### TypeScript declarations
+//!
+//! With the `ts` feature, `#[structural(ts)]` adds `ts_interface()` to the schema module, declaring
+//! the full schema as a TypeScript `interface`, and implements [`TsType`](ts::TsType) for every
+//! selection as `Pick` and `Omit` types over it, with Optional and Patch fields as `?` properties
+//! that may also be `null`. [`ts::alias`] declares a selection under the name of its alias.
+//!
+//! ### sqlx integration
+//!
+//! With the `sqlx` feature, `#[derive(FromRow)]` implements `sqlx::FromRow` for every field
+//! selection. Present fields read a required column, Optional and Patch fields read a nullable
+//! column that may also be left out of the query, and Absent fields are not read at all.
+//! `#[sqlx(rename = "...")]` renames a column, and `#[sqlx(prefix = "...")]` reads a nested
+//! structural field from the same row with prefixed column names (`#[sqlx(flatten)]` for no
+//! prefix).
+//!
+//! The schema module also lists the columns `FromRow` reads for a selection: `user::columns::<F>()`
+//! returns them, and `user::select_list::<F>()` renders a `SELECT` list that can be qualified with a
+//! table alias and take prefixed columns from a joined table, so the projection matches the type.
+//!
+//! `insert_query(table)` and `update_query(table)` build a `sqlx::QueryBuilder` writing only the
+//! fields that hold a value or an explicit `null`, with bound values in matching order; Absent
+//! fields and Optional `None` are left out. Table and column names are written into the SQL
+//! unquoted, so the table name is a `&'static str`.
+//!
 //! ### Extract and merge
 //!
 //! ```ignore
@@ -170,6 +295,145 @@
 //! assert_eq!(overridden.id, 21);
 //! ```
 //!
+//! ### Deriving selections
+//!
+//! Each schema module's `ops` module provides utilities for deriving one selection from another:
+//! `Partial<F>` (Present → Optional), `Required<F>` (Optional and Patch → Present),
+//! `Intersect<F1, F2>` and `Difference<F1, F2>`. `select!` also accepts `Pick<F>` and `Omit<F>`
+//! forms:
+//!
+//! ```ignore
+//! type Create = select!(user: name, email);
+//! type Update = user::ops::Partial<Create>;                // name?, email?
+//! type Contact = select!(user: Pick<select!(user: id, ..Create)>, id, email);
+//! type Anonymous = select!(user: Omit<Create>, name);      // email only
+//! ```
+//!
+//! ### Narrowing
+//!
+//! `F: user::Satisfies<G>` holds when every field of `F` is at least as strong as in `G` (Present
+//! satisfies anything, Optional and Patch satisfy Optional, Patch and Absent, and Absent satisfies
+//! only Absent). It unlocks an infallible `narrow::<G>()`, which drops the fields `G` does not
+//! select. `G: user::SubsetOf<F>` states the same relation from the other side, and
+//! [`assert_satisfies!`] checks it at compile time:
+//!
+//! ```ignore
+//! type Summary = select!(user: id, name?);
+//!
+//! fn summarize<F: user::Satisfies<Summary>>(user: User<F>) -> User<Summary> {
+//!     user.narrow()
+//! }
+//!
+//! assert_satisfies!(user: select!(user: id, name, email), Summary);
+//! ```
+//!
+//! ### Requiring fields
+//!
+//! `require::<F2>()` upgrades the fields selected in `F2` in place (typically Optional → Present)
+//! and keeps every other field as is. On failure it returns a `RequireError` holding the original
+//! value and the name of every missing field, using serde's names when fields are renamed:
+//!
+//! ```ignore
+//! let req: User<select!(user: all?)> = serde_json::from_str(json)?;
+//! match req.require::<select!(user: name, email)>() {
+//!     Ok(user) => create(user),
+//!     Err(err) => return bad_request(err.missing()),
+//! }
+//! ```
+//!
+//! ### Borrowed views
+//!
+//! With `#[structural(views)]`, `as_ref()` and `as_mut()` return a view (`UserRef` / `UserMut`)
+//! with the same field presence but `&T` / `&mut T` in place of each value. Views support
+//! `extract`, and `UserRef` serializes like `User`, so a subset of a value you still own can be
+//! passed along without cloning:
+//!
+//! ```ignore
+//! let (public, _) = user.as_ref().extract::<select!(user: id, name)>();
+//! let json = serde_json::to_string(&public)?;
+//! ```
+//!
+//! ### Patch fields
+//!
+//! `Patch` fields (`field~` in `select!`) hold a `PatchValue<T>` that is `Missing`, `Null`, or a
+//! `Value`. With serde, a missing key deserializes to `Missing` and an explicit `null` to `Null`.
+//! When merged, `Missing` keeps the existing value and `Null` clears it:
+//!
+//! ```ignore
+//! # use structural_typing::{structural, select};
+//! #
+//! # #[structural]
+//! # struct User {
+//! #     id: u32,
+//! #     name: String,
+//! #     email: String,
+//! # }
+//! #
+//! let stored = user::empty().id(1).email(Some("alice@example.com".to_owned()));
+//! let patch: User<select!(user: name~, email~)> = serde_json::from_str(r#"{"email":null}"#)?;
+//!
+//! let (updated, _) = stored.merge(patch).extract::<select!(user: id, email?)>();
+//! assert_eq!(updated.email, None);
+//! ```
+//!
+//! A Patch field merged over a Present one stays Patch, since the patch may set it to `null`: use
+//! `try_extract` to require it again, and reject the patch if it was cleared:
+//!
+//! ```ignore
+//! let stored = user::empty().id(1).name("Alice".to_owned());
+//! let patch: User<select!(user: name~)> = serde_json::from_str(r#"{"name":null}"#)?;
+//!
+//! let Ok((updated, _)) = stored.merge(patch).try_extract::<select!(user: id, name)>() else {
+//!     return Err("name cannot be cleared".into());
+//! };
+//! ```
+//!
+//! ### Custom presence markers
+//!
+//! Markers are ordinary types implementing `Presence`, so other crates can add their own by
+//! implementing `CustomPresence`. A marker picks a container implementing `Access` (which converts
+//! to and from `Option<T>`) and declares whether it always holds a value and whether it can
+//! represent a missing one, backing each flag with an infallible method; `merge`, `extract`,
+//! `try_extract`, the utility selections and deserialization are derived from that. Implement
+//! `InferPresence` for the container to make builder setters accept it:
+//!
+//! ```ignore
+//! use core::convert::Infallible;
+//! use structural_typing::{access::Access, presence::*};
+//!
+//! struct Heap<T>(Box<T>);
+//!
+//! impl<T> Access<T> for Heap<T> {
+//!     const IS_ABSENT: bool = false;
+//!     fn get(&self) -> Option<&T> { Some(&self.0) }
+//!     fn get_mut(&mut self) -> Option<&mut T> { Some(&mut self.0) }
+//!     fn into_value(self) -> Result<T, Self> { Ok(*self.0) }
+//!     fn from_value(value: T) -> Self { Heap(Box::new(value)) }
+//! }
+//!
+//! struct Boxed;
+//!
+//! impl CustomPresence for Boxed {
+//!     type Output<T> = Heap<T>;
+//!     type AlwaysPresent = True;
+//!     type AcceptsMissing = False;
+//!     fn take<T>(value: Heap<T>, _always_present: ()) -> T { *value.0 }
+//!     fn missing<T>(accepts_missing: Infallible) -> Heap<T> { match accepts_missing {} }
+//!     // `as_ref` and `as_mut` box the borrow
+//! }
+//!
+//! impl<T> InferPresence<T> for Heap<T> {
+//!     type Presence = Boxed;
+//! }
+//!
+//! let user = user::empty().name(Heap(Box::new("Alice".to_owned())));
+//! let (owned, _) = user.extract::<select!(user: name)>();
+//! ```
+//!
+//! Containers must be able to hand back an owned `T` for any `T`, so reference-counted storage
+//! such as `Arc<T>` is not supported, and field types must be `Sized`: unsized data such as `str`
+//! or `[u8]` is declared as a `Box<str>` or `Box<[u8]>` field rather than stored by a marker.
+//!
 //! ### Nested schemas
 //!
 //! Schemas can contain other structural types:
@@ -202,6 +466,14 @@
 //! }
 //! ```
 //!
+//! `select!` can select the nested schema in the same breath. With braces after a nested field it
+//! expands to the whole struct type:
+//!
+//! ```ignore
+//! // User<select!(user: id, address), select!(address: street, city)>
+//! type Shipping = select!(user: id, address { street, city });
+//! ```
+//!
 //! #### Nested setters
 //!
 //! Use `#[nested(...)]` to generate convenient setters for nested struct fields:
@@ -246,8 +518,9 @@
 //!
 //! The `#[structural]` macro generates a module containing a `Fields` trait with associated types
 //! for each field. Field presence is tracked through type-level markers (`Present`, `Optional`,
-//! `Absent`) that implement the `Presence` trait. Functions express requirements through trait bounds,
-//! and the builder API uses type inference to determine field states.
+//! `Absent`, `Patch`, or your own) that implement the `Presence` trait. Functions express requirements
+//! through trait bounds, and the builder API uses type inference to determine field states. Extraction
+//! and merging are defined once over the `Presence` algebra rather than per marker pair.
 //!
 //! This approach enables:
 //! - **Compile-time verification**: All field requirements checked during compilation
@@ -260,146 +533,54 @@
 
 /// Runtime field access trait for checking presence and getting values.
 pub mod access;
+/// axum extractors reporting rejected fields.
+#[cfg(feature = "axum")]
+pub mod axum;
 /// Traits for splitting structs into selected fields and remainder.
 pub mod extract;
 /// Type-level presence markers and traits for field state tracking.
 pub mod presence;
+/// Error type for upgrading selected fields in place with `require`.
+pub mod require;
+/// schemars integration helpers.
+#[cfg(feature = "schemars")]
+pub mod schemars;
 /// Serde integration helpers.
 #[cfg(feature = "serde")]
 pub mod serde;
+/// sqlx integration helpers.
+#[cfg(feature = "sqlx")]
+pub mod sqlx;
+/// TypeScript declarations for schemas and their selections.
+#[cfg(feature = "ts")]
+pub mod ts;
+/// utoipa integration helpers.
+#[cfg(feature = "utoipa")]
+pub mod utoipa;
 
 pub use structural_typing_macros::structural;
 
-/// Construct a `FieldSet` by selecting fields from a module.
-///
-/// # Presence States
-///
-/// | Suffix | Presence | Field Type |
-/// |--------|----------|------------|
-/// | (none) | Present | `T` |
-/// | `?` | Optional | `Option<T>` |
-/// | `-` | Absent | `PhantomData<T>` |
-///
-/// Access is always `user.field`. In generic code use `.get()` from the `Access` trait.
-///
-/// # Syntax Reference
+pub use structural_typing_macros::select;
+
+#[doc(hidden)]
+pub use structural_typing_macros::select_with_schema as __select_with_schema;
+
+/// Asserts at compile time that field set `F` satisfies `G` (`F: module::Satisfies<G>`).
 ///
-/// | Pattern | Description |
-/// |---------|-------------|
-/// | `select!(mod: field)` | field is Present (required) |
-/// | `select!(mod: field?)` | field is Optional |
-/// | `select!(mod: field-)` | field is Absent |
-/// | `select!(mod: field<P>)` | field has custom presence type P |
-/// | `select!(mod: all)` | all fields Present |
-/// | `select!(mod: all?)` | all fields Optional |
-/// | `select!(mod: all-)` | all fields Absent |
-/// | `select!(mod: f1, f2?)` | multiple fields (f1=Present, f2=Optional) |
-/// | `select!(mod: f, ..F)` | spread: f=Present, rest inherited from F |
-/// | `select!(mod: f?, ..F)` | spread: f=Optional, rest inherited from F |
-/// | `select!(mod: f, all-)` | f=Present, all others Absent |
+/// Expands to an item, so it can be used at module level or inside a function body.
 ///
 /// # Examples
 ///
 /// ```ignore
-/// // Concrete types for API boundaries
-/// type Create = select!(user: name, email);      // Both required
-/// type Update = select!(user: name?, email?);    // Both optional
-///
-/// // Generic: add id field to any existing fields
-/// type WithId<F: user::Fields> = select!(user: id, ..F);
-///
-/// // Function using concrete type
-/// fn create(user: User<Create>) -> User<WithId<Create>> { ... }
+/// assert_satisfies!(user: select!(user: id, name), select!(user: id, name?));
+/// assert_satisfies!(user: user::with::all, user::ops::Partial<user::with::all>);
 /// ```
 #[macro_export]
-macro_rules! select {
-    // Error: spread without fields
-    ($($module:ident)::+ : .. $spread:ty) => {
-        compile_error!("select! requires at least one explicit field before spread operator")
-    };
-
-    // Error: empty field list
-    ($($module:ident)::+ :) => {
-        compile_error!("select! requires at least one field")
-    };
-
-    // Single field with spread: Present
-    ($($module:ident)::+ : $field:ident, .. $spread:ty) => {
-        $($module)::+::with::$field<::structural_typing::presence::Present, $spread>
-    };
-
-    // Single field with spread: Optional
-    ($($module:ident)::+ : $field:ident ?, .. $spread:ty) => {
-        $($module)::+::with::$field<::structural_typing::presence::Optional, $spread>
-    };
-
-    // Single field with spread: Absent
-    ($($module:ident)::+ : $field:ident -, .. $spread:ty) => {
-        $($module)::+::with::$field<::structural_typing::presence::Absent, $spread>
-    };
-
-    // Single field with spread: Custom type
-    ($($module:ident)::+ : $field:ident < $presence:ty >, .. $spread:ty) => {
-        $($module)::+::with::$field<$presence, $spread>
-    };
-
-    // Single field: Present
-    ($($module:ident)::+ : $field:ident) => {
-        $($module)::+::with::$field
-    };
-
-    // Single field: Present with trailing comma
-    ($($module:ident)::+ : $field:ident,) => {
-        $($module)::+::with::$field
-    };
-
-    // Single field: Optional
-    ($($module:ident)::+ : $field:ident ?) => {
-        $($module)::+::with::$field<::structural_typing::presence::Optional>
-    };
-
-    // Single field: Optional with trailing comma
-    ($($module:ident)::+ : $field:ident ?,) => {
-        $($module)::+::with::$field<::structural_typing::presence::Optional>
-    };
-
-    // Single field: Absent
-    ($($module:ident)::+ : $field:ident -) => {
-        $($module)::+::with::$field<::structural_typing::presence::Absent>
-    };
-
-    // Single field: Absent with trailing comma
-    ($($module:ident)::+ : $field:ident -,) => {
-        $($module)::+::with::$field<::structural_typing::presence::Absent>
-    };
-
-    // Single field: Custom type
-    ($($module:ident)::+ : $field:ident < $presence:ty >) => {
-        $($module)::+::with::$field<$presence>
-    };
-
-    // Single field: Custom type with trailing comma
-    ($($module:ident)::+ : $field:ident < $presence:ty >,) => {
-        $($module)::+::with::$field<$presence>
-    };
-
-    // Multiple fields: Optional + rest
-    ($($module:ident)::+ : $field:ident ?, $($rest:tt)+) => {
-        $($module)::+::with::$field<::structural_typing::presence::Optional, select!($($module)::+ : $($rest)+)>
-    };
-
-    // Multiple fields: Absent + rest
-    ($($module:ident)::+ : $field:ident -, $($rest:tt)+) => {
-        $($module)::+::with::$field<::structural_typing::presence::Absent, select!($($module)::+ : $($rest)+)>
-    };
-
-    // Multiple fields: Custom type + rest
-    ($($module:ident)::+ : $field:ident < $presence:ty >, $($rest:tt)+) => {
-        $($module)::+::with::$field<$presence, select!($($module)::+ : $($rest)+)>
-    };
-
-    // Multiple fields: Present + rest
-    ($($module:ident)::+ : $field:ident, $($rest:tt)+) => {
-        $($module)::+::with::$field<::structural_typing::presence::Present, select!($($module)::+ : $($rest)+)>
+macro_rules! assert_satisfies {
+    ($($module:ident)::+ : $source:ty, $target:ty $(,)?) => {
+        const _: () = {
+            const fn assert_satisfies<F: $($module)::+::Satisfies<G>, G: $($module)::+::Fields>() {}
+            assert_satisfies::<$source, $target>();
+        };
     };
 }
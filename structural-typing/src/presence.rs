@@ -1,11 +1,9 @@
 //! Type-level markers for field presence states.
 
+use core::convert::Infallible;
 use core::marker::PhantomData;
 
-use crate::{
-    access::Access,
-    extract::{Extract, TryExtract},
-};
+use crate::access::{Access, patch_from};
 
 /// Marker indicating a field is present with a concrete value.
 pub struct Present;
@@ -13,20 +11,172 @@ pub struct Present;
 pub struct Optional;
 /// Marker indicating a field is absent (`PhantomData`).
 pub struct Absent;
+/// Marker indicating a field may be missing, explicitly null, or set (`PatchValue`).
+pub struct Patch;
+
+/// Three-state value for [`Patch`] fields, distinguishing "not provided" from "cleared".
+///
+/// Mirrors RFC 7396 merge-patch semantics: a missing key keeps the existing value, an explicit
+/// `null` clears it, and any other value replaces it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PatchValue<T> {
+    /// No value was provided.
+    #[default]
+    Missing,
+    /// The value was explicitly cleared.
+    Null,
+    /// A value was provided.
+    Value(T),
+}
+
+impl<T> PatchValue<T> {
+    /// Returns `true` if no value was provided.
+    #[must_use]
+    pub const fn is_missing(&self) -> bool {
+        matches!(self, Self::Missing)
+    }
+
+    /// Returns `true` if the value was explicitly cleared.
+    #[must_use]
+    pub const fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns `true` if a value was provided.
+    #[must_use]
+    pub const fn is_value(&self) -> bool {
+        matches!(self, Self::Value(_))
+    }
+
+    /// Converts from `&PatchValue<T>` to `PatchValue<&T>`.
+    #[must_use]
+    pub const fn as_ref(&self) -> PatchValue<&T> {
+        match self {
+            Self::Missing => PatchValue::Missing,
+            Self::Null => PatchValue::Null,
+            Self::Value(value) => PatchValue::Value(value),
+        }
+    }
+
+    /// Converts from `&mut PatchValue<T>` to `PatchValue<&mut T>`.
+    #[must_use]
+    pub const fn as_mut(&mut self) -> PatchValue<&mut T> {
+        match self {
+            Self::Missing => PatchValue::Missing,
+            Self::Null => PatchValue::Null,
+            Self::Value(value) => PatchValue::Value(value),
+        }
+    }
+
+    /// Applies this patch to an existing optional value: `Missing` keeps it, `Null` clears it,
+    /// and `Value` replaces it.
+    #[must_use]
+    pub fn apply(self, current: Option<T>) -> Option<T> {
+        match self {
+            Self::Missing => current,
+            Self::Null => None,
+            Self::Value(value) => Some(value),
+        }
+    }
+}
+
+/// Type-level boolean used by [`Presence`] to describe which extractions always succeed.
+pub trait Bool {
+    /// Runtime value of this boolean.
+    const VALUE: bool;
+    /// Logical or of two type-level booleans.
+    type Or<Other: Bool>: Bool;
+    /// Evidence that this boolean holds: `()` for [`True`], uninhabited for [`False`].
+    type Proof;
+    /// `Then` for [`True`], `Else` for [`False`].
+    type If<Then: Presence, Else: Presence>: Presence;
+
+    /// Evidence for this boolean or for `Other`, given evidence for their `Or`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the evidence for `Other` if this boolean is [`False`].
+    fn either<Other: Bool>(
+        proof: <Self::Or<Other> as Bool>::Proof,
+    ) -> Result<Self::Proof, Other::Proof>;
+}
+
+/// Type-level `true`.
+pub enum True {}
+/// Type-level `false`.
+pub enum False {}
+
+impl Bool for True {
+    const VALUE: bool = true;
+    type Or<Other: Bool> = True;
+    type Proof = ();
+    type If<Then: Presence, Else: Presence> = Then;
+
+    #[inline]
+    fn either<Other: Bool>(proof: ()) -> Result<(), Other::Proof> {
+        Ok(proof)
+    }
+}
+
+impl Bool for False {
+    const VALUE: bool = false;
+    type Or<Other: Bool> = Other;
+    type Proof = Infallible;
+    type If<Then: Presence, Else: Presence> = Else;
+
+    #[inline]
+    fn either<Other: Bool>(proof: Other::Proof) -> Result<Infallible, Other::Proof> {
+        Err(proof)
+    }
+}
+
+/// Extracted value and remainder produced when splitting a `Source` field into `Target`.
+pub type Extracted<Target, Source, T> = (
+    <Target as Presence>::Output<T>,
+    <<Target as Presence>::Remainder<Source> as Presence>::Output<T>,
+);
+
+/// Evidence that extracting a `Source` field into `Target` cannot fail: either `Target` accepts a
+/// missing value or `Source` always holds one.
+pub type ExtractProof<Target, Source> = Result<
+    <<Target as Presence>::AcceptsMissing as Bool>::Proof,
+    <<Source as Presence>::AlwaysPresent as Bool>::Proof,
+>;
 
 /// Trait for type-level presence markers with associated container types.
+///
+/// The crate never matches on concrete markers, so every marker works with `merge`, `extract`,
+/// `try_extract` and deserialization. Custom markers implement the smaller [`CustomPresence`],
+/// which derives this trait.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a presence marker",
+    label = "expected `Present`, `Optional`, `Absent`, `Patch` or a custom marker",
+    note = "presence markers select how a field is stored, e.g. `Present` stores `T` and `Optional` stores `Option<T>`"
+)]
 pub trait Presence {
+    /// Container type (T, Option\<T>, `PhantomData`\<T>, `PatchValue`\<T>, or a custom one).
+    type Output<T>: Access<T>;
+    /// [`True`] if the container always holds a value, so extracting from it cannot fail.
+    type AlwaysPresent: Bool;
+    /// [`True`] if the container can represent a missing value, so extracting into it cannot fail.
+    ///
+    /// At least one of this and `AlwaysPresent` must be `True`, so a field can always be
+    /// extracted into its own presence.
+    type AcceptsMissing: Bool<Or<Self::AlwaysPresent> = True>;
+    /// Presence left in the source after extracting it into this presence.
+    ///
+    /// `Absent` for presences that take the value; `Source` for presences that leave it in place.
+    type Remainder<Source: Presence>: Presence;
+    /// Presence after making the field optional (`Partial`): Present → Optional.
+    type Partial: Presence;
+    /// Presence after making the field required (`Required`): Optional and Patch → Present.
+    type Required: Presence;
+    /// `Other` if this presence holds the field (anything but Absent), Absent otherwise (`Intersect`).
+    type Keep<Other: Presence>: Presence;
     /// Presence when combined with Option.
     type OptionOrSelf: Presence;
     /// Result of merging this presence with another.
     type Or<Other: Presence>: Presence;
-    /// Container type (T, Option\<T>, or `PhantomData`\<T>).
-    type Output<T>: Access<T>
-        + Extract<PhantomData<T>, T>
-        + Extract<Option<T>, T>
-        + Extract<Self::Output<T>, T>
-        + TryExtract<Option<T>, T>
-        + InferPresence<T, Presence = Self>;
 
     /// Merge two values, preferring the first if present.
     fn or<T, Other: Presence>(
@@ -39,9 +189,153 @@ pub trait Presence {
         option: Option<T>,
         self_: <Self as Presence>::Output<T>,
     ) -> <<Self as Presence>::OptionOrSelf as Presence>::Output<T>;
+
+    /// Borrow a value, keeping its presence state.
+    fn as_ref<T>(value: &Self::Output<T>) -> Self::Output<&T>;
+
+    /// Mutably borrow a value, keeping its presence state.
+    fn as_mut<T>(value: &mut Self::Output<T>) -> Self::Output<&mut T>;
+
+    /// Take the value out of a container that always holds one.
+    fn take<T>(value: Self::Output<T>, always_present: <Self::AlwaysPresent as Bool>::Proof) -> T;
+
+    /// Split a source value into this presence and the remainder left behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` with the original value if it cannot be represented in this presence
+    /// (e.g., a `None` extracted into `Present`).
+    fn split<T, Source: Presence>(
+        source: Source::Output<T>,
+    ) -> Result<Extracted<Self, Source, T>, Source::Output<T>>;
+
+    /// Split a source value that is known to fit into this presence.
+    fn split_proven<T, Source: Presence>(
+        source: Source::Output<T>,
+        proof: ExtractProof<Self, Source>,
+    ) -> Extracted<Self, Source, T>;
+}
+
+/// The part of [`Presence`] a custom marker implements; the rest is derived.
+///
+/// A custom marker always holds its field, so extracting into it leaves an Absent remainder and
+/// intersecting keeps the other side. `Partial` makes it `Optional` unless it accepts a missing
+/// value, `Required` makes it `Present` unless it always holds one, and merging keeps its value if
+/// it holds one and fills it from the other side otherwise.
+///
+/// The flags are backed by [`CustomPresence::take`] and [`CustomPresence::missing`]: a marker
+/// whose flag is [`False`] receives uninhabited evidence and implements the method with
+/// `match proof {}`.
+///
+/// Field types must be `Sized`, so a boxing marker stores `Box<T>` of a sized `T`; declare
+/// unsized data as a `Box<str>` or `Box<[u8]>` field instead.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a presence marker",
+    label = "expected `Present`, `Optional`, `Absent`, `Patch` or a custom marker",
+    note = "presence markers select how a field is stored, e.g. `Present` stores `T` and `Optional` stores `Option<T>`"
+)]
+pub trait CustomPresence {
+    /// Container type.
+    type Output<T>: Access<T>;
+    /// [`True`] if the container always holds a value, so extracting from it cannot fail.
+    type AlwaysPresent: Bool;
+    /// [`True`] if the container can represent a missing value, so extracting into it cannot fail.
+    type AcceptsMissing: Bool<Or<Self::AlwaysPresent> = True>;
+
+    /// Take the value out of a container that always holds one.
+    fn take<T>(value: Self::Output<T>, always_present: <Self::AlwaysPresent as Bool>::Proof) -> T;
+
+    /// Construct a container holding no value.
+    fn missing<T>(accepts_missing: <Self::AcceptsMissing as Bool>::Proof) -> Self::Output<T>;
+
+    /// Borrow a value, keeping its presence state.
+    fn as_ref<T>(value: &Self::Output<T>) -> Self::Output<&T>;
+
+    /// Mutably borrow a value, keeping its presence state.
+    fn as_mut<T>(value: &mut Self::Output<T>) -> Self::Output<&mut T>;
 }
 
-/// Infers presence state from value type: `T` → Present, `Option<T>` → Optional, `PhantomData<T>` → Absent.
+impl<M: CustomPresence> Presence for M {
+    type Output<T> = M::Output<T>;
+    type AlwaysPresent = M::AlwaysPresent;
+    type AcceptsMissing = M::AcceptsMissing;
+    type Remainder<Source: Presence> = Absent;
+    type Partial = <M::AcceptsMissing as Bool>::If<M, Optional>;
+    type Required = <M::AlwaysPresent as Bool>::If<M, Present>;
+    type Keep<Other: Presence> = Other;
+    type OptionOrSelf = M;
+    type Or<Other: Presence> = M;
+
+    #[inline]
+    fn or<T, Other: Presence>(
+        self_: Self::Output<T>,
+        other: Other::Output<T>,
+    ) -> <Self::Or<Other> as Presence>::Output<T> {
+        if self_.get().is_some() {
+            return self_;
+        }
+        match other.into_value() {
+            Ok(value) => M::Output::from_value(value),
+            Err(_) => self_,
+        }
+    }
+
+    #[inline]
+    fn option_or_self<T>(
+        option: Option<T>,
+        self_: Self::Output<T>,
+    ) -> <Self::OptionOrSelf as Presence>::Output<T> {
+        match option {
+            Some(value) => M::Output::from_value(value),
+            None => self_,
+        }
+    }
+
+    #[inline]
+    fn as_ref<T>(value: &Self::Output<T>) -> Self::Output<&T> {
+        M::as_ref(value)
+    }
+
+    #[inline]
+    fn as_mut<T>(value: &mut Self::Output<T>) -> Self::Output<&mut T> {
+        M::as_mut(value)
+    }
+
+    #[inline]
+    fn take<T>(value: Self::Output<T>, always_present: <Self::AlwaysPresent as Bool>::Proof) -> T {
+        M::take(value, always_present)
+    }
+
+    #[inline]
+    fn split<T, Source: Presence>(
+        source: Source::Output<T>,
+    ) -> Result<Extracted<Self, Source, T>, Source::Output<T>> {
+        Ok((M::Output::from_access(source)?, PhantomData))
+    }
+
+    #[inline]
+    fn split_proven<T, Source: Presence>(
+        source: Source::Output<T>,
+        proof: ExtractProof<Self, Source>,
+    ) -> Extracted<Self, Source, T> {
+        let value = match proof {
+            Ok(accepts_missing) => match source.into_value() {
+                Ok(value) => M::Output::from_value(value),
+                Err(_) => M::missing(accepts_missing),
+            },
+            Err(always_present) => M::Output::from_value(Source::take(source, always_present)),
+        };
+        (value, PhantomData)
+    }
+}
+
+/// Infers presence state from value type: `T` → Present, `Option<T>` → Optional, `PhantomData<T>` → Absent,
+/// `PatchValue<T>` → Patch.
+#[diagnostic::on_unimplemented(
+    message = "cannot set a `{T}` field from `{Self}`",
+    label = "expected `{T}`, `Option<{T}>`, `PhantomData<{T}>` or `PatchValue<{T}>`",
+    note = "custom containers need an `InferPresence<{T}>` impl naming their presence marker"
+)]
 pub trait InferPresence<T> {
     /// The presence state corresponding to this value type.
     type Presence: Presence<Output<T> = Self>;
@@ -59,10 +353,20 @@ impl<T> InferPresence<T> for PhantomData<T> {
     type Presence = Absent;
 }
 
+impl<T> InferPresence<T> for PatchValue<T> {
+    type Presence = Patch;
+}
+
 impl Presence for Present {
+    type Partial = Optional;
+    type Required = Present;
+    type Keep<Other: Presence> = Other;
     type OptionOrSelf = Present;
     type Or<Other: Presence> = Present;
     type Output<T> = T;
+    type AlwaysPresent = True;
+    type AcceptsMissing = False;
+    type Remainder<Source: Presence> = Absent;
 
     #[inline]
     fn or<T, Other: Presence>(
@@ -79,12 +383,51 @@ impl Presence for Present {
     ) -> <<Self as Presence>::OptionOrSelf as Presence>::Output<T> {
         option.unwrap_or(self_)
     }
+
+    #[inline]
+    fn as_ref<T>(value: &Self::Output<T>) -> Self::Output<&T> {
+        value
+    }
+
+    #[inline]
+    fn as_mut<T>(value: &mut Self::Output<T>) -> Self::Output<&mut T> {
+        value
+    }
+
+    #[inline]
+    fn take<T>(value: Self::Output<T>, _always_present: ()) -> T {
+        value
+    }
+
+    #[inline]
+    fn split<T, Source: Presence>(
+        source: Source::Output<T>,
+    ) -> Result<Extracted<Self, Source, T>, Source::Output<T>> {
+        Ok((T::from_access(source)?, PhantomData))
+    }
+
+    #[inline]
+    fn split_proven<T, Source: Presence>(
+        source: Source::Output<T>,
+        proof: ExtractProof<Self, Source>,
+    ) -> Extracted<Self, Source, T> {
+        match proof {
+            Ok(never) => match never {},
+            Err(always_present) => (Source::take(source, always_present), PhantomData),
+        }
+    }
 }
 
 impl Presence for Optional {
+    type Partial = Optional;
+    type Required = Present;
+    type Keep<Other: Presence> = Other;
     type OptionOrSelf = Optional;
     type Or<Other: Presence> = Other::OptionOrSelf;
     type Output<T> = Option<T>;
+    type AlwaysPresent = False;
+    type AcceptsMissing = True;
+    type Remainder<Source: Presence> = Absent;
 
     #[inline]
     fn or<T, Other: Presence>(
@@ -101,12 +444,48 @@ impl Presence for Optional {
     ) -> <<Self as Presence>::OptionOrSelf as Presence>::Output<T> {
         option.or(self_)
     }
+
+    #[inline]
+    fn as_ref<T>(value: &Self::Output<T>) -> Self::Output<&T> {
+        value.as_ref()
+    }
+
+    #[inline]
+    fn as_mut<T>(value: &mut Self::Output<T>) -> Self::Output<&mut T> {
+        value.as_mut()
+    }
+
+    #[inline]
+    fn take<T>(_value: Self::Output<T>, always_present: Infallible) -> T {
+        match always_present {}
+    }
+
+    #[inline]
+    fn split<T, Source: Presence>(
+        source: Source::Output<T>,
+    ) -> Result<Extracted<Self, Source, T>, Source::Output<T>> {
+        Ok((source.into_option(), PhantomData))
+    }
+
+    #[inline]
+    fn split_proven<T, Source: Presence>(
+        source: Source::Output<T>,
+        _proof: ExtractProof<Self, Source>,
+    ) -> Extracted<Self, Source, T> {
+        (source.into_option(), PhantomData)
+    }
 }
 
 impl Presence for Absent {
+    type Partial = Absent;
+    type Required = Absent;
+    type Keep<Other: Presence> = Absent;
     type OptionOrSelf = Optional;
     type Or<Other: Presence> = Other;
     type Output<T> = PhantomData<T>;
+    type AlwaysPresent = False;
+    type AcceptsMissing = True;
+    type Remainder<Source: Presence> = Source;
 
     #[inline]
     fn or<T, Other: Presence>(
@@ -123,4 +502,103 @@ impl Presence for Absent {
     ) -> <<Self as Presence>::OptionOrSelf as Presence>::Output<T> {
         option
     }
+
+    #[inline]
+    fn as_ref<T>(_value: &Self::Output<T>) -> Self::Output<&T> {
+        PhantomData
+    }
+
+    #[inline]
+    fn as_mut<T>(_value: &mut Self::Output<T>) -> Self::Output<&mut T> {
+        PhantomData
+    }
+
+    #[inline]
+    fn take<T>(_value: Self::Output<T>, always_present: Infallible) -> T {
+        match always_present {}
+    }
+
+    #[inline]
+    fn split<T, Source: Presence>(
+        source: Source::Output<T>,
+    ) -> Result<Extracted<Self, Source, T>, Source::Output<T>> {
+        Ok((PhantomData, source))
+    }
+
+    #[inline]
+    fn split_proven<T, Source: Presence>(
+        source: Source::Output<T>,
+        _proof: ExtractProof<Self, Source>,
+    ) -> Extracted<Self, Source, T> {
+        (PhantomData, source)
+    }
+}
+
+impl Presence for Patch {
+    type Partial = Patch;
+    type Required = Present;
+    type Keep<Other: Presence> = Other;
+    type OptionOrSelf = Patch;
+    // Stays Patch over any presence, Present included, since the patch may clear the field
+    type Or<Other: Presence> = Patch;
+    type Output<T> = PatchValue<T>;
+    type AlwaysPresent = False;
+    type AcceptsMissing = True;
+    type Remainder<Source: Presence> = Absent;
+
+    #[inline]
+    fn or<T, Other: Presence>(
+        self_: <Self as Presence>::Output<T>,
+        other: Other::Output<T>,
+    ) -> <<Self as Presence>::Or<Other> as Presence>::Output<T> {
+        match self_ {
+            PatchValue::Missing if other.is_absent() => PatchValue::Missing,
+            PatchValue::Missing => match other.into_option() {
+                Some(value) => PatchValue::Value(value),
+                None => PatchValue::Null,
+            },
+            patch => patch,
+        }
+    }
+
+    #[inline]
+    fn option_or_self<T>(
+        option: Option<T>,
+        self_: <Self as Presence>::Output<T>,
+    ) -> <<Self as Presence>::OptionOrSelf as Presence>::Output<T> {
+        match option {
+            Some(value) => PatchValue::Value(value),
+            None => self_,
+        }
+    }
+
+    #[inline]
+    fn as_ref<T>(value: &Self::Output<T>) -> Self::Output<&T> {
+        value.as_ref()
+    }
+
+    #[inline]
+    fn as_mut<T>(value: &mut Self::Output<T>) -> Self::Output<&mut T> {
+        value.as_mut()
+    }
+
+    #[inline]
+    fn take<T>(_value: Self::Output<T>, always_present: Infallible) -> T {
+        match always_present {}
+    }
+
+    #[inline]
+    fn split<T, Source: Presence>(
+        source: Source::Output<T>,
+    ) -> Result<Extracted<Self, Source, T>, Source::Output<T>> {
+        Ok((patch_from(source), PhantomData))
+    }
+
+    #[inline]
+    fn split_proven<T, Source: Presence>(
+        source: Source::Output<T>,
+        _proof: ExtractProof<Self, Source>,
+    ) -> Extracted<Self, Source, T> {
+        (patch_from(source), PhantomData)
+    }
 }
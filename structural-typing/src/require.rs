@@ -0,0 +1,55 @@
+//! Error type for `require`, which upgrades selected fields in place.
+
+use core::fmt;
+
+/// Error returned by `require` when selected fields hold no value.
+///
+/// Carries the original value unchanged, plus the name of every missing field in declaration
+/// order (using serde's deserialize name when the struct renames fields).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequireError<S> {
+    value: S,
+    missing: Vec<&'static str>,
+}
+
+impl<S> RequireError<S> {
+    /// Create a new error for the given value and missing field names.
+    #[must_use]
+    pub const fn new(value: S, missing: Vec<&'static str>) -> Self {
+        Self { value, missing }
+    }
+
+    /// Get the names of the missing fields.
+    #[must_use]
+    pub fn missing(&self) -> &[&'static str] {
+        &self.missing
+    }
+
+    /// Get the original value.
+    #[must_use]
+    pub const fn value(&self) -> &S {
+        &self.value
+    }
+
+    /// Recover the original value.
+    #[must_use]
+    pub fn into_value(self) -> S {
+        self.value
+    }
+}
+
+impl<S> fmt::Display for RequireError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.missing.len() == 1 { "" } else { "s" };
+        write!(f, "missing required field{plural} ")?;
+        for (i, field) in self.missing.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "`{field}`")?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: fmt::Debug> std::error::Error for RequireError<S> {}
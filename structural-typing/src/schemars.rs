@@ -0,0 +1,186 @@
+//! schemars integration helpers.
+//!
+//! `#[derive(JsonSchema)]` on a `#[structural]` struct implements
+//! [`JsonSchema`](::schemars::JsonSchema) for every field selection, describing what
+//! deserializing that selection accepts: Present fields are listed under `required`, Optional
+//! and Patch fields are listed but nullable, and Absent fields are left out, so their types need
+//! not implement `JsonSchema`. Fields use their serialized names, and fields with a serde default
+//! are not required.
+//!
+//! Each selection is a schema of its own, named after the struct and its selected fields the way
+//! `select!` writes them, like `User<id, name, email?>`. Fields whose type depends on the
+//! struct's generic parameters, like nested structural fields, add their own schema name:
+//! `Todo<id, project: Project<id, name>>`.
+
+use core::marker::PhantomData;
+use std::borrow::Cow;
+
+use ::schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde_json::{Map, Value};
+
+use crate::presence::PatchValue;
+
+/// Name of a selection's schema: the struct's name followed by its selected fields.
+#[doc(hidden)]
+pub struct SchemaName {
+    name: String,
+    fields: usize,
+}
+
+impl SchemaName {
+    /// Start the name of a schema of `name`.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            fields: 0,
+        }
+    }
+
+    /// Add a selected field, marked `?` if not required and followed by the schema name of its
+    /// type if that depends on the struct's generic parameters.
+    pub fn field(&mut self, field: &str, required: bool, ty: Option<&str>) {
+        self.name
+            .push_str(if self.fields == 0 { "<" } else { ", " });
+        self.fields += 1;
+        self.name.push_str(field);
+        if !required {
+            self.name.push('?');
+        }
+        if let Some(ty) = ty {
+            self.name.push_str(": ");
+            self.name.push_str(ty);
+        }
+    }
+
+    /// Get the finished name.
+    #[must_use]
+    pub fn finish(mut self) -> Cow<'static, str> {
+        self.name
+            .push_str(if self.fields == 0 { "<>" } else { ">" });
+        self.name.into()
+    }
+}
+
+/// Object schema of a selection, built field by field.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct ObjectSchema {
+    properties: Map<String, Value>,
+    required: Vec<Value>,
+}
+
+impl ObjectSchema {
+    /// Create a schema without properties.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Finish the schema, with the struct's doc comment as its description.
+    #[must_use]
+    pub fn into_schema(self, description: Option<&str>) -> Schema {
+        let mut schema = Map::new();
+        schema.insert("type".to_owned(), "object".into());
+        if let Some(description) = description {
+            schema.insert("description".to_owned(), description.into());
+        }
+        schema.insert("properties".to_owned(), Value::Object(self.properties));
+        if !self.required.is_empty() {
+            schema.insert("required".to_owned(), Value::Array(self.required));
+        }
+        Schema::from(schema)
+    }
+}
+
+/// A field of a selection's schema: `PatchValue<T>` adds `T`'s schema, and `PhantomData<T>` for
+/// Absent fields adds nothing.
+#[doc(hidden)]
+pub trait FieldSchema {
+    /// Add the field to `name`, with the name of its type's schema if `generic`.
+    fn name_field(name: &mut SchemaName, field: &str, required: bool, generic: bool);
+
+    /// Add the field to `id`, with the id of its type's schema if `generic`.
+    fn id_field(id: &mut SchemaName, field: &str, required: bool, generic: bool);
+
+    /// Add the field to `object` under `properties`, and under `required` if it is.
+    fn add_field(
+        generator: &mut SchemaGenerator,
+        object: &mut ObjectSchema,
+        field: &str,
+        required: bool,
+        nullable: bool,
+        description: Option<&str>,
+    );
+
+    /// Add the properties of a `#[serde(flatten)]` field to `object`, and its required
+    /// properties if the field itself is `required`.
+    fn add_flattened(generator: &mut SchemaGenerator, object: &mut ObjectSchema, required: bool);
+}
+
+impl<T: JsonSchema> FieldSchema for PatchValue<T> {
+    fn name_field(name: &mut SchemaName, field: &str, required: bool, generic: bool) {
+        name.field(field, required, generic.then(T::schema_name).as_deref());
+    }
+
+    fn id_field(id: &mut SchemaName, field: &str, required: bool, generic: bool) {
+        id.field(field, required, generic.then(T::schema_id).as_deref());
+    }
+
+    fn add_field(
+        generator: &mut SchemaGenerator,
+        object: &mut ObjectSchema,
+        field: &str,
+        required: bool,
+        nullable: bool,
+        description: Option<&str>,
+    ) {
+        let mut schema = if nullable {
+            generator.subschema_for::<Option<T>>()
+        } else {
+            generator.subschema_for::<T>()
+        };
+        if let Some(description) = description {
+            schema.insert("description".to_owned(), description.into());
+        }
+        object.properties.insert(field.to_owned(), schema.into());
+        if required {
+            object.required.push(field.into());
+        }
+    }
+
+    fn add_flattened(generator: &mut SchemaGenerator, object: &mut ObjectSchema, required: bool) {
+        let mut schema = T::json_schema(generator);
+        if let Some(Value::Object(properties)) = schema.remove("properties") {
+            object.properties.extend(properties);
+        }
+        if required {
+            if let Some(Value::Array(fields)) = schema.remove("required") {
+                object.required.extend(fields);
+            }
+        }
+    }
+}
+
+impl<T> FieldSchema for PhantomData<T> {
+    fn name_field(_name: &mut SchemaName, _field: &str, _required: bool, _generic: bool) {}
+
+    fn id_field(_id: &mut SchemaName, _field: &str, _required: bool, _generic: bool) {}
+
+    fn add_field(
+        _generator: &mut SchemaGenerator,
+        _object: &mut ObjectSchema,
+        _field: &str,
+        _required: bool,
+        _nullable: bool,
+        _description: Option<&str>,
+    ) {
+    }
+
+    fn add_flattened(
+        _generator: &mut SchemaGenerator,
+        _object: &mut ObjectSchema,
+        _required: bool,
+    ) {
+    }
+}
@@ -1,29 +1,355 @@
 //! Serde integration helpers.
 
 use core::fmt;
+use core::marker::PhantomData;
+use std::borrow::Cow;
 
-/// Error returned when deserializing a structural type fails due to a missing required field.
+use serde::de::{IgnoredAny, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::access::Access;
+use crate::presence::{False, PatchValue, True};
+
+#[cfg(feature = "compact")]
+pub mod compact;
+
+/// Error returned when deserializing a structural type fails due to its selected fields.
+///
+/// Lists every missing required field and, with `#[structural(deny_absent_input)]`, every field
+/// given in the input although the selection makes it Absent. Fields are listed in declaration
+/// order under their serialized names (after `rename` and `rename_all`), so a client sees all of
+/// them at once.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct MissingFieldError {
-    field: &'static str,
+pub struct FieldsError {
+    missing: Vec<&'static str>,
+    unexpected: Vec<&'static str>,
 }
 
-impl MissingFieldError {
-    /// Create a new error for a missing field.
+impl FieldsError {
+    /// Create a new error for the given missing and unexpected fields.
+    #[must_use]
+    pub const fn new(missing: Vec<&'static str>, unexpected: Vec<&'static str>) -> Self {
+        Self {
+            missing,
+            unexpected,
+        }
+    }
+
+    /// Get the names of the missing required fields.
     #[must_use]
-    pub const fn new(field: &'static str) -> Self {
-        Self { field }
+    pub fn missing(&self) -> &[&'static str] {
+        &self.missing
     }
 
-    /// Get the name of the missing field.
+    /// Get the names of the fields given in the input although they are Absent.
     #[must_use]
-    pub const fn field(&self) -> &'static str {
-        self.field
+    pub fn unexpected(&self) -> &[&'static str] {
+        &self.unexpected
     }
 }
 
-impl fmt::Display for MissingFieldError {
+fn write_fields(f: &mut fmt::Formatter<'_>, kind: &str, fields: &[&str]) -> fmt::Result {
+    let plural = if fields.len() == 1 { "" } else { "s" };
+    write!(f, "{kind} field{plural} ")?;
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "`{field}`")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for FieldsError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "missing field `{}`", self.field)
+        if !self.missing.is_empty() {
+            write_fields(f, "missing", &self.missing)?;
+        }
+        if !self.unexpected.is_empty() {
+            if !self.missing.is_empty() {
+                f.write_str("; ")?;
+            }
+            write_fields(f, "unexpected", &self.unexpected)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for FieldsError {}
+
+/// Structural types that can report a [`FieldsError`] apart from the deserializer's errors,
+/// generated by `#[derive(Deserialize)]`.
+///
+/// `Deserialize` turns a `FieldsError` into the deserializer's error type, leaving only its
+/// message. Integrations that report the fields themselves, like the axum extractors, go through
+/// this trait instead.
+pub trait DeserializeFields<'de>: Sized {
+    /// Deserialize the input, then check it against the selection.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the deserializer if the input is malformed or a value has the wrong
+    /// type. Otherwise returns `Ok(Err(..))` listing the missing and unexpected fields, if any.
+    fn deserialize_fields<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Result<Self, FieldsError>, D::Error>;
+}
+
+/// A field of a generated deserialize helper: its container, if its key was given in the input.
+///
+/// The helper holds each field as `Given<PatchValue<T>>`, or as `Given<PhantomData<T>>` when the
+/// selection makes it Absent. The latter skips the value without needing `T: Deserialize`.
+#[doc(hidden)]
+pub struct Given<C>(pub Option<C>);
+
+impl<C> Given<C> {
+    /// Whether the field's key was given in the input.
+    #[must_use]
+    pub const fn is_given(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// The container, holding a missing value if the key was not given, and whether it was.
+    pub fn into_parts<T>(self) -> (C, bool)
+    where
+        C: Access<T>,
+    {
+        match self.0 {
+            Some(value) => (value, true),
+            None => match C::from_access(PhantomData) {
+                Ok(missing) => (missing, false),
+                Err(_) => unreachable!("helper containers can represent a missing value"),
+            },
+        }
+    }
+}
+
+impl<C> Default for Given<C> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Given<PatchValue<T>> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PatchValue::deserialize(deserializer).map(|value| Self(Some(value)))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Given<PhantomData<T>> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        IgnoredAny::deserialize(deserializer).map(|_| Self(Some(PhantomData)))
+    }
+}
+
+/// A field's `deserialize_with` function producing `T`, implemented by a marker the helper
+/// declares for each such field.
+///
+/// The function is generic over the deserializer, so a marker type stands in for it where a
+/// closure could only take one deserializer type.
+#[doc(hidden)]
+pub trait DeserializeFn<'de, T> {
+    /// Call the function.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the function.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error>;
+}
+
+/// Adapts a field's `deserialize_with` function producing `T` to the helper's container.
+///
+/// The function receives every value but `null`, which is read as `PatchValue::Null` like for
+/// fields without a function. Absent fields skip the value without calling it.
+#[doc(hidden)]
+pub trait DeserializeWith<T>: Sized {
+    /// Deserialize through `W`, or skip the value if the container cannot hold it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `W`, or of the deserializer when skipping the value.
+    fn deserialize_with<'de, D: Deserializer<'de>, W: DeserializeFn<'de, T>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error>;
+}
+
+impl<T> DeserializeWith<T> for Given<PatchValue<T>> {
+    fn deserialize_with<'de, D: Deserializer<'de>, W: DeserializeFn<'de, T>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct WithVisitor<T, W>(PhantomData<fn() -> (T, W)>);
+
+        impl<'de, T, W: DeserializeFn<'de, T>> Visitor<'de> for WithVisitor<T, W> {
+            type Value = PatchValue<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a value or null")
+            }
+
+            fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+                Ok(PatchValue::Null)
+            }
+
+            fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+                Ok(PatchValue::Null)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                W::deserialize(deserializer).map(PatchValue::Value)
+            }
+        }
+
+        deserializer
+            .deserialize_option(WithVisitor::<T, W>(PhantomData))
+            .map(|value| Self(Some(value)))
+    }
+}
+
+impl<T> DeserializeWith<T> for Given<PhantomData<T>> {
+    fn deserialize_with<'de, D: Deserializer<'de>, W: DeserializeFn<'de, T>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        IgnoredAny::deserialize(deserializer).map(|_| Self(Some(PhantomData)))
+    }
+}
+
+/// `T::default()` for a field whose presence needs a value, implemented for a presence's
+/// `AcceptsMissing`: [`False`] defaults `T` and requires `T: Default`, [`True`] keeps the missing
+/// key and does not.
+///
+/// `#[serde(default)]` bounds fields on this rather than on `T: Default` directly, so that
+/// selections leaving a field Optional, Patch or Absent do not need its type to implement
+/// `Default`.
+#[doc(hidden)]
+pub trait MissingDefault<T> {
+    /// The value to fill a missing key with, if any.
+    fn missing_default() -> Option<T>;
+}
+
+impl<T: Default> MissingDefault<T> for False {
+    fn missing_default() -> Option<T> {
+        Some(T::default())
+    }
+}
+
+impl<T> MissingDefault<T> for True {
+    fn missing_default() -> Option<T> {
+        None
+    }
+}
+
+/// Deserializes a `Cow<str>` field marked `#[serde(borrow)]`, borrowing from the input when it can.
+///
+/// Serde's derive does this for plain structs; the generated helper routes such fields here.
+#[doc(hidden)]
+pub fn borrow_cow_str<'de: 'a, 'a, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Cow<'a, str>, D::Error> {
+    struct CowStrVisitor;
+
+    impl<'a> Visitor<'a> for CowStrVisitor {
+        type Value = Cow<'a, str>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a string")
+        }
+
+        fn visit_borrowed_str<E: serde::de::Error>(self, value: &'a str) -> Result<Self::Value, E> {
+            Ok(Cow::Borrowed(value))
+        }
+
+        fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(value.to_owned()))
+        }
+
+        fn visit_string<E: serde::de::Error>(self, value: String) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(value))
+        }
+    }
+
+    deserializer.deserialize_str(CowStrVisitor)
+}
+
+/// Deserializes a `Cow<[u8]>` field marked `#[serde(borrow)]`, borrowing from the input when it
+/// can.
+#[doc(hidden)]
+pub fn borrow_cow_bytes<'de: 'a, 'a, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Cow<'a, [u8]>, D::Error> {
+    struct CowBytesVisitor;
+
+    impl<'a> Visitor<'a> for CowBytesVisitor {
+        type Value = Cow<'a, [u8]>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a byte array")
+        }
+
+        fn visit_borrowed_bytes<E: serde::de::Error>(
+            self,
+            value: &'a [u8],
+        ) -> Result<Self::Value, E> {
+            Ok(Cow::Borrowed(value))
+        }
+
+        fn visit_borrowed_str<E: serde::de::Error>(self, value: &'a str) -> Result<Self::Value, E> {
+            Ok(Cow::Borrowed(value.as_bytes()))
+        }
+
+        fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(value.to_vec()))
+        }
+
+        fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(value.as_bytes().to_vec()))
+        }
+
+        fn visit_byte_buf<E: serde::de::Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(value))
+        }
+
+        fn visit_string<E: serde::de::Error>(self, value: String) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(value.into_bytes()))
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'a>>(
+            self,
+            mut seq: A,
+        ) -> Result<Self::Value, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            Ok(Cow::Owned(bytes))
+        }
+    }
+
+    deserializer.deserialize_bytes(CowBytesVisitor)
+}
+
+/// Serializes `Missing` and `Null` as `none` and `Value` as `some`.
+///
+/// Structural types skip `Missing` fields entirely, so only `Null` reaches the serializer as `null`.
+impl<T: Serialize> Serialize for PatchValue<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PatchValue::Missing | PatchValue::Null => serializer.serialize_none(),
+            PatchValue::Value(value) => serializer.serialize_some(value),
+        }
+    }
+}
+
+/// Deserializes `null` as `Null` and anything else as `Value`.
+///
+/// Pair with `#[serde(default)]` so that a missing key yields `Missing`.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for PatchValue<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => PatchValue::Value(value),
+            None => PatchValue::Null,
+        })
     }
 }
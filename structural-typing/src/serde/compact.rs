@@ -0,0 +1,312 @@
+//! Compact encoding for formats that are not self-describing, like bincode or postcard.
+//!
+//! `skip_serializing_if` leaves such formats unable to tell which fields were skipped. The compact
+//! encoding writes a tuple instead: a presence bitmap, then the value of every field that has
+//! one, in declaration order. Each field takes two bits of the bitmap, one set when its value
+//! follows and one set for an explicit `null` (`PatchValue::Null`).
+//!
+//! Structs opt in with `#[structural(compact)]`, and are encoded compactly by wrapping them in
+//! [`Compact`] or through `#[serde(with = "structural_typing::serde::compact")]` on a field.
+//! Nested structural fields, those taking a field-set parameter like `Project<P>`, are written in
+//! the compact encoding too, so their types need `#[structural(compact)]` as well. Field
+//! attributes replacing serialization, like `#[serde(with = "...")]`, are rejected.
+//! Deserializing checks the bitmap against the target selection and reports missing fields like
+//! the regular encoding does. Every field type must implement `Serialize` and `Deserialize`, since
+//! values of Absent fields in the target must still be read past.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{Error as _, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::access::Access;
+use crate::presence::PatchValue;
+
+/// Encodes a structural value compactly when serialized and decodes it when deserialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compact<T>(pub T);
+
+/// Structural types that can be written in the compact encoding, generated by
+/// `#[structural(compact)]` alongside `Serialize`.
+pub trait CompactSerialize {
+    /// Number of fields in the struct.
+    const FIELDS: usize;
+
+    /// Record the state of every field in `bitmap`.
+    fn fill_bitmap(&self, bitmap: &mut Bitmap);
+
+    /// Write the value of every field marked as following in the bitmap.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the serializer.
+    fn write_values<S: SerializeTuple>(&self, tuple: &mut S) -> Result<(), S::Error>;
+}
+
+/// Structural types that can be read from the compact encoding, generated by
+/// `#[structural(compact)]` alongside `Deserialize`.
+pub trait CompactDeserialize<'de>: Sized {
+    /// Number of fields in the struct.
+    const FIELDS: usize;
+
+    /// Read the value of every field marked as following in `bitmap` and check the result
+    /// against the selection.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the deserializer, or a [`FieldsError`](super::FieldsError) listing
+    /// the fields the selection requires but the input lacks.
+    fn read_values<A: SeqAccess<'de>>(bitmap: &Bitmap, seq: &mut A) -> Result<Self, A::Error>;
+}
+
+impl<T: CompactSerialize> CompactSerialize for &T {
+    const FIELDS: usize = T::FIELDS;
+
+    fn fill_bitmap(&self, bitmap: &mut Bitmap) {
+        (**self).fill_bitmap(bitmap);
+    }
+
+    fn write_values<S: SerializeTuple>(&self, tuple: &mut S) -> Result<(), S::Error> {
+        (**self).write_values(tuple)
+    }
+}
+
+/// State of one field in the compact encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldState {
+    /// The field has no value and nothing follows.
+    Missing,
+    /// The field is an explicit `null` and nothing follows.
+    Null,
+    /// The field's value follows.
+    Value,
+}
+
+impl FieldState {
+    /// State of a field's container.
+    pub fn of<A: Access<T>, T>(value: &A) -> Self {
+        if value.get().is_some() {
+            FieldState::Value
+        } else if value.is_explicit_null() {
+            FieldState::Null
+        } else {
+            FieldState::Missing
+        }
+    }
+}
+
+/// Presence bitmap of the compact encoding, two bits per field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    bytes: Vec<u8>,
+}
+
+impl Bitmap {
+    /// An empty bitmap for `fields` fields, all missing.
+    #[must_use]
+    pub fn new(fields: usize) -> Self {
+        Self {
+            bytes: vec![0; (fields * 2).div_ceil(8)],
+        }
+    }
+
+    /// Set the state of the field at `index`.
+    pub fn set(&mut self, index: usize, state: FieldState) {
+        let (value, null) = match state {
+            FieldState::Missing => (false, false),
+            FieldState::Null => (false, true),
+            FieldState::Value => (true, false),
+        };
+        self.set_bit(index * 2, value);
+        self.set_bit(index * 2 + 1, null);
+    }
+
+    /// Get the state of the field at `index`.
+    #[must_use]
+    pub fn get(&self, index: usize) -> FieldState {
+        match (self.bit(index * 2), self.bit(index * 2 + 1)) {
+            (true, _) => FieldState::Value,
+            (false, true) => FieldState::Null,
+            (false, false) => FieldState::Missing,
+        }
+    }
+
+    /// Number of fields whose value follows.
+    #[must_use]
+    pub fn values(&self) -> usize {
+        self.bytes
+            .iter()
+            .map(|byte| (byte & 0b0101_0101).count_ones() as usize)
+            .sum()
+    }
+
+    /// Read the field at `index` as a `PatchValue`, taking its value from `seq` if one follows.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the deserializer, or an error if the value is announced but absent.
+    pub fn read<'de, T: Deserialize<'de>, A: SeqAccess<'de>>(
+        &self,
+        index: usize,
+        seq: &mut A,
+    ) -> Result<PatchValue<T>, A::Error> {
+        Ok(match self.get(index) {
+            FieldState::Missing => PatchValue::Missing,
+            FieldState::Null => PatchValue::Null,
+            FieldState::Value => match seq.next_element()? {
+                Some(value) => PatchValue::Value(value),
+                None => return Err(A::Error::custom("compact value ended before its fields")),
+            },
+        })
+    }
+
+    /// Read the nested structural field at `index` like [`Bitmap::read`], its value in the
+    /// compact encoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the deserializer, or an error if the value is announced but absent.
+    pub fn read_compact<'de, T: CompactDeserialize<'de>, A: SeqAccess<'de>>(
+        &self,
+        index: usize,
+        seq: &mut A,
+    ) -> Result<PatchValue<T>, A::Error> {
+        Ok(match self.read::<Compact<T>, A>(index, seq)? {
+            PatchValue::Missing => PatchValue::Missing,
+            PatchValue::Null => PatchValue::Null,
+            PatchValue::Value(Compact(value)) => PatchValue::Value(value),
+        })
+    }
+
+    fn bit(&self, bit: usize) -> bool {
+        self.bytes[bit / 8] & (1 << (bit % 8)) != 0
+    }
+
+    fn set_bit(&mut self, bit: usize, set: bool) {
+        if set {
+            self.bytes[bit / 8] |= 1 << (bit % 8);
+        } else {
+            self.bytes[bit / 8] &= !(1 << (bit % 8));
+        }
+    }
+}
+
+impl Serialize for Bitmap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bitmap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a presence bitmap")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+                Ok(bytes.to_vec())
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(bytes)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(bytes)
+            }
+        }
+
+        deserializer
+            .deserialize_bytes(BytesVisitor)
+            .map(|bytes| Self { bytes })
+    }
+}
+
+impl<T: CompactSerialize> Serialize for Compact<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: CompactDeserialize<'de>> Deserialize<'de> for Compact<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(Compact)
+    }
+}
+
+/// Serialize `value` in the compact encoding, for `#[serde(with = "...")]`.
+///
+/// # Errors
+///
+/// Returns the error of the serializer.
+pub fn serialize<T: CompactSerialize, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut bitmap = Bitmap::new(T::FIELDS);
+    value.fill_bitmap(&mut bitmap);
+    let mut tuple = serializer.serialize_tuple(1 + bitmap.values())?;
+    tuple.serialize_element(&bitmap)?;
+    value.write_values(&mut tuple)?;
+    tuple.end()
+}
+
+/// Deserialize a value from the compact encoding, for `#[serde(with = "...")]`.
+///
+/// # Errors
+///
+/// Returns the error of the deserializer, or an error if the bitmap does not match the struct,
+/// sets bits past its fields or both bits of one field, or lacks fields the selection requires.
+pub fn deserialize<'de, T: CompactDeserialize<'de>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    struct CompactVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: CompactDeserialize<'de>> Visitor<'de> for CompactVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a presence bitmap followed by field values")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+            let Some(bitmap) = seq.next_element::<Bitmap>()? else {
+                return Err(A::Error::custom("missing presence bitmap"));
+            };
+            if bitmap.bytes.len() != Bitmap::new(T::FIELDS).bytes.len() {
+                return Err(A::Error::custom(format_args!(
+                    "presence bitmap does not describe {} fields",
+                    T::FIELDS
+                )));
+            }
+            if (T::FIELDS * 2..bitmap.bytes.len() * 8).any(|bit| bitmap.bit(bit)) {
+                return Err(A::Error::custom(format_args!(
+                    "presence bitmap sets bits past its {} fields",
+                    T::FIELDS
+                )));
+            }
+            if let Some(index) =
+                (0..T::FIELDS).find(|index| bitmap.bit(index * 2) && bitmap.bit(index * 2 + 1))
+            {
+                return Err(A::Error::custom(format_args!(
+                    "presence bitmap marks field {index} as both a value and null"
+                )));
+            }
+            T::read_values(&bitmap, &mut seq)
+        }
+    }
+
+    // The tuple is as long as its bitmap says; formats without lengths only need an upper bound.
+    deserializer.deserialize_tuple(1 + T::FIELDS, CompactVisitor(PhantomData))
+}
@@ -0,0 +1,425 @@
+//! sqlx integration helpers.
+//!
+//! `#[derive(FromRow)]` on a `#[structural]` struct implements [`FromRow`](::sqlx::FromRow) for
+//! every field selection: Present fields read a required column, which may be `NULL` only for an
+//! `Option<_>` field, Optional and Patch fields read a nullable column that may also be missing
+//! from the row, and Absent fields are not read at all, so their types need not implement `Decode`.
+//! It also generates `insert_query` and `update_query`, which write the fields holding a value or
+//! an explicit `null` through [`Written`](crate::sqlx::Written).
+
+use core::fmt;
+use core::marker::PhantomData;
+use std::borrow::Cow;
+
+use ::sqlx::error::UnexpectedNullError;
+use ::sqlx::{ColumnIndex, Database, Decode, Encode, Error, QueryBuilder, Row, Type, ValueRef};
+
+use crate::access::{Access, is_absent_or_none};
+use crate::presence::PatchValue;
+
+/// Structural types that can be read from columns sharing a name prefix, generated by
+/// `#[derive(FromRow)]`.
+///
+/// `FromRow` reads with an empty prefix; nested fields marked `#[sqlx(prefix = "...")]` read their
+/// columns with the prefix added.
+pub trait FromPrefixedRow<'r, R: Row>: Sized {
+    /// Read the struct from the columns of `row` named `prefix` followed by each field's column.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the row, such as a missing column or `NULL` for a Present field.
+    fn from_prefixed_row(row: &'r R, prefix: &str) -> Result<Self, Error>;
+}
+
+/// A field of a structural row: `PatchValue<T>` reads the column, `PhantomData<T>` for Absent
+/// fields reads nothing.
+#[doc(hidden)]
+pub trait ReadColumn<'r, R: Row>: Sized {
+    /// Read the field from `column`, failing on a missing column if `required`, and on `NULL` if
+    /// `required` and the field type is not `nullable`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the row.
+    fn read_column(row: &'r R, column: &str, required: bool, nullable: bool)
+    -> Result<Self, Error>;
+}
+
+impl<'r, R, T> ReadColumn<'r, R> for PatchValue<T>
+where
+    R: Row,
+    T: Decode<'r, R::Database> + Type<R::Database>,
+    for<'a> &'a str: ColumnIndex<R>,
+{
+    fn read_column(
+        row: &'r R,
+        column: &str,
+        required: bool,
+        nullable: bool,
+    ) -> Result<Self, Error> {
+        if required {
+            // Check for `NULL` first, since some drivers decode it as a default. A nullable type
+            // like `Option<_>` decodes it itself.
+            if !nullable && row.try_get_raw(column)?.is_null() {
+                return Err(Error::ColumnDecode {
+                    index: format!("{column:?}"),
+                    source: Box::new(UnexpectedNullError),
+                });
+            }
+            return row.try_get::<T, _>(column).map(PatchValue::Value);
+        }
+        match row.try_get::<Option<T>, _>(column) {
+            Ok(Some(value)) => Ok(PatchValue::Value(value)),
+            Ok(None) => Ok(PatchValue::Null),
+            Err(Error::ColumnNotFound(_)) => Ok(PatchValue::Missing),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl<'r, R: Row, T> ReadColumn<'r, R> for PhantomData<T> {
+    fn read_column(
+        _row: &'r R,
+        _column: &str,
+        _required: bool,
+        _nullable: bool,
+    ) -> Result<Self, Error> {
+        Ok(PhantomData)
+    }
+}
+
+/// A nested structural field of a row: `PatchValue<T>` reads the nested struct, `PhantomData<T>`
+/// for Absent fields reads nothing.
+#[doc(hidden)]
+pub trait ReadNested<'r, R: Row>: Sized {
+    /// Read the nested struct from the columns named `prefix` followed by its field columns. Unless
+    /// `required`, a missing column or `NULL` in a required nested column makes the field missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the row.
+    fn read_nested(row: &'r R, prefix: &str, required: bool) -> Result<Self, Error>;
+}
+
+impl<'r, R: Row, T: FromPrefixedRow<'r, R>> ReadNested<'r, R> for PatchValue<T> {
+    fn read_nested(row: &'r R, prefix: &str, required: bool) -> Result<Self, Error> {
+        match T::from_prefixed_row(row, prefix) {
+            Ok(value) => Ok(PatchValue::Value(value)),
+            Err(err) if !required && is_missing(&err) => Ok(PatchValue::Missing),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl<'r, R: Row, T> ReadNested<'r, R> for PhantomData<T> {
+    fn read_nested(_row: &'r R, _prefix: &str, _required: bool) -> Result<Self, Error> {
+        Ok(PhantomData)
+    }
+}
+
+fn is_missing(err: &Error) -> bool {
+    match err {
+        Error::ColumnNotFound(_) => true,
+        Error::ColumnDecode { source, .. } => source.is::<UnexpectedNullError>(),
+        _ => false,
+    }
+}
+
+/// The column `name` with `prefix` in front.
+#[doc(hidden)]
+#[must_use]
+pub fn column<'a>(prefix: &str, name: &'a str) -> Cow<'a, str> {
+    if prefix.is_empty() {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(format!("{prefix}{name}"))
+    }
+}
+
+/// A column read by `FromRow`: `name` in the struct declaring it, reached through the prefixes of
+/// the nested fields leading there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    prefix: String,
+    name: &'static str,
+}
+
+impl Column {
+    /// Create a column named `name` in its struct, read with `prefix` in front.
+    #[must_use]
+    pub fn new(prefix: &str, name: &'static str) -> Self {
+        Self {
+            prefix: prefix.to_owned(),
+            name,
+        }
+    }
+
+    /// Get the prefix of the nested fields leading to the column, empty for the outer struct.
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Get the name of the column in the struct declaring it.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Writes the name the column has in a row: its prefix followed by its name.
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.prefix, self.name)
+    }
+}
+
+/// Structural types listing the columns their `FromRow` reads, generated by `#[derive(FromRow)]`.
+pub trait SelectColumns {
+    /// Push every column read for the selected fields, with `prefix` in front.
+    fn push_columns(prefix: &str, columns: &mut Vec<Column>);
+
+    /// Get every column read for the selected fields, in declaration order.
+    #[must_use]
+    fn columns() -> Vec<Column> {
+        let mut columns = Vec::new();
+        Self::push_columns("", &mut columns);
+        columns
+    }
+}
+
+/// Column list of a `SELECT`, rendered with `Display`.
+///
+/// Columns are written as they are named in the row, qualified with the table alias if one is
+/// set. A nested rule takes the columns under a prefix from another table instead, aliasing them
+/// back to their prefixed names: with `.nested("project_", "projects")`, `project_name` is
+/// written as `projects.name AS project_name`. Names are written unquoted and unescaped, so table
+/// aliases must not come from input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectList {
+    columns: Vec<Column>,
+    table: Option<String>,
+    nested: Vec<(String, String)>,
+}
+
+impl SelectList {
+    /// Create a column list for `columns`, unqualified.
+    #[must_use]
+    pub const fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            table: None,
+            nested: Vec::new(),
+        }
+    }
+
+    /// Qualify columns with the table alias `alias`.
+    #[must_use]
+    pub fn table(mut self, alias: impl Into<String>) -> Self {
+        self.table = Some(alias.into());
+        self
+    }
+
+    /// Read the columns under `prefix` from the table alias `alias`. The longest matching
+    /// prefix wins.
+    #[must_use]
+    pub fn nested(mut self, prefix: impl Into<String>, alias: impl Into<String>) -> Self {
+        self.nested.push((prefix.into(), alias.into()));
+        self
+    }
+
+    /// Get the columns of the list.
+    #[must_use]
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+}
+
+impl fmt::Display for SelectList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            let rule = self
+                .nested
+                .iter()
+                .filter(|(prefix, _)| column.prefix.starts_with(prefix.as_str()))
+                .max_by_key(|(prefix, _)| prefix.len());
+            match rule {
+                Some((prefix, alias)) => {
+                    let inner = &column.prefix[prefix.len()..];
+                    write!(f, "{alias}.{inner}{}", column.name)?;
+                    if !prefix.is_empty() {
+                        write!(f, " AS {column}")?;
+                    }
+                }
+                None => match &self.table {
+                    Some(table) => write!(f, "{table}.{column}")?,
+                    None => write!(f, "{column}")?,
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A bound value waiting for its placeholder.
+type Bind<'args, DB> = Box<dyn FnOnce(&mut QueryBuilder<'args, DB>) + 'args>;
+
+/// Columns written by a structural value and their values, in matching order.
+///
+/// Values are bound with [`QueryBuilder::push_bind`], so placeholders follow the database's
+/// style, like `?` or `$1`.
+pub struct Written<'args, DB: Database> {
+    columns: Vec<Column>,
+    values: Vec<Bind<'args, DB>>,
+}
+
+impl<'args, DB: Database> Written<'args, DB> {
+    /// Create an empty list.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            columns: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Add `column` with its value.
+    pub fn push<T: 'args + Encode<'args, DB> + Type<DB>>(&mut self, column: Column, value: T) {
+        self.columns.push(column);
+        self.values.push(Box::new(move |builder| {
+            builder.push_bind(value);
+        }));
+    }
+
+    /// Get the written columns.
+    #[must_use]
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Whether no column is written.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Push `INSERT INTO table (columns) VALUES (values)`, or `INSERT INTO table DEFAULT VALUES`
+    /// when no column is written.
+    ///
+    /// The table and column names are pushed into the SQL as written, unquoted and unescaped, so
+    /// `table` is `'static` to keep it to names fixed in the program; never build it from input.
+    pub fn push_insert(self, table: &'static str, builder: &mut QueryBuilder<'args, DB>) {
+        if self.is_empty() {
+            builder.push(format_args!("INSERT INTO {table} DEFAULT VALUES"));
+            return;
+        }
+        builder.push(format_args!("INSERT INTO {table} ("));
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                builder.push(", ");
+            }
+            builder.push(column);
+        }
+        builder.push(") VALUES (");
+        for (i, value) in self.values.into_iter().enumerate() {
+            if i > 0 {
+                builder.push(", ");
+            }
+            value(builder);
+        }
+        builder.push(")");
+    }
+
+    /// Push the assignments of an `UPDATE`: `column = value` for every written column, separated
+    /// by commas. Pushes nothing when no column is written.
+    ///
+    /// Column names are pushed into the SQL as written, unquoted and unescaped; only values are
+    /// bound.
+    pub fn push_set(self, builder: &mut QueryBuilder<'args, DB>) {
+        for (i, (column, value)) in self.columns.iter().zip(self.values).enumerate() {
+            if i > 0 {
+                builder.push(", ");
+            }
+            builder.push(format_args!("{column} = "));
+            value(builder);
+        }
+    }
+}
+
+impl<DB: Database> Default for Written<'_, DB> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structural types that can write their fields as columns, generated by `#[derive(FromRow)]`.
+pub trait WriteColumns<'args, DB: Database> {
+    /// Add every field holding a value or an explicit `null` to `written`, with `prefix` in front
+    /// of its column.
+    fn write_columns(self, prefix: &str, written: &mut Written<'args, DB>);
+}
+
+/// A field of a structural value being written: `PatchValue<T>` writes its value, or `NULL` for
+/// an explicit null, and `PhantomData<T>` for Absent fields writes nothing.
+#[doc(hidden)]
+pub trait WriteColumn<'args, DB: Database> {
+    /// Add the field to `written` unless it is missing.
+    fn write_column(self, column: Column, written: &mut Written<'args, DB>);
+}
+
+impl<'args, DB, T> WriteColumn<'args, DB> for PatchValue<T>
+where
+    DB: Database,
+    T: 'args + Encode<'args, DB> + Type<DB>,
+    Option<T>: Encode<'args, DB>,
+{
+    fn write_column(self, column: Column, written: &mut Written<'args, DB>) {
+        match self {
+            PatchValue::Missing => {}
+            PatchValue::Null => written.push(column, None::<T>),
+            PatchValue::Value(value) => written.push(column, value),
+        }
+    }
+}
+
+impl<'args, DB: Database, T> WriteColumn<'args, DB> for PhantomData<T> {
+    fn write_column(self, _column: Column, _written: &mut Written<'args, DB>) {}
+}
+
+/// A nested structural field being written: `PatchValue<T>` writes the nested value's columns,
+/// `PhantomData<T>` for Absent fields writes nothing.
+#[doc(hidden)]
+pub trait WriteNested<'args, DB: Database> {
+    /// Add the nested value's columns to `written`, with `prefix` in front.
+    fn write_nested(self, prefix: &str, written: &mut Written<'args, DB>);
+}
+
+impl<'args, DB: Database, T: WriteColumns<'args, DB>> WriteNested<'args, DB> for PatchValue<T> {
+    fn write_nested(self, prefix: &str, written: &mut Written<'args, DB>) {
+        if let PatchValue::Value(value) = self {
+            value.write_columns(prefix, written);
+        }
+    }
+}
+
+impl<'args, DB: Database, T> WriteNested<'args, DB> for PhantomData<T> {
+    fn write_nested(self, _prefix: &str, _written: &mut Written<'args, DB>) {}
+}
+
+/// Moves a field into the container it is written from: missing for Absent fields and Optional
+/// `None`, the value or an explicit `null` otherwise.
+#[doc(hidden)]
+pub fn into_written<A: Access<T>, C: Access<T>, T>(value: A) -> C {
+    let written = if is_absent_or_none(&value) {
+        C::from_access(PhantomData::<T>).ok()
+    } else {
+        C::from_access(value).ok()
+    };
+    match written {
+        Some(written) => written,
+        None => unreachable!("write containers can hold every non-Absent field"),
+    }
+}
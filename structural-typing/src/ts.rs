@@ -0,0 +1,394 @@
+//! TypeScript declarations for schemas and their selections.
+//!
+//! `#[structural(ts)]` adds `ts_interface()` to the schema module, returning an `interface` for the
+//! full schema, and implements [`TsType`](crate::ts::TsType) for every field selection, spelling
+//! it in terms of that interface: `Pick` for the selected fields (or `Omit` for the unselected
+//! ones, if fewer), and an object type for Optional and Patch fields, which become `?` properties
+//! that may also be `null`, and for nested structural fields holding a selection of their own.
+//! [`alias`](crate::ts::alias) names a selection:
+//!
+//! ```ignore
+//! #[structural(ts)]
+//! struct User {
+//!     id: u32,
+//!     name: String,
+//!     email: String,
+//! }
+//!
+//! type CreateUser = User<select!(user: name, email?)>;
+//!
+//! assert_eq!(
+//!     user::ts_interface(),
+//!     "export interface User {\n  id: number;\n  name: string;\n  email: string;\n}\n",
+//! );
+//! assert_eq!(
+//!     ts::alias::<CreateUser>("CreateUser"),
+//!     "export type CreateUser = Pick<User, \"name\"> & { email?: User[\"email\"] | null };\n",
+//! );
+//! ```
+//!
+//! Properties use their serialized names, and doc comments become `/** ... */` comments. Field types
+//! implement [`TsType`](crate::ts::TsType); implement it for types of your own.
+
+use core::marker::PhantomData;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::presence::PatchValue;
+
+/// A Rust type with a TypeScript counterpart.
+pub trait TsType {
+    /// Get the TypeScript type, like `string` or `Pick<User, "id">`.
+    fn ts_type() -> String;
+}
+
+/// Declare the TypeScript type of `T` as `export type name = ...;`.
+#[must_use]
+pub fn alias<T: TsType>(name: &str) -> String {
+    format!("export type {name} = {};\n", T::ts_type())
+}
+
+macro_rules! ts_type {
+    ($ts:literal: $($ty:ty),*) => {
+        $(
+            impl TsType for $ty {
+                fn ts_type() -> String {
+                    $ts.to_owned()
+                }
+            }
+        )*
+    };
+}
+
+ts_type!("boolean": bool);
+ts_type!("number": i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+ts_type!("string": char, str, String);
+ts_type!("null": ());
+
+impl<T: TsType> TsType for Option<T> {
+    fn ts_type() -> String {
+        format!("{} | null", T::ts_type())
+    }
+}
+
+macro_rules! ts_array {
+    ($($ty:ty),*) => {
+        $(
+            impl<T: TsType> TsType for $ty {
+                fn ts_type() -> String {
+                    format!("{}[]", element(T::ts_type()))
+                }
+            }
+        )*
+    };
+}
+
+ts_array!([T], Vec<T>, VecDeque<T>, BTreeSet<T>);
+
+impl<T: TsType, const N: usize> TsType for [T; N] {
+    fn ts_type() -> String {
+        format!("{}[]", element(T::ts_type()))
+    }
+}
+
+impl<T: TsType, S> TsType for HashSet<T, S> {
+    fn ts_type() -> String {
+        format!("{}[]", element(T::ts_type()))
+    }
+}
+
+impl<K: TsType, V: TsType> TsType for BTreeMap<K, V> {
+    fn ts_type() -> String {
+        format!("Record<{}, {}>", K::ts_type(), V::ts_type())
+    }
+}
+
+impl<K: TsType, V: TsType, S> TsType for HashMap<K, V, S> {
+    fn ts_type() -> String {
+        format!("Record<{}, {}>", K::ts_type(), V::ts_type())
+    }
+}
+
+macro_rules! ts_transparent {
+    ($($ty:ty),*) => {
+        $(
+            impl<T: TsType + ?Sized> TsType for $ty {
+                fn ts_type() -> String {
+                    T::ts_type()
+                }
+            }
+        )*
+    };
+}
+
+ts_transparent!(&T, Box<T>, Rc<T>, Arc<T>);
+
+impl<T: TsType + ToOwned + ?Sized> TsType for Cow<'_, T> {
+    fn ts_type() -> String {
+        T::ts_type()
+    }
+}
+
+macro_rules! ts_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: TsType),+> TsType for ($($name,)+) {
+            fn ts_type() -> String {
+                let types: &[String] = &[$($name::ts_type()),+];
+                format!("[{}]", types.join(", "))
+            }
+        }
+    };
+}
+
+ts_tuple!(A);
+ts_tuple!(A, B);
+ts_tuple!(A, B, C);
+ts_tuple!(A, B, C, D);
+
+/// Parenthesize a union or intersection used as an array element.
+fn element(ty: String) -> String {
+    if ty.contains(" | ") || ty.contains(" & ") {
+        format!("({ty})")
+    } else {
+        ty
+    }
+}
+
+/// A property name, quoted unless it is a valid identifier.
+fn property(name: &str) -> Cow<'_, str> {
+    let mut chars = name.chars();
+    let identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(format!("{name:?}"))
+    }
+}
+
+/// A `/** ... */` comment, indented by `indent`.
+fn jsdoc(out: &mut String, indent: &str, description: &str) {
+    if description.contains('\n') {
+        let _ = writeln!(out, "{indent}/**");
+        for line in description.lines() {
+            let _ = writeln!(out, "{}", format!("{indent} * {line}").trim_end());
+        }
+        let _ = writeln!(out, "{indent} */");
+    } else {
+        let _ = writeln!(out, "{indent}/** {description} */");
+    }
+}
+
+/// `interface` declaration of a full schema, built property by property.
+#[doc(hidden)]
+pub struct Interface {
+    name: &'static str,
+    description: Option<&'static str>,
+    extends: Vec<String>,
+    properties: String,
+}
+
+impl Interface {
+    /// Start the interface of `name`, with the struct's doc comment as its description.
+    #[must_use]
+    pub fn new(name: &'static str, description: Option<&'static str>) -> Self {
+        Self {
+            name,
+            description,
+            extends: Vec::new(),
+            properties: String::new(),
+        }
+    }
+
+    /// Add a property of type `ty`.
+    pub fn property(&mut self, name: &str, ty: &str, description: Option<&str>) {
+        if let Some(description) = description {
+            jsdoc(&mut self.properties, "  ", description);
+        }
+        let _ = writeln!(self.properties, "  {}: {ty};", property(name));
+    }
+
+    /// Add the properties of a `#[serde(flatten)]` field of type `ty`.
+    pub fn flatten(&mut self, ty: String) {
+        self.extends.push(ty);
+    }
+
+    /// Get the declaration.
+    #[must_use]
+    pub fn finish(self) -> String {
+        let mut out = String::new();
+        if let Some(description) = self.description {
+            jsdoc(&mut out, "", description);
+        }
+        let _ = write!(out, "export interface {}", self.name);
+        if !self.extends.is_empty() {
+            let _ = write!(out, " extends {}", self.extends.join(", "));
+        }
+        let _ = writeln!(out, " {{\n{}}}", self.properties);
+        out
+    }
+}
+
+/// A field spelled out as a property: an Optional or Patch field, or a nested field holding a
+/// selection other than the interface's, with its TypeScript type if not the interface's.
+struct Property {
+    name: &'static str,
+    required: bool,
+    ty: Option<String>,
+}
+
+/// A `#[serde(flatten)]` field: the TypeScript type of its selection, `None` if Absent, and
+/// whether it is the interface's.
+struct Flattened {
+    required: bool,
+    ty: Option<String>,
+    full: bool,
+}
+
+/// TypeScript type of a selection, spelled in terms of the schema's interface.
+#[doc(hidden)]
+pub struct Selection {
+    name: &'static str,
+    required: Vec<&'static str>,
+    /// Fields left out of the `Pick` of required fields: Absent fields and `properties`.
+    omitted: Vec<&'static str>,
+    properties: Vec<Property>,
+    flattened: Vec<Flattened>,
+}
+
+impl Selection {
+    /// Start a selection of the interface `name`.
+    #[must_use]
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            required: Vec::new(),
+            omitted: Vec::new(),
+            properties: Vec::new(),
+            flattened: Vec::new(),
+        }
+    }
+
+    /// Get the TypeScript type.
+    #[must_use]
+    pub fn finish(self) -> String {
+        let name = self.name;
+        let whole = self.omitted.is_empty()
+            && self
+                .flattened
+                .iter()
+                .all(|field| field.required && field.full);
+        if whole {
+            return name.to_owned();
+        }
+
+        let mut parts = Vec::new();
+        if !self.required.is_empty() {
+            // The interface extends those of flattened fields, whose properties `Omit` would keep.
+            if self.flattened.is_empty() && self.omitted.len() < self.required.len() {
+                parts.push(format!("Omit<{name}, {}>", keys(&self.omitted)));
+            } else {
+                parts.push(format!("Pick<{name}, {}>", keys(&self.required)));
+            }
+        }
+        for field in self.flattened {
+            match field.ty {
+                Some(ty) if field.required => parts.push(ty),
+                Some(ty) => parts.push(format!("Partial<{ty}>")),
+                None => {}
+            }
+        }
+        if !self.properties.is_empty() {
+            // Optional and Patch fields may also be given as `null`.
+            let properties: Vec<_> = self
+                .properties
+                .into_iter()
+                .map(|field| {
+                    let ty = field
+                        .ty
+                        .unwrap_or_else(|| format!("{name}[{:?}]", field.name));
+                    if field.required {
+                        format!("{}: {ty}", property(field.name))
+                    } else {
+                        format!("{}?: {ty} | null", property(field.name))
+                    }
+                })
+                .collect();
+            parts.push(format!("{{ {} }}", properties.join("; ")));
+        }
+        if parts.is_empty() {
+            "{}".to_owned()
+        } else {
+            parts.join(" & ")
+        }
+    }
+}
+
+/// Union of string literal types, like `"id" | "name"`.
+fn keys(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|name| format!("{name:?}"))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// A field of a selection: `PatchValue<T>` adds `T`'s type, and `PhantomData<T>` for Absent
+/// fields leaves it out.
+#[doc(hidden)]
+pub trait TsField {
+    /// Add the field to `selection`, as a nullable `?` property unless `required`. A nested field whose
+    /// type differs from `full`, its type in the interface, spells out its own selection.
+    fn select(selection: &mut Selection, name: &'static str, required: bool, full: Option<String>);
+
+    /// Add a `#[serde(flatten)]` field to `selection`, its properties optional unless `required`.
+    fn select_flattened(selection: &mut Selection, required: bool, full: String);
+}
+
+impl<T: TsType> TsField for PatchValue<T> {
+    fn select(selection: &mut Selection, name: &'static str, required: bool, full: Option<String>) {
+        let ty = full
+            .is_some()
+            .then(T::ts_type)
+            .filter(|ty| Some(ty) != full.as_ref());
+        if required && ty.is_none() {
+            selection.required.push(name);
+        } else {
+            selection.omitted.push(name);
+            selection.properties.push(Property { name, required, ty });
+        }
+    }
+
+    fn select_flattened(selection: &mut Selection, required: bool, full: String) {
+        let ty = T::ts_type();
+        selection.flattened.push(Flattened {
+            required,
+            full: ty == full,
+            ty: Some(ty),
+        });
+    }
+}
+
+impl<T> TsField for PhantomData<T> {
+    fn select(
+        selection: &mut Selection,
+        name: &'static str,
+        _required: bool,
+        _full: Option<String>,
+    ) {
+        selection.omitted.push(name);
+    }
+
+    fn select_flattened(selection: &mut Selection, _required: bool, _full: String) {
+        selection.flattened.push(Flattened {
+            required: false,
+            ty: None,
+            full: false,
+        });
+    }
+}
@@ -0,0 +1,244 @@
+//! utoipa integration helpers.
+//!
+//! `#[derive(ToSchema)]` on a `#[structural]` struct implements
+//! [`ToSchema`](::utoipa::ToSchema) (and through it [`PartialSchema`](::utoipa::PartialSchema))
+//! for every field selection: Present fields are listed under `required`, Optional and Patch
+//! fields are listed but nullable, and Absent fields are left out, so their types need not
+//! implement `ToSchema`. Fields use their serialized names, and fields with a serde default are
+//! not required.
+//!
+//! Each selection is a component of its own. Its default name lists the struct's name and its
+//! selected fields, with `-opt` marking fields that are not required, like
+//! `Project-id.name.description-opt`; selections of nested structural fields follow, like
+//! `Todo-title.project-id`. Type aliases listed in `#[structural(components(...))]` name the
+//! component of their selection after the alias instead:
+//!
+//! ```ignore
+//! #[structural(components(CreateProject, ProjectBasic))]
+//! #[derive(Deserialize, ToSchema)]
+//! struct Project { id: u32, name: String, description: String }
+//!
+//! type CreateProject = Project<select!(project: name, description?)>;
+//! type ProjectBasic = Project<select!(project: id, name)>;
+//! type TodoWithProject = Todo<select!(todo: all), select!(project: id, name)>;
+//!
+//! #[derive(OpenApi)]
+//! #[openapi(components(schemas(CreateProject, TodoWithProject)))]
+//! struct ApiDoc;
+//! ```
+//!
+//! The component of `TodoWithProject` then references `ProjectBasic`, since its `project` field
+//! has that type. The aliases stay plain aliases of the selection, so they must be in scope of the
+//! struct. Selections are compared with them through `TypeId`, so a struct listing components
+//! implements `ToSchema` only for its `'static` selections.
+//!
+//! Fields of primitive, `String`, collection and smart pointer types are described inline; fields
+//! of other types, nested structural fields included, reference their own component.
+//!
+//! The derive implements utoipa's public traits only, while utoipa describes `Option<T>`, `Vec<T>`
+//! and other wrappers through a hidden one, so wrappers of selections have no schema. Write arrays
+//! of selections in `#[utoipa::path]` bodies as `[ProjectBasic]`, which references the component.
+
+use core::marker::PhantomData;
+use std::borrow::Cow;
+
+use ::utoipa::ToSchema;
+use ::utoipa::openapi::RefOr;
+use ::utoipa::openapi::schema::{
+    Object, ObjectBuilder, OneOfBuilder, Ref, Schema, SchemaType, Type,
+};
+
+use crate::presence::PatchValue;
+
+/// Default name of a selection's component: the struct's name followed by the selected fields of
+/// its field sets.
+#[doc(hidden)]
+pub struct ComponentName {
+    name: String,
+    fields: usize,
+}
+
+impl ComponentName {
+    /// Start the name of a component of `name`.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            fields: 0,
+        }
+    }
+
+    /// Start the fields of the next field set.
+    pub fn selection(&mut self) {
+        self.name.push('-');
+        self.fields = 0;
+    }
+
+    /// Add a selected field, marked `-opt` if not required.
+    pub fn field(&mut self, field: &str, required: bool) {
+        if self.fields > 0 {
+            self.name.push('.');
+        }
+        self.fields += 1;
+        self.name.push_str(field);
+        if !required {
+            self.name.push_str("-opt");
+        }
+    }
+
+    /// Add the name of a type argument that is not a field set.
+    pub fn type_arg(&mut self, name: &str) {
+        self.name.push('-');
+        self.name.push_str(name);
+    }
+
+    /// Get the finished name.
+    #[must_use]
+    pub fn finish(self) -> Cow<'static, str> {
+        self.name.into()
+    }
+}
+
+/// Object schema of a selection, built field by field.
+#[doc(hidden)]
+pub struct ObjectSchema {
+    object: Object,
+}
+
+impl ObjectSchema {
+    /// Create a schema without properties.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            object: ObjectBuilder::new().schema_type(Type::Object).build(),
+        }
+    }
+
+    /// Finish the schema, with the struct's doc comment as its description.
+    #[must_use]
+    pub fn into_schema(mut self, description: Option<&str>) -> RefOr<Schema> {
+        self.object.description = description.map(str::to_owned);
+        RefOr::T(Schema::Object(self.object))
+    }
+}
+
+impl Default for ObjectSchema {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A field of a selection's schema: `PatchValue<T>` adds `T`'s schema, and `PhantomData<T>` for
+/// Absent fields adds nothing.
+#[doc(hidden)]
+pub trait FieldSchema {
+    /// Add the field to `object` under `properties`, and under `required` if it is. `inline`
+    /// fields are described in place, others reference their type's component.
+    fn add_field(
+        object: &mut ObjectSchema,
+        field: &str,
+        required: bool,
+        nullable: bool,
+        inline: bool,
+        description: Option<&str>,
+    );
+
+    /// Add the properties of a `#[serde(flatten)]` field to `object`, and its required
+    /// properties if the field itself is `required`.
+    fn add_flattened(object: &mut ObjectSchema, required: bool);
+
+    /// Add the components the field's schema refers to, including its type's own component
+    /// unless it is `inline`.
+    fn add_schemas(schemas: &mut Vec<(String, RefOr<Schema>)>, inline: bool);
+}
+
+impl<T: ToSchema> FieldSchema for PatchValue<T> {
+    fn add_field(
+        object: &mut ObjectSchema,
+        field: &str,
+        required: bool,
+        nullable: bool,
+        inline: bool,
+        description: Option<&str>,
+    ) {
+        let mut schema = if inline {
+            T::schema()
+        } else {
+            Ref::from_schema_name(T::name()).into()
+        };
+        if nullable {
+            schema = nullable_schema(schema);
+        }
+        if let Some(description) = description {
+            describe(&mut schema, description);
+        }
+        object.object.properties.insert(field.to_owned(), schema);
+        if required {
+            object.object.required.push(field.to_owned());
+        }
+    }
+
+    fn add_flattened(object: &mut ObjectSchema, required: bool) {
+        if let RefOr::T(Schema::Object(nested)) = T::schema() {
+            object.object.properties.extend(nested.properties);
+            if required {
+                object.object.required.extend(nested.required);
+            }
+        }
+    }
+
+    fn add_schemas(schemas: &mut Vec<(String, RefOr<Schema>)>, inline: bool) {
+        if !inline {
+            schemas.push((T::name().into_owned(), T::schema()));
+        }
+        T::schemas(schemas);
+    }
+}
+
+impl<T> FieldSchema for PhantomData<T> {
+    fn add_field(
+        _object: &mut ObjectSchema,
+        _field: &str,
+        _required: bool,
+        _nullable: bool,
+        _inline: bool,
+        _description: Option<&str>,
+    ) {
+    }
+
+    fn add_flattened(_object: &mut ObjectSchema, _required: bool) {}
+
+    fn add_schemas(_schemas: &mut Vec<(String, RefOr<Schema>)>, _inline: bool) {}
+}
+
+/// Allow `null` besides `schema`: added to its type if it has a single one, like utoipa does for
+/// `Option` fields, or as `oneOf` otherwise.
+fn nullable_schema(schema: RefOr<Schema>) -> RefOr<Schema> {
+    match schema {
+        RefOr::T(Schema::Object(mut object)) => {
+            if let SchemaType::Type(ty) = object.schema_type {
+                object.schema_type = [ty, Type::Null].into_iter().collect();
+                return RefOr::T(Schema::Object(object));
+            }
+            one_of_null(RefOr::T(Schema::Object(object)))
+        }
+        schema => one_of_null(schema),
+    }
+}
+
+fn one_of_null(schema: RefOr<Schema>) -> RefOr<Schema> {
+    OneOfBuilder::new()
+        .item(ObjectBuilder::new().schema_type(Type::Null))
+        .item(schema)
+        .into()
+}
+
+fn describe(schema: &mut RefOr<Schema>, description: &str) {
+    match schema {
+        RefOr::Ref(reference) => description.clone_into(&mut reference.description),
+        RefOr::T(Schema::Object(object)) => object.description = Some(description.to_owned()),
+        RefOr::T(Schema::Array(array)) => array.description = Some(description.to_owned()),
+        RefOr::T(Schema::OneOf(one_of)) => one_of.description = Some(description.to_owned()),
+        RefOr::T(_) => {}
+    }
+}
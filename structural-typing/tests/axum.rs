@@ -0,0 +1,177 @@
+#![cfg(feature = "axum")]
+
+use axum::Router;
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use axum::routing::{get, post};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use structural_typing::axum::{StructuralJson, StructuralPath, StructuralQuery};
+use structural_typing::{select, structural};
+use tower::ServiceExt;
+
+#[structural(deny_absent_input)]
+#[derive(Deserialize, Debug, PartialEq)]
+struct User {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+type CreateUser = User<select!(user: name, email?)>;
+type UserId = User<select!(user: id)>;
+
+#[structural]
+#[derive(Deserialize, Debug, PartialEq)]
+struct Article<U: user::Fields = select!(user: all)> {
+    title: String,
+    author: User<U>,
+}
+
+type CreateArticle = Article<select!(article: all), select!(user: name)>;
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/users",
+            post(|StructuralJson(user): StructuralJson<CreateUser>| async move { user.name }),
+        )
+        .route(
+            "/articles",
+            post(
+                |StructuralJson(article): StructuralJson<CreateArticle>| async move {
+                    article.author.name
+                },
+            ),
+        )
+        .route(
+            "/search",
+            get(|StructuralQuery(user): StructuralQuery<CreateUser>| async move { user.name }),
+        )
+        .route(
+            "/users/{id}",
+            get(|StructuralPath(user): StructuralPath<UserId>| async move { user.id.to_string() }),
+        )
+}
+
+async fn send(request: Request<Body>) -> (StatusCode, String) {
+    let response = app().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+async fn post_json(body: &str) -> (StatusCode, String) {
+    send(
+        Request::post("/users")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_owned()))
+            .unwrap(),
+    )
+    .await
+}
+
+async fn get_uri(uri: &str) -> (StatusCode, String) {
+    send(Request::get(uri).body(Body::empty()).unwrap()).await
+}
+
+fn rejection(body: &str) -> Value {
+    serde_json::from_str(body).unwrap()
+}
+
+#[tokio::test]
+async fn json_extracts_selected_fields() {
+    assert_eq!(
+        post_json(r#"{"name": "Alice"}"#).await,
+        (StatusCode::OK, "Alice".to_owned())
+    );
+}
+
+#[tokio::test]
+async fn json_lists_missing_and_unexpected_fields() {
+    let (status, body) = post_json(r#"{"id": 1, "email": "alice@example.com"}"#).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        rejection(&body),
+        json!({
+            "message": "missing field `name`; unexpected field `id`",
+            "missing": ["name"],
+            "unexpected": ["id"],
+            "invalid": [],
+        })
+    );
+}
+
+#[tokio::test]
+async fn json_names_field_of_wrong_type() {
+    let (status, body) = post_json(r#"{"name": 7}"#).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let body = rejection(&body);
+    assert_eq!(body["missing"], json!([]));
+    assert_eq!(body["invalid"][0]["field"], "name");
+    let message = body["invalid"][0]["message"].as_str().unwrap();
+    assert!(
+        message.starts_with("invalid type: integer `7`"),
+        "{message}"
+    );
+}
+
+#[tokio::test]
+async fn json_reports_nested_missing_fields_as_invalid() {
+    let (status, body) = send(
+        Request::post("/articles")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"title": "Hello", "author": {}}"#))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let body = rejection(&body);
+    assert_eq!(body["missing"], json!([]));
+    assert_eq!(body["invalid"][0]["field"], "author");
+    let message = body["invalid"][0]["message"].as_str().unwrap();
+    assert!(message.starts_with("missing field `name`"), "{message}");
+}
+
+#[tokio::test]
+async fn json_rejects_malformed_body_and_content_type() {
+    let (status, body) = post_json(r#"{"name": "#).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(rejection(&body)["invalid"], json!([]));
+
+    let (status, _) = post_json(r#"{"name": "Alice"} []"#).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send(
+        Request::post("/users")
+            .header(header::CONTENT_TYPE, "text/plain")
+            .body(Body::from(r#"{"name": "Alice"}"#))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[tokio::test]
+async fn query_lists_missing_fields() {
+    assert_eq!(
+        get_uri("/search?name=Alice").await,
+        (StatusCode::OK, "Alice".to_owned())
+    );
+
+    let (status, body) = get_uri("/search?email=a").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(rejection(&body)["missing"], json!(["name"]));
+}
+
+#[tokio::test]
+async fn path_parses_and_names_invalid_parameter() {
+    assert_eq!(
+        get_uri("/users/42").await,
+        (StatusCode::OK, "42".to_owned())
+    );
+
+    let (status, body) = get_uri("/users/abc").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(rejection(&body)["invalid"][0]["field"], "id");
+}
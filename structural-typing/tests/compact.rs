@@ -0,0 +1,191 @@
+#![cfg(feature = "compact")]
+
+use serde::{Deserialize, Serialize};
+use structural_typing::presence::PatchValue;
+use structural_typing::serde::compact::{Bitmap, Compact, FieldState};
+use structural_typing::{select, structural};
+
+#[structural(compact)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct User {
+    name: String,
+    email: String,
+    id: u64,
+}
+
+#[test]
+fn bitmap_tracks_two_bits_per_field() {
+    let mut bitmap = Bitmap::new(5);
+    bitmap.set(0, FieldState::Value);
+    bitmap.set(3, FieldState::Null);
+    bitmap.set(4, FieldState::Value);
+    assert_eq!(bitmap.get(0), FieldState::Value);
+    assert_eq!(bitmap.get(1), FieldState::Missing);
+    assert_eq!(bitmap.get(3), FieldState::Null);
+    assert_eq!(bitmap.get(4), FieldState::Value);
+    assert_eq!(bitmap.values(), 2);
+
+    bitmap.set(4, FieldState::Missing);
+    assert_eq!(bitmap.get(4), FieldState::Missing);
+    assert_eq!(bitmap.values(), 1);
+}
+
+#[test]
+fn compact_writes_bitmap_then_values() {
+    let user = user::empty().name("Alice".to_owned()).id(7);
+    let json = serde_json::to_string(&Compact(&user)).unwrap();
+    assert_eq!(json, r#"[[17],"Alice",7]"#);
+}
+
+#[test]
+fn compact_round_trip() {
+    let user = user::empty()
+        .name("Alice".to_owned())
+        .email(Some("alice@example.com".to_owned()))
+        .id(7);
+    let json = serde_json::to_string(&Compact(&user)).unwrap();
+
+    let Compact(decoded): Compact<User<select!(user: name, email?, id)>> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, user);
+}
+
+#[test]
+fn compact_round_trip_missing_optional() {
+    let user = user::empty().name("Alice".to_owned()).email(None).id(7);
+    let json = serde_json::to_string(&Compact(&user)).unwrap();
+
+    let Compact(decoded): Compact<User<select!(user: name, email?, id)>> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, user);
+}
+
+#[test]
+fn compact_reads_past_absent_fields() {
+    let user = user::empty()
+        .name("Alice".to_owned())
+        .email("alice@example.com".to_owned())
+        .id(7);
+    let json = serde_json::to_string(&Compact(&user)).unwrap();
+
+    let Compact(decoded): Compact<User<select!(user: id)>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.id, 7);
+}
+
+#[test]
+fn compact_reports_missing_fields() {
+    let user = user::empty().name("Alice".to_owned());
+    let json = serde_json::to_string(&Compact(&user)).unwrap();
+
+    let err = serde_json::from_str::<Compact<User<select!(user: name, email, id)>>>(&json)
+        .unwrap_err()
+        .to_string();
+    assert!(err.starts_with("missing fields `email`, `id`"), "{err}");
+}
+
+#[test]
+fn compact_rejects_bitmap_of_other_struct() {
+    let err = serde_json::from_str::<Compact<User<select!(user: name)>>>(r#"[[1,0],"Alice"]"#)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.starts_with("presence bitmap does not describe 3 fields"),
+        "{err}"
+    );
+}
+
+#[test]
+fn compact_rejects_bits_past_the_fields() {
+    let err = serde_json::from_str::<Compact<User<select!(user: name)>>>(r#"[[65],"Alice"]"#)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.starts_with("presence bitmap sets bits past its 3 fields"),
+        "{err}"
+    );
+}
+
+#[test]
+fn compact_rejects_field_both_value_and_null() {
+    let err = serde_json::from_str::<Compact<User<select!(user: name)>>>(r#"[[3],"Alice"]"#)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.starts_with("presence bitmap marks field 0 as both a value and null"),
+        "{err}"
+    );
+}
+
+#[test]
+fn compact_keeps_explicit_null() {
+    let user: User<select!(user: name~, email~, id~)> = user::empty()
+        .name(PatchValue::Value("Alice".to_owned()))
+        .email(PatchValue::Null)
+        .id(PatchValue::Missing);
+    let json = serde_json::to_string(&Compact(&user)).unwrap();
+    assert_eq!(json, r#"[[9],"Alice"]"#);
+
+    let Compact(decoded): Compact<User<select!(user: name~, email~, id~)>> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, user);
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Envelope {
+    version: u8,
+    #[serde(with = "structural_typing::serde::compact")]
+    user: User<select!(user: name, id?)>,
+}
+
+#[test]
+fn compact_with_attribute() {
+    let envelope = Envelope {
+        version: 1,
+        user: user::empty().name("Alice".to_owned()).id(None),
+    };
+    let json = serde_json::to_string(&envelope).unwrap();
+    assert_eq!(json, r#"{"version":1,"user":[[1],"Alice"]}"#);
+    assert_eq!(serde_json::from_str::<Envelope>(&json).unwrap(), envelope);
+}
+
+#[structural(compact)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Project {
+    id: u64,
+    name: String,
+}
+
+#[structural(compact)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Todo<P: project::Fields = select!(project: all)> {
+    title: String,
+    done: bool,
+    project: Project<P>,
+}
+
+#[test]
+fn compact_nests_structural_fields() {
+    let todo = todo::empty()
+        .title("Sweep".to_owned())
+        .project(project::empty().name("Home".to_owned()));
+    let json = serde_json::to_string(&Compact(&todo)).unwrap();
+    assert_eq!(json, r#"[[17],"Sweep",[[4],"Home"]]"#);
+}
+
+#[test]
+fn compact_round_trip_without_self_description() {
+    type Summary = select!(todo: title, done?, project { id?, name });
+
+    let todo: Summary = todo::empty()
+        .title("Sweep".to_owned())
+        .done(None)
+        .project(project::empty().id(None).name("Home".to_owned()));
+    let bytes = postcard::to_allocvec(&Compact(&todo)).unwrap();
+
+    let Compact(decoded): Compact<Summary> = postcard::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, todo);
+
+    type Names = select!(todo: title, project { name });
+    let Compact(names): Compact<Names> = postcard::from_bytes(&bytes).unwrap();
+    assert_eq!(names.project.name, "Home");
+}
@@ -2,4 +2,6 @@
 fn ui() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/*.rs");
+    #[cfg(feature = "utoipa")]
+    t.compile_fail("tests/ui/utoipa/*.rs");
 }
@@ -377,6 +377,27 @@ impl<F: test_struct::Fields<name = Present, email = Present>> TestStruct<F> {
     }
 }
 
+fn greeting<F: test_struct::field::name<Present>>(value: &TestStruct<F>) -> String {
+    format!("Hello, {}!", value.name)
+}
+
+fn greeting_through_fields<F: test_struct::Fields<name = Present>>(
+    value: &TestStruct<F>,
+) -> String {
+    greeting(value)
+}
+
+fn greeting_through_field<F: test_struct::field::name<Present>>(value: &TestStruct<F>) -> String {
+    value.greet()
+}
+
+#[test]
+fn field_requirements_match_fields_bounds_in_generic_code() {
+    let value = test_struct::empty().name("Dana".to_owned());
+    assert_eq!(greeting_through_fields(&value), "Hello, Dana!");
+    assert_eq!(greeting_through_field(&value), "Hello, Dana!");
+}
+
 #[test]
 fn select_absent_field() {
     type NameAbsent = select!(test_struct: name-);
@@ -434,3 +455,53 @@ fn select_mixed_presence() {
     assert_eq!(val.email, Some("charlie@example.com".to_owned()));
     assert_eq!(val.id, PhantomData);
 }
+
+#[test]
+fn take_field_returns_value_and_rest() {
+    let val = test_struct::empty()
+        .name("Alice".to_owned())
+        .email(Some("alice@example.com".to_owned()))
+        .id(1);
+
+    let (name, rest) = val.take_name();
+    assert_eq!(name, "Alice");
+    let _: PhantomData<String> = rest.name;
+    assert_eq!(rest.id, 1);
+
+    let (email, rest) = rest.take_email();
+    assert_eq!(email, Some("alice@example.com".to_owned()));
+
+    let (name, _): (PhantomData<String>, _) = rest.take_name();
+    assert_eq!(name, PhantomData);
+}
+
+#[test]
+fn without_field_drops_value() {
+    let val = test_struct::empty()
+        .name("Bob".to_owned())
+        .email("bob@example.com".to_owned())
+        .id(2);
+
+    let public: TestStruct<select!(test_struct: name, id)> = val.without_email();
+    assert_eq!(public.name, "Bob");
+
+    // Re-set after removal
+    let restored = public.email("new@example.com".to_owned());
+    assert_eq!(restored.email, "new@example.com");
+}
+
+#[test]
+fn take_in_generic_context() {
+    fn split_id<F: test_struct::Fields<id = Present>>(
+        data: TestStruct<F>,
+    ) -> (
+        u64,
+        TestStruct<test_struct::with::id<structural_typing::presence::Absent, F>>,
+    ) {
+        data.take_id()
+    }
+
+    let (id, rest) = split_id(test_struct::empty().name("Carol".to_owned()).id(3));
+    assert_eq!(id, 3);
+    assert_eq!(rest.name, "Carol");
+}
@@ -0,0 +1,264 @@
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use structural_typing::{
+    access::Access,
+    presence::{CustomPresence, False, InferPresence, Optional, Present, True},
+    select, structural,
+};
+
+/// Field stored on the heap.
+#[derive(Clone, Debug, PartialEq)]
+struct Heap<T>(Box<T>);
+
+impl<T> Heap<T> {
+    fn new(value: T) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+impl<T> Access<T> for Heap<T> {
+    const IS_ABSENT: bool = false;
+
+    fn get(&self) -> Option<&T> {
+        Some(&self.0)
+    }
+    fn get_mut(&mut self) -> Option<&mut T> {
+        Some(&mut self.0)
+    }
+    fn into_value(self) -> Result<T, Self> {
+        Ok(*self.0)
+    }
+    fn from_value(value: T) -> Self {
+        Heap::new(value)
+    }
+}
+
+/// Marker storing fields as `Heap<T>`.
+struct Boxed;
+
+impl CustomPresence for Boxed {
+    type Output<T> = Heap<T>;
+    type AlwaysPresent = True;
+    type AcceptsMissing = False;
+
+    fn take<T>(value: Self::Output<T>, _always_present: ()) -> T {
+        *value.0
+    }
+
+    fn missing<T>(accepts_missing: Infallible) -> Self::Output<T> {
+        match accepts_missing {}
+    }
+
+    fn as_ref<T>(value: &Self::Output<T>) -> Self::Output<&T> {
+        Heap::new(&*value.0)
+    }
+
+    fn as_mut<T>(value: &mut Self::Output<T>) -> Self::Output<&mut T> {
+        Heap::new(&mut *value.0)
+    }
+}
+
+impl<T> InferPresence<T> for Heap<T> {
+    type Presence = Boxed;
+}
+
+/// Field that may be unset, stored without `Option` in its public API.
+#[derive(Clone, Debug, PartialEq)]
+struct Slot<T>(Option<T>);
+
+impl<T> Access<T> for Slot<T> {
+    const IS_ABSENT: bool = false;
+
+    fn get(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+    fn get_mut(&mut self) -> Option<&mut T> {
+        self.0.as_mut()
+    }
+    fn into_value(self) -> Result<T, Self> {
+        self.0.ok_or(Slot(None))
+    }
+    fn from_value(value: T) -> Self {
+        Slot(Some(value))
+    }
+    fn from_option(value: Option<T>) -> Result<Self, Option<T>> {
+        Ok(Slot(value))
+    }
+}
+
+/// Marker storing fields as `Slot<T>`.
+struct Slotted;
+
+impl CustomPresence for Slotted {
+    type Output<T> = Slot<T>;
+    type AlwaysPresent = False;
+    type AcceptsMissing = True;
+
+    fn take<T>(_value: Self::Output<T>, always_present: Infallible) -> T {
+        match always_present {}
+    }
+
+    fn missing<T>(_accepts_missing: ()) -> Self::Output<T> {
+        Slot(None)
+    }
+
+    fn as_ref<T>(value: &Self::Output<T>) -> Self::Output<&T> {
+        Slot(value.0.as_ref())
+    }
+
+    fn as_mut<T>(value: &mut Self::Output<T>) -> Self::Output<&mut T> {
+        Slot(value.0.as_mut())
+    }
+}
+
+impl<T> InferPresence<T> for Slot<T> {
+    type Presence = Slotted;
+}
+
+#[structural]
+#[derive(Clone, Debug, PartialEq)]
+struct Document {
+    title: String,
+    body: String,
+}
+
+#[test]
+fn builder_infers_custom_presence() {
+    let doc: Document<select!(document: title, body<Boxed>)> = document::empty()
+        .title("Notes".to_owned())
+        .body(Heap::new("Long text".to_owned()));
+    assert_eq!(doc.body.get(), Some(&"Long text".to_owned()));
+}
+
+#[test]
+fn extract_custom_to_builtin() {
+    let doc = document::empty()
+        .title("Notes".to_owned())
+        .body(Heap::new("Text".to_owned()));
+
+    let (present, rest) = doc.clone().extract::<select!(document: body)>();
+    assert_eq!(present.body, "Text");
+    assert_eq!(rest.title, "Notes");
+    let _: PhantomData<String> = rest.body;
+
+    let (optional, _) = doc.extract::<select!(document: body?)>();
+    assert_eq!(optional.body, Some("Text".to_owned()));
+}
+
+#[test]
+fn try_extract_into_custom() {
+    let some = document::empty().body(Some("Text".to_owned()));
+    let (boxed, _) = some
+        .try_extract::<select!(document: body<Boxed>)>()
+        .unwrap();
+    assert_eq!(*boxed.body.0, "Text");
+
+    let none = document::empty().body(None::<String>);
+    assert!(
+        none.try_extract::<select!(document: body<Boxed>)>()
+            .is_err()
+    );
+}
+
+#[test]
+fn extract_builtin_into_custom_and_back() {
+    let doc = document::empty().title("Title".to_owned());
+    let (boxed, _) = doc.extract::<select!(document: title<Boxed>)>();
+    let (present, _) = boxed.extract::<select!(document: title<Present>)>();
+    assert_eq!(present.title, "Title");
+}
+
+#[test]
+fn merge_with_custom_presence() {
+    let boxed = document::empty().body(Heap::new("Boxed".to_owned()));
+    let optional = document::empty().body(None::<String>);
+
+    let merged = boxed.clone().merge(optional);
+    assert_eq!(merged.body, Heap::new("Boxed".to_owned()));
+
+    let replaced = boxed.merge(document::empty().body(Some("Optional".to_owned())));
+    assert_eq!(replaced.body, Heap::new("Optional".to_owned()));
+
+    let filled = document::empty()
+        .title("Kept".to_owned())
+        .merge(document::empty().body(Heap::new("Added".to_owned())));
+    assert_eq!(filled.title, "Kept");
+    assert_eq!(*filled.body.0, "Added");
+}
+
+#[test]
+fn optional_extraction_is_infallible_from_custom() {
+    fn to_optional<F: document::Fields<body = Boxed>>(
+        doc: Document<F>,
+    ) -> Document<document::with::body<Optional, F>> {
+        doc.extract::<document::with::body<Optional, F>>().0
+    }
+
+    let doc = document::empty().body(Heap::new("Body".to_owned()));
+    assert_eq!(to_optional(doc).body, Some("Body".to_owned()));
+}
+
+#[test]
+fn extract_into_custom_accepting_missing() {
+    let doc = document::empty().title("Title".to_owned());
+    let (slotted, _) = doc.extract::<select!(document: title<Slotted>, body<Slotted>)>();
+    assert_eq!(slotted.title, Slot(Some("Title".to_owned())));
+    assert_eq!(slotted.body, Slot(None));
+
+    let (present, _) = slotted.try_extract::<select!(document: title)>().unwrap();
+    assert_eq!(present.title, "Title");
+}
+
+#[test]
+fn merge_fills_custom_accepting_missing() {
+    let filled = document::empty()
+        .body("Body".to_owned())
+        .merge(document::empty().body(Slot(None::<String>)));
+    assert_eq!(filled.body, Slot(Some("Body".to_owned())));
+
+    let kept = document::empty()
+        .body("Other".to_owned())
+        .merge(document::empty().body(Slot(Some("Kept".to_owned()))));
+    assert_eq!(kept.body, Slot(Some("Kept".to_owned())));
+}
+
+#[test]
+fn derived_utility_presences() {
+    structural_typing::assert_satisfies!(
+        document: select!(document: body<Slotted>),
+        document::ops::Partial<select!(document: body<Slotted>)>
+    );
+    let doc: Document<document::ops::Partial<select!(document: title<Boxed>)>> =
+        document::empty().title(None::<String>);
+    assert_eq!(doc.title, None);
+    let doc: Document<document::ops::Required<select!(document: title<Slotted>)>> =
+        document::empty().title("Title".to_owned());
+    assert_eq!(doc.title, "Title");
+}
+
+#[cfg(feature = "serde")]
+mod serde_support {
+    use serde::Deserialize;
+    use structural_typing::{select, structural};
+
+    use super::{Boxed, Heap};
+
+    #[structural]
+    #[derive(Debug, Deserialize)]
+    struct Article {
+        title: String,
+        body: String,
+    }
+
+    #[test]
+    fn deserialize_into_custom_presence() {
+        let article: Article<select!(article: title, body<Boxed>)> =
+            serde_json::from_str(r#"{"title":"T","body":"B"}"#).unwrap();
+        assert_eq!(article.body, Heap::new("B".to_owned()));
+
+        let missing: Result<Article<select!(article: title, body<Boxed>)>, _> =
+            serde_json::from_str(r#"{"title":"T"}"#);
+        assert!(missing.is_err());
+    }
+}
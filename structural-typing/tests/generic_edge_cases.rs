@@ -168,3 +168,16 @@ fn type_changing_setter_nested() {
     // Verify type changed
     assert_eq!(store.item_field.data, 42);
 }
+
+#[test]
+fn take_preserves_nested_generics() {
+    let item = item::empty::<i32>().data(7);
+    let store = store::empty::<item::with::all, i32>().item_field(item);
+
+    let (item, rest) = store.take_item_field();
+    assert_eq!(item.data, 7);
+    let restored = rest.item_field(item);
+    let emptied: Store<select!(store: item_field-), item::with::all, i32> =
+        restored.without_item_field();
+    let _: core::marker::PhantomData<Item<item::with::all, i32>> = emptied.item_field;
+}
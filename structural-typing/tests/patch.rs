@@ -0,0 +1,182 @@
+use core::marker::PhantomData;
+
+use structural_typing::{
+    access::Access,
+    presence::{Patch, PatchValue},
+    select, structural,
+};
+
+#[structural]
+#[derive(Clone, Debug, PartialEq)]
+struct Profile {
+    name: String,
+    bio: String,
+    age: u32,
+}
+
+#[test]
+fn builder_infers_patch() {
+    let val: Profile<select!(profile: name, bio~)> = profile::empty()
+        .name("Alice".to_owned())
+        .bio(PatchValue::Null);
+    assert_eq!(val.name, "Alice");
+    assert_eq!(val.bio, PatchValue::Null);
+}
+
+#[test]
+fn select_all_patch() {
+    let val: Profile<select!(profile: all~)> = profile::empty()
+        .name(PatchValue::Value("Bob".to_owned()))
+        .bio(PatchValue::Missing)
+        .age(PatchValue::Null);
+    assert!(val.name.is_value());
+    assert!(val.bio.is_missing());
+    assert!(val.age.is_null());
+}
+
+#[test]
+fn select_patch_with_spread() {
+    type BioPatch<F> = select!(profile: bio~, ..F);
+    let val: Profile<BioPatch<select!(profile: name)>> = profile::empty()
+        .name("Carol".to_owned())
+        .bio(PatchValue::Value("Hi".to_owned()));
+    assert_eq!(val.name, "Carol");
+    assert_eq!(val.bio, PatchValue::Value("Hi".to_owned()));
+}
+
+#[test]
+fn access_patch_value() {
+    let mut val = profile::empty().bio(PatchValue::Value("Hello".to_owned()));
+    assert_eq!(val.bio.get(), Some(&"Hello".to_owned()));
+    if let Some(bio) = Access::<String>::get_mut(&mut val.bio) {
+        bio.push('!');
+    }
+    assert_eq!(val.bio.into_option(), Some("Hello!".to_owned()));
+
+    let null: PatchValue<String> = PatchValue::Null;
+    assert_eq!(Access::<String>::get(&null), None);
+    assert!(!Access::<String>::is_absent(&null));
+    assert!(Access::<String>::is_absent(&PatchValue::<String>::Missing));
+}
+
+#[test]
+fn merge_null_clears() {
+    let current = profile::empty()
+        .name("Alice".to_owned())
+        .bio(Some("Old bio".to_owned()));
+    let patch = profile::empty().bio(PatchValue::Null);
+
+    let merged = current.merge(patch);
+    assert_eq!(merged.name, "Alice");
+    assert_eq!(merged.bio, PatchValue::Null);
+}
+
+#[test]
+fn merge_missing_keeps() {
+    let current = profile::empty().name("Alice".to_owned()).age(30);
+    let patch = profile::empty()
+        .name(PatchValue::Missing)
+        .age(PatchValue::Value(31));
+
+    let merged = current.merge(patch);
+    assert_eq!(merged.name, PatchValue::Value("Alice".to_owned()));
+    assert_eq!(merged.age, PatchValue::Value(31));
+}
+
+#[test]
+fn merge_missing_over_absent_stays_missing() {
+    let patch = profile::empty().bio(PatchValue::<String>::Missing);
+    let merged = profile::empty().merge(patch);
+    assert_eq!(merged.bio, PatchValue::Missing);
+}
+
+#[test]
+fn merge_present_over_patch() {
+    let patch = profile::empty().name(PatchValue::Null);
+    let merged = patch.merge(profile::empty().name("Dave".to_owned()));
+    assert_eq!(merged.name, "Dave");
+}
+
+#[test]
+fn merge_optional_over_patch() {
+    let patch = profile::empty().bio(PatchValue::Null);
+
+    let merged = patch.clone().merge(profile::empty().bio(None));
+    assert_eq!(merged.bio, PatchValue::Null);
+
+    let merged = patch.merge(profile::empty().bio(Some("New".to_owned())));
+    assert_eq!(merged.bio, PatchValue::Value("New".to_owned()));
+}
+
+#[test]
+fn apply_patch_to_optional() {
+    let current = profile::empty().bio(Some("Old".to_owned()));
+    let merged = current.merge(profile::empty().bio(PatchValue::Missing));
+    let (bio_only, _) = merged.extract::<select!(profile: bio?)>();
+    assert_eq!(bio_only.bio, Some("Old".to_owned()));
+
+    assert_eq!(
+        PatchValue::Null.apply(Some("Old".to_owned())),
+        None::<String>
+    );
+    assert_eq!(PatchValue::Missing.apply(Some(1)), Some(1));
+    assert_eq!(PatchValue::Value(2).apply(Some(1)), Some(2));
+}
+
+#[test]
+fn extract_into_patch() {
+    let full = profile::empty().name("Eve".to_owned()).bio(None).age(40);
+
+    let (patch, remainder) = full.extract::<select!(profile: name~, bio~)>();
+    assert_eq!(patch.name, PatchValue::Value("Eve".to_owned()));
+    assert_eq!(patch.bio, PatchValue::Missing);
+    assert_eq!(patch.age, PhantomData);
+    assert_eq!(remainder.age, 40);
+    assert_eq!(remainder.name, PhantomData);
+
+    let (patch, _) = profile::empty().extract::<select!(profile: all~)>();
+    assert_eq!(patch.name, PatchValue::Missing);
+}
+
+#[test]
+fn extract_patch_to_optional() {
+    let patch = profile::empty()
+        .name(PatchValue::Value("Frank".to_owned()))
+        .bio(PatchValue::Null);
+    let (optional, _) = patch.extract::<select!(profile: name?, bio?)>();
+    assert_eq!(optional.name, Some("Frank".to_owned()));
+    assert_eq!(optional.bio, None);
+}
+
+#[test]
+fn try_extract_patch_to_present() {
+    let patch = profile::empty()
+        .name(PatchValue::Value("Grace".to_owned()))
+        .age(PatchValue::Value(25));
+    let (present, _) = patch.try_extract::<select!(profile: name, age)>().unwrap();
+    assert_eq!(present.name, "Grace");
+    assert_eq!(present.age, 25);
+
+    let null_age = profile::empty()
+        .name(PatchValue::Value("Heidi".to_owned()))
+        .age(PatchValue::<u32>::Null);
+    let cloned = null_age.clone();
+    assert_eq!(
+        null_age
+            .try_extract::<select!(profile: name, age)>()
+            .unwrap_err(),
+        cloned
+    );
+}
+
+#[test]
+fn try_extract_patch_preserves_state() {
+    let patch = profile::empty()
+        .name(PatchValue::<String>::Missing)
+        .bio(PatchValue::<String>::Null);
+    let (same, _) = patch
+        .try_extract::<profile::with::all<Patch>>()
+        .unwrap_or_else(|_| panic!("patch to patch cannot fail"));
+    assert_eq!(same.name, PatchValue::Missing);
+    assert_eq!(same.bio, PatchValue::Null);
+}
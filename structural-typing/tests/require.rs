@@ -0,0 +1,78 @@
+use structural_typing::{presence::PatchValue, select, structural};
+
+#[structural]
+#[derive(Clone, Debug, PartialEq)]
+struct User {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+#[test]
+fn require_upgrades_selected_fields() {
+    let user: User<select!(user: all?)> = user::empty()
+        .id(Some(1))
+        .name(Some("Alice".to_owned()))
+        .email(None);
+
+    let required = user.require::<select!(user: id, name)>().unwrap();
+    let id: u32 = required.id;
+    let name: String = required.name;
+    let email: Option<String> = required.email;
+    assert_eq!(id, 1);
+    assert_eq!(name, "Alice");
+    assert_eq!(email, None);
+}
+
+#[test]
+fn require_reports_every_missing_field() {
+    let user: User<select!(user: all?)> = user::empty().id(Some(1)).name(None).email(None);
+
+    let err = user.clone().require::<select!(user: all)>().unwrap_err();
+    assert_eq!(err.missing(), ["name", "email"]);
+    assert_eq!(err.value(), &user);
+    assert_eq!(err.to_string(), "missing required fields `name`, `email`");
+    assert_eq!(err.into_value(), user);
+}
+
+#[test]
+fn require_single_missing_field_message() {
+    let user = user::empty().id(None::<u32>);
+    let err = user.require::<select!(user: id)>().unwrap_err();
+    assert_eq!(err.to_string(), "missing required field `id`");
+}
+
+#[test]
+fn require_keeps_unselected_fields() {
+    let user = user::empty()
+        .id(5)
+        .name(Some("Bob".to_owned()))
+        .email(PatchValue::Null);
+
+    let required = user.require::<select!(user: name)>().unwrap();
+    assert_eq!(required.id, 5);
+    assert_eq!(required.name, "Bob");
+    assert_eq!(required.email, PatchValue::Null);
+}
+
+#[test]
+fn require_missing_absent_field() {
+    let user = user::empty().id(1);
+    let err = user.require::<select!(user: id, email)>().unwrap_err();
+    assert_eq!(err.missing(), ["email"]);
+}
+
+#[test]
+fn require_in_generic_context() {
+    fn named<F: user::Fields<name = structural_typing::presence::Optional>>(
+        user: User<F>,
+    ) -> Option<String> {
+        user.require::<select!(user: name)>().ok().map(|u| u.name)
+    }
+
+    assert_eq!(
+        named(user::empty().name(Some("Carol".to_owned()))),
+        Some("Carol".to_owned())
+    );
+    assert_eq!(named(user::empty().name(None::<String>)), None);
+}
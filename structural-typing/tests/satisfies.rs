@@ -0,0 +1,84 @@
+use structural_typing::{
+    assert_satisfies,
+    presence::{Optional, PatchValue, Present},
+    select, structural,
+};
+
+#[structural]
+#[derive(Clone, Debug, PartialEq)]
+struct User {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+assert_satisfies!(user: user::with::all, user::with::all);
+assert_satisfies!(user: select!(user: id, name), select!(user: id, name?));
+assert_satisfies!(user: select!(user: id, name?), select!(user: name~));
+assert_satisfies!(user: select!(user: id, email~), select!(user: email?));
+assert_satisfies!(user: user::with::all, user::ops::Partial<user::with::all>);
+
+fn needs_id<F: user::Satisfies<select!(user: id, name?)>>(
+    user: User<F>,
+) -> User<select!(user: id, name?)> {
+    user.narrow()
+}
+
+fn asks_less<G: user::SubsetOf<select!(user: id, name)>>() {}
+
+#[test]
+fn narrow_drops_unselected_fields() {
+    let user = user::empty()
+        .id(1)
+        .name("Alice".to_owned())
+        .email("alice@example.com".to_owned());
+
+    let narrowed = user.narrow::<select!(user: id, name?)>();
+    assert_eq!(narrowed.id, 1);
+    assert_eq!(narrowed.name, Some("Alice".to_owned()));
+    let _: core::marker::PhantomData<String> = narrowed.email;
+}
+
+#[test]
+fn generic_narrow_through_satisfies() {
+    let full = user::empty()
+        .id(2)
+        .name("Bob".to_owned())
+        .email("bob@example.com".to_owned());
+    assert_eq!(needs_id(full).name, Some("Bob".to_owned()));
+
+    let minimal = user::empty().id(3).name(None::<String>);
+    let narrowed = needs_id(minimal);
+    assert_eq!(narrowed.id, 3);
+    assert_eq!(narrowed.name, None);
+}
+
+#[test]
+fn narrow_into_patch() {
+    let user = user::empty().name(Some("Carol".to_owned()));
+    let patch = user.narrow::<select!(user: name~)>();
+    assert_eq!(patch.name, PatchValue::Value("Carol".to_owned()));
+}
+
+#[test]
+fn narrow_unset_optional_into_missing_patch() {
+    let user = user::empty().name(None::<String>);
+    let patch = user.narrow::<select!(user: name~)>();
+    assert_eq!(patch.name, PatchValue::Missing);
+
+    let user = user::empty().name(PatchValue::<String>::Null);
+    let patch = user.narrow::<select!(user: name~)>();
+    assert_eq!(patch.name, PatchValue::Null);
+}
+
+#[test]
+fn subset_of_is_satisfies_reversed() {
+    asks_less::<select!(user: id)>();
+    asks_less::<select!(user: id?, name~)>();
+    asks_less::<user::with::all<structural_typing::presence::Absent>>();
+}
+
+#[test]
+fn assert_inside_function() {
+    assert_satisfies!(user: user::with::all<Present>, user::with::all<Optional>);
+}
@@ -0,0 +1,171 @@
+#![cfg(all(feature = "schemars", feature = "serde"))]
+
+use schemars::generate::SchemaSettings;
+use schemars::{JsonSchema, schema_for};
+use serde::Deserialize;
+use serde_json::json;
+use structural_typing::{select, structural};
+
+/// Not `JsonSchema`: only describable when Absent.
+#[derive(Debug)]
+struct Connection;
+
+/// A registered user.
+#[structural]
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct User {
+    id: u32,
+    /// Full name.
+    display_name: String,
+    #[serde(rename = "mail")]
+    email: String,
+    #[serde(default)]
+    verified: bool,
+    connection: Connection,
+}
+
+#[structural]
+#[derive(Deserialize, JsonSchema)]
+struct Project {
+    id: u32,
+    name: String,
+}
+
+#[structural]
+#[derive(Deserialize, JsonSchema)]
+struct Todo<P: project::Fields = select!(project: all)> {
+    title: String,
+    project: Project<P>,
+}
+
+#[structural]
+#[derive(Deserialize, JsonSchema)]
+struct Audit {
+    created_at: u64,
+    created_by: String,
+}
+
+#[structural]
+#[derive(Deserialize, JsonSchema)]
+struct Document<A: audit::Fields = select!(audit: all)> {
+    title: String,
+    #[serde(flatten)]
+    audit: Audit<A>,
+}
+
+/// Written and read under different names.
+#[structural]
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all(serialize = "camelCase"))]
+struct Account {
+    #[serde(rename(serialize = "accountId", deserialize = "id"))]
+    account_id: u32,
+    display_name: String,
+}
+
+#[test]
+fn present_fields_are_required_and_optional_fields_nullable() {
+    let schema = schema_for!(User<select!(user: id, display_name, email?, verified~)>);
+    assert_eq!(
+        schema.as_value(),
+        &json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "User<id, displayName, mail?, verified?>",
+            "description": "A registered user.",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "format": "uint32", "minimum": 0 },
+                "displayName": { "type": "string", "description": "Full name." },
+                "mail": { "type": ["string", "null"] },
+                "verified": { "type": ["boolean", "null"] },
+            },
+            "required": ["id", "displayName"],
+        })
+    );
+}
+
+#[test]
+fn defaulted_present_field_is_not_required() {
+    let schema = schema_for!(User<select!(user: id, verified)>);
+    assert_eq!(schema.get("required"), Some(&json!(["id"])));
+    assert_eq!(
+        schema.get("properties").unwrap()["verified"],
+        json!({ "type": "boolean" })
+    );
+}
+
+#[test]
+fn names_follow_selection() {
+    assert_eq!(
+        <User<select!(user: id, email?)>>::schema_name(),
+        "User<id, mail?>"
+    );
+    assert_ne!(
+        <User<select!(user: id, email?)>>::schema_id(),
+        <User<select!(user: id, email)>>::schema_id()
+    );
+    assert_eq!(
+        <Todo<select!(todo: all), select!(project: id)>>::schema_name(),
+        "Todo<title, project: Project<id>>"
+    );
+    assert_eq!(
+        <Todo<select!(todo: title), select!(project: id)>>::schema_name(),
+        "Todo<title>"
+    );
+}
+
+#[test]
+fn nested_fields_reference_their_selection() {
+    let schema = schema_for!(Todo<select!(todo: all), select!(project: name)>);
+    assert_eq!(
+        schema.get("properties").unwrap()["project"],
+        json!({ "$ref": "#/$defs/Project%3Cname%3E" })
+    );
+    assert_eq!(
+        schema.get("$defs").unwrap()["Project<name>"],
+        json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"],
+        })
+    );
+}
+
+#[test]
+fn flattened_fields_share_the_object() {
+    let schema =
+        schema_for!(Document<select!(document: all), select!(audit: created_at, created_by?)>);
+    assert_eq!(
+        schema.get("properties").unwrap(),
+        &json!({
+            "title": { "type": "string" },
+            "created_at": { "type": "integer", "format": "uint64", "minimum": 0 },
+            "created_by": { "type": ["string", "null"] },
+        })
+    );
+    assert_eq!(
+        schema.get("required"),
+        Some(&json!(["title", "created_at"]))
+    );
+
+    let schema = schema_for!(Document<select!(document: title, audit?)>);
+    assert_eq!(schema.get("required"), Some(&json!(["title"])));
+}
+
+#[test]
+fn split_renames_follow_the_contract() {
+    let input = SchemaSettings::default()
+        .into_generator()
+        .into_root_schema_for::<Account<select!(account: all)>>();
+    assert_eq!(input.get("required"), Some(&json!(["id", "display_name"])));
+
+    let output = SchemaSettings::default()
+        .for_serialize()
+        .into_generator()
+        .into_root_schema_for::<Account<select!(account: all)>>();
+    assert_eq!(
+        output.get("required"),
+        Some(&json!(["accountId", "displayName"]))
+    );
+}
@@ -0,0 +1,156 @@
+use structural_typing::{
+    presence::{Absent, Optional, Patch, Present},
+    select, structural,
+};
+
+#[structural]
+#[derive(Clone, Debug, PartialEq)]
+struct User {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+#[structural]
+#[derive(Clone, Debug, PartialEq)]
+struct Project {
+    id: u32,
+    name: String,
+    description: String,
+}
+
+#[structural]
+#[derive(Clone, Debug, PartialEq)]
+struct Todo<P: project::Fields = select!(project: all-)> {
+    id: u32,
+    title: String,
+    project: Project<P>,
+}
+
+mod models {
+    use structural_typing::{select, structural};
+
+    #[structural]
+    pub struct Tag {
+        pub label: String,
+        pub color: String,
+    }
+
+    #[structural]
+    pub struct Note<T: tag::Fields = select!(tag: all-)> {
+        pub text: String,
+        pub tag: Tag<T>,
+    }
+}
+
+mod accounts {
+    use structural_typing::structural;
+
+    #[structural]
+    pub struct Member {
+        pub id: u32,
+    }
+}
+
+mod billing {
+    use structural_typing::structural;
+
+    #[structural]
+    pub struct Member {
+        pub id: u32,
+    }
+}
+
+macro_rules! define_entry {
+    ($module:ident) => {
+        mod $module {
+            use structural_typing::structural;
+
+            #[structural]
+            pub struct Entry {
+                pub id: u32,
+            }
+        }
+    };
+}
+
+define_entry!(ledger);
+define_entry!(journal);
+
+fn assert_same<A, B>()
+where
+    A: SameAs<B>,
+{
+}
+
+trait SameAs<T> {}
+impl<T> SameAs<T> for T {}
+
+#[test]
+fn explicit_fields_override_all() {
+    assert_same::<select!(user: all, email-), user::FieldSet<Present, Present, Absent>>();
+    assert_same::<select!(user: email-, all), user::FieldSet<Present, Present, Absent>>();
+    assert_same::<select!(user: all?, id), user::FieldSet<Present, Optional, Optional>>();
+
+    let user: User<select!(user: all, email-)> = user::empty().id(1).name("Alice".to_owned());
+    assert_eq!(user.name, "Alice");
+}
+
+#[test]
+fn spreads_merge_in_order() {
+    type Ids = select!(user: id);
+    type Contact = select!(user: name?, email~);
+
+    assert_same::<select!(user: ..Ids, ..Contact), user::Merge<Ids, Contact>>();
+    assert_same::<select!(user: name, ..Ids, ..Contact), user::FieldSet<Present, Present, Patch>>();
+    assert_same::<select!(user: all-, ..Contact), user::FieldSet<Absent, Optional, Patch>>();
+}
+
+#[test]
+fn nested_selection_fills_struct_parameters() {
+    assert_same::<
+        select!(todo: title, project { id, name }),
+        Todo<select!(todo: title, project), select!(project: id, name)>,
+    >();
+    assert_same::<
+        select!(todo: all, project? { all, description- }),
+        Todo<select!(todo: all, project?), select!(project: id, name)>,
+    >();
+
+    let todo: select!(todo: title, project { id }) = todo::empty()
+        .title("Write tests".to_owned())
+        .project(project::empty().id(7));
+    assert_eq!(todo.project.id, 7);
+}
+
+#[test]
+fn nested_selection_through_module_path() {
+    assert_same::<
+        select!(models::note: text, tag { label }),
+        models::Note<select!(models::note: text, tag), select!(models::tag: label)>,
+    >();
+}
+
+#[test]
+fn pick_and_omit_still_come_first() {
+    type Create = select!(user: name, email);
+    assert_same::<select!(user: Pick<Create>, name), select!(user: name)>();
+    assert_same::<select!(user: Omit<Create>, name), select!(user: email)>();
+}
+
+#[test]
+fn same_shaped_schemas_in_sibling_modules() {
+    assert_same::<select!(accounts::member: id), accounts::member::FieldSet<Present>>();
+    assert_same::<select!(billing::member: id), billing::member::FieldSet<Present>>();
+}
+
+#[test]
+fn schemas_generated_by_one_macro_in_two_modules() {
+    assert_same::<select!(ledger::entry: id), ledger::entry::FieldSet<Present>>();
+    assert_same::<select!(journal::entry: id), journal::entry::FieldSet<Present>>();
+
+    let entry: ledger::Entry<select!(ledger::entry: id)> = ledger::entry::empty().id(1);
+    assert_eq!(entry.id, 1);
+    let entry: journal::Entry<select!(journal::entry: id)> = journal::entry::empty().id(2);
+    assert_eq!(entry.id, 2);
+}
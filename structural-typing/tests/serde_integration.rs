@@ -2,6 +2,7 @@
 
 use core::marker::PhantomData;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use structural_typing::{select, structural};
 
 #[structural]
@@ -12,7 +13,7 @@ struct TestUser {
     id: u64,
 }
 
-#[structural]
+#[structural(views)]
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 struct UserWithRename {
     #[serde(rename = "user_name")]
@@ -30,7 +31,7 @@ struct UserWithAlias {
     id: u64,
 }
 
-#[structural]
+#[structural(views)]
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 struct UserWithRenameAll {
@@ -78,6 +79,37 @@ fn deserialize_missing_required_field_error() {
     assert!(err_msg.contains("missing field"));
 }
 
+#[test]
+fn deserialize_reports_every_missing_field() {
+    let result: Result<TestUser<test_user::with::all>, _> =
+        serde_json::from_str(r#"{"email":"a@b.c"}"#);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "missing fields `name`, `id`"
+    );
+}
+
+#[test]
+fn deserialize_missing_fields_use_serialized_names() {
+    let result: Result<UserWithRename<user_with_rename::with::all>, _> =
+        serde_json::from_str(r#"{"id":1}"#);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .starts_with("missing fields `user_name`, `email`")
+    );
+
+    let result: Result<UserWithRenameAll<user_with_rename_all::with::all>, _> =
+        serde_json::from_str(r#"{"emailAddress":"a@b.c"}"#);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .starts_with("missing fields `userName`, `userId`")
+    );
+}
+
 #[test]
 fn deserialize_with_rename_attr() {
     let json = r#"{"user_name":"Alice","email":"alice@test.com","id":123}"#;
@@ -435,3 +467,515 @@ fn serde_with_absent_field() {
     assert_eq!(user.id, 123);
     assert_eq!(user.email, PhantomData);
 }
+
+#[test]
+fn deserialize_patch_distinguishes_missing_and_null() {
+    use structural_typing::presence::PatchValue;
+
+    let json = r#"{"name":"Alice","email":null}"#;
+    let user: TestUser<select!(test_user: all~)> = serde_json::from_str(json).unwrap();
+    assert_eq!(user.name, PatchValue::Value("Alice".to_owned()));
+    assert_eq!(user.email, PatchValue::Null);
+    assert_eq!(user.id, PatchValue::Missing);
+}
+
+#[test]
+fn serialize_patch_omits_missing() {
+    use structural_typing::presence::PatchValue;
+
+    let user = test_user::empty()
+        .name(PatchValue::Value("Bob".to_owned()))
+        .email(PatchValue::<String>::Null)
+        .id(PatchValue::<u64>::Missing);
+    let json = serde_json::to_string(&user).unwrap();
+    assert_eq!(json, r#"{"name":"Bob","email":null}"#);
+}
+
+#[test]
+fn patch_merge_from_json() {
+    let current = test_user::empty()
+        .name("Carol".to_owned())
+        .email(Some("carol@test.com".to_owned()))
+        .id(7);
+    let patch: TestUser<select!(test_user: name~, email~)> =
+        serde_json::from_str(r#"{"email":null}"#).unwrap();
+
+    let (updated, _) = current
+        .merge(patch)
+        .extract::<select!(test_user: name?, email?, id)>();
+    assert_eq!(updated.name, Some("Carol".to_owned()));
+    assert_eq!(updated.email, None);
+    assert_eq!(updated.id, 7);
+}
+
+#[test]
+fn deserialize_null_for_present_field_via_patch_helper_errors() {
+    let json = r#"{"name":"Dave","email":null,"id":1}"#;
+    let result: Result<TestUser<select!(test_user: name, email, id)>, _> =
+        serde_json::from_str(json);
+    assert!(result.unwrap_err().to_string().contains("missing field"));
+}
+
+fn default_port() -> u16 {
+    8080
+}
+
+#[structural]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct ServerConfig {
+    host: String,
+    #[serde(default = "default_port")]
+    port: u16,
+    #[serde(default, rename = "max_conns")]
+    max_connections: u32,
+}
+
+#[structural]
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default)]
+struct Limits {
+    retries: u8,
+    verbose: bool,
+}
+
+/// Not `Default`: only selectable when not Present under the container default.
+#[derive(Deserialize, Debug, PartialEq)]
+struct Backoff(u32);
+
+#[structural]
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default)]
+struct RetryPolicy {
+    retries: u8,
+    backoff: Backoff,
+}
+
+#[test]
+fn field_default_fills_missing_present_field() {
+    let config: ServerConfig<select!(server_config: all)> =
+        serde_json::from_str(r#"{"host":"localhost"}"#).unwrap();
+    assert_eq!(config.host, "localhost");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.max_connections, 0);
+
+    let config: ServerConfig<select!(server_config: all)> =
+        serde_json::from_str(r#"{"host":"localhost","port":1,"max_conns":5}"#).unwrap();
+    assert_eq!(config.port, 1);
+    assert_eq!(config.max_connections, 5);
+}
+
+#[test]
+fn field_default_does_not_apply_to_optional_or_patch() {
+    let config: ServerConfig<select!(server_config: host, port?, max_connections~)> =
+        serde_json::from_str(r#"{"host":"localhost"}"#).unwrap();
+    assert_eq!(config.port, None);
+    assert!(config.max_connections.is_missing());
+}
+
+#[test]
+fn field_default_does_not_cover_null() {
+    let result: Result<ServerConfig<select!(server_config: all)>, _> =
+        serde_json::from_str(r#"{"host":"localhost","port":null}"#);
+    assert!(result.is_err());
+}
+
+#[test]
+fn field_without_default_still_required() {
+    let result: Result<ServerConfig<select!(server_config: all)>, _> =
+        serde_json::from_str(r#"{"port":1}"#);
+    assert!(result.is_err());
+}
+
+#[test]
+fn container_default_applies_to_present_fields() {
+    let limits: Limits<select!(limits: all)> = serde_json::from_str("{}").unwrap();
+    assert_eq!(limits.retries, 0);
+    assert!(!limits.verbose);
+
+    let limits: Limits<select!(limits: retries, verbose?)> =
+        serde_json::from_str(r#"{"retries":3}"#).unwrap();
+    assert_eq!(limits.retries, 3);
+    assert_eq!(limits.verbose, None);
+}
+
+#[test]
+fn container_default_only_requires_default_of_present_fields() {
+    let policy: RetryPolicy<select!(retry_policy: retries, backoff?)> =
+        serde_json::from_str("{}").unwrap();
+    assert_eq!(policy.retries, 0);
+    assert_eq!(policy.backoff, None);
+
+    let policy: RetryPolicy<select!(retry_policy: retries, backoff?)> =
+        serde_json::from_str(r#"{"backoff":5}"#).unwrap();
+    assert_eq!(policy.backoff, Some(Backoff(5)));
+}
+
+#[test]
+fn serialize_borrowed_view() {
+    let user = user_with_rename::empty()
+        .name("Alice".to_owned())
+        .email("alice@example.com".to_owned())
+        .id(1);
+
+    let (public, _) = user
+        .as_ref()
+        .extract::<select!(user_with_rename: name, id)>();
+    let json = serde_json::to_string(&public).unwrap();
+    assert_eq!(json, r#"{"user_name":"Alice","id":1}"#);
+    assert_eq!(user.email, "alice@example.com");
+}
+
+#[test]
+fn serialize_borrowed_view_matches_owned() {
+    let user = user_with_rename_all::empty()
+        .user_name("Bob".to_owned())
+        .user_id(Some(2));
+    assert_eq!(
+        serde_json::to_string(&user.as_ref()).unwrap(),
+        serde_json::to_string(&user).unwrap()
+    );
+}
+
+#[test]
+fn require_reports_serde_names() {
+    let user: UserWithRename<select!(user_with_rename: all?)> =
+        serde_json::from_str(r#"{"id":1}"#).unwrap();
+    let err = user
+        .require::<select!(user_with_rename: all)>()
+        .unwrap_err();
+    assert_eq!(err.missing(), ["user_name", "email"]);
+
+    let user: UserWithRenameAll<select!(user_with_rename_all: all?)> =
+        serde_json::from_str("{}").unwrap();
+    let err = user
+        .require::<select!(user_with_rename_all: all)>()
+        .unwrap_err();
+    assert_eq!(err.missing(), ["userName", "emailAddress", "userId"]);
+}
+
+#[structural(deny_absent_input)]
+#[derive(Deserialize, Debug)]
+struct CreateAccount {
+    #[serde(rename = "accountId", alias = "account_id")]
+    id: u64,
+    name: String,
+    role: String,
+}
+
+#[test]
+fn deny_absent_input_rejects_absent_fields() {
+    let result: Result<CreateAccount<select!(create_account: name)>, _> =
+        serde_json::from_str(r#"{"accountId":1,"name":"Alice","role":"admin"}"#);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .starts_with("unexpected fields `accountId`, `role`")
+    );
+
+    let result: Result<CreateAccount<select!(create_account: name)>, _> =
+        serde_json::from_str(r#"{"account_id":1,"name":"Alice"}"#);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .starts_with("unexpected field `accountId`")
+    );
+
+    let result: Result<CreateAccount<select!(create_account: name)>, _> =
+        serde_json::from_str(r#"{"role":null}"#);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .starts_with("missing field `name`; unexpected field `role`")
+    );
+}
+
+#[test]
+fn deny_absent_input_accepts_selected_fields() {
+    let account: CreateAccount<select!(create_account: name, role?, id~)> =
+        serde_json::from_str(r#"{"accountId":1,"name":"Alice"}"#).unwrap();
+    assert_eq!(account.name, "Alice");
+    assert_eq!(account.role, None);
+}
+
+#[structural]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Audit {
+    #[serde(rename = "createdAt")]
+    created_at: u64,
+    updated_at: u64,
+}
+
+#[structural]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Document<A: audit::Fields = select!(audit: all)> {
+    title: String,
+    #[serde(flatten)]
+    audit: Audit<A>,
+}
+
+#[test]
+fn flatten_roundtrip() {
+    let doc: Document<document::with::all, select!(audit: created_at, updated_at?)> =
+        serde_json::from_str(r#"{"title":"Notes","createdAt":1}"#).unwrap();
+    assert_eq!(doc.title, "Notes");
+    assert_eq!(doc.audit.created_at, 1);
+    assert_eq!(doc.audit.updated_at, None);
+
+    let doc = document::empty()
+        .title("Notes".to_owned())
+        .audit(audit::empty().created_at(1));
+    assert_eq!(
+        serde_json::to_string(&doc).unwrap(),
+        r#"{"title":"Notes","createdAt":1}"#
+    );
+}
+
+#[test]
+fn flatten_reports_nested_missing_fields() {
+    let result: Result<Document<document::with::all, audit::with::all>, _> =
+        serde_json::from_str(r#"{"updated_at":2}"#);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .starts_with("missing fields `title`, `createdAt`")
+    );
+}
+
+#[test]
+fn flatten_optional_nested_struct() {
+    type Doc = Document<select!(document: title, audit?), audit::with::all>;
+
+    let doc: Doc = serde_json::from_str(r#"{"title":"Notes"}"#).unwrap();
+    assert_eq!(doc.audit, None);
+
+    let doc: Doc =
+        serde_json::from_str(r#"{"title":"Notes","createdAt":1,"updated_at":2}"#).unwrap();
+    assert_eq!(doc.audit.unwrap().updated_at, 2);
+
+    let result: Result<Doc, _> = serde_json::from_str(r#"{"title":"Notes","createdAt":1}"#);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .starts_with("missing field `updated_at`")
+    );
+}
+
+#[structural(deny_absent_input)]
+#[derive(Deserialize, Debug)]
+struct CreateDocument<A: audit::Fields = select!(audit: all)> {
+    title: String,
+    #[serde(flatten)]
+    audit: Audit<A>,
+}
+
+#[test]
+fn flatten_deny_absent_input_reports_nested_fields() {
+    let result: Result<CreateDocument<select!(create_document: title), audit::with::all>, _> =
+        serde_json::from_str(r#"{"title":"Notes","createdAt":1}"#);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .starts_with("unexpected field `createdAt`")
+    );
+}
+
+#[derive(Debug, PartialEq)]
+struct Connection;
+
+#[structural]
+#[derive(Deserialize, Debug)]
+struct Session {
+    token: String,
+    connection: Connection,
+}
+
+#[test]
+fn absent_fields_need_not_be_deserialize() {
+    let session: Session<select!(session: token)> =
+        serde_json::from_str(r#"{"token":"abc","connection":{"port":80}}"#).unwrap();
+    assert_eq!(session.token, "abc");
+
+    let session = session.connection(Connection);
+    assert_eq!(session.connection, Connection);
+}
+
+fn number_from_string<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+fn optional_number_from_string<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u32>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+mod uppercase {
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        Ok(String::deserialize(deserializer)?.to_uppercase())
+    }
+}
+
+#[structural]
+#[derive(Deserialize, Debug)]
+struct Product {
+    #[serde(deserialize_with = "number_from_string")]
+    price: u32,
+    #[serde(with = "uppercase")]
+    code: String,
+    #[serde(deserialize_with = "optional_number_from_string")]
+    #[structural(deserialize_option)]
+    stock: u32,
+}
+
+#[test]
+fn deserialize_with_producing_value() {
+    let product: Product<select!(product: price, code, stock?)> =
+        serde_json::from_str(r#"{"price":"42","code":"ab","stock":null}"#).unwrap();
+    assert_eq!(product.price, 42);
+    assert_eq!(product.code, "AB");
+    assert_eq!(product.stock, None);
+
+    let product: Product<select!(product: price?, code~, stock)> =
+        serde_json::from_str(r#"{"stock":"7"}"#).unwrap();
+    assert_eq!(product.price, None);
+    assert!(product.code.is_missing());
+    assert_eq!(product.stock, 7);
+}
+
+#[test]
+fn deserialize_with_reads_null_without_the_function() {
+    let product: Product<select!(product: price?, code~)> =
+        serde_json::from_str(r#"{"price":null,"code":null}"#).unwrap();
+    assert_eq!(product.price, None);
+    assert!(product.code.is_null());
+
+    let result: Result<Product<select!(product: price)>, _> =
+        serde_json::from_str(r#"{"price":null}"#);
+    assert!(result.is_err());
+}
+
+#[test]
+fn deserialize_with_reports_missing_and_skips_absent() {
+    let result: Result<Product<select!(product: price, code)>, _> =
+        serde_json::from_str(r#"{"stock":"7"}"#);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .starts_with("missing fields `price`, `code`")
+    );
+
+    let product: Product<select!(product: code)> =
+        serde_json::from_str(r#"{"price":"not a number","code":"x"}"#).unwrap();
+    assert_eq!(product.code, "X");
+}
+
+#[structural(serialize_none = "omit", views)]
+#[derive(Serialize, Debug)]
+struct Profile {
+    name: String,
+    bio: String,
+    #[structural(serialize_none = "null")]
+    avatar: String,
+}
+
+#[test]
+fn serialize_none_omit_and_null() {
+    let profile = profile::empty()
+        .name("Alice".to_owned())
+        .bio(None)
+        .avatar(None);
+    let json = serde_json::to_string(&profile).unwrap();
+    assert_eq!(json, r#"{"name":"Alice","avatar":null}"#);
+    assert_eq!(serde_json::to_string(&profile.as_ref()).unwrap(), json);
+
+    let profile = profile::empty()
+        .name("Alice".to_owned())
+        .bio(Some("Hi".to_owned()));
+    assert_eq!(
+        serde_json::to_string(&profile).unwrap(),
+        r#"{"name":"Alice","bio":"Hi"}"#
+    );
+}
+
+#[test]
+fn serialize_none_omit_keeps_patch_null() {
+    use structural_typing::presence::PatchValue;
+
+    let profile = profile::empty()
+        .name("Alice".to_owned())
+        .bio(PatchValue::Null)
+        .avatar(PatchValue::<String>::Missing);
+    assert_eq!(
+        serde_json::to_string(&profile).unwrap(),
+        r#"{"name":"Alice","bio":null}"#
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Source<'a> {
+    host: &'a str,
+}
+
+fn trimmed<'de: 'a, 'a, D: serde::Deserializer<'de>>(deserializer: D) -> Result<&'a str, D::Error> {
+    <&str>::deserialize(deserializer).map(str::trim)
+}
+
+#[structural]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Event<'a> {
+    name: &'a str,
+    payload: &'a [u8],
+    #[serde(borrow)]
+    note: Cow<'a, str>,
+    #[serde(borrow)]
+    raw: Cow<'a, [u8]>,
+    #[serde(borrow = "'a")]
+    source: Source<'a>,
+    #[serde(deserialize_with = "trimmed")]
+    tag: &'a str,
+}
+
+#[test]
+fn deserialize_borrows_from_input() {
+    let json = String::from(
+        r#"{"name":"click","payload":"abc","note":"hi","raw":"xyz","source":{"host":"h"},"tag":" t "}"#,
+    );
+    let event: Event<select!(event: all)> = serde_json::from_str(&json).unwrap();
+    assert_eq!(event.name, "click");
+    assert_eq!(event.payload, b"abc");
+    assert!(matches!(event.note, Cow::Borrowed("hi")));
+    assert!(matches!(event.raw, Cow::Borrowed(b"xyz")));
+    assert_eq!(event.source, Source { host: "h" });
+    assert_eq!(event.tag, "t");
+    assert!(
+        json.as_bytes()
+            .as_ptr_range()
+            .contains(&event.name.as_ptr())
+    );
+}
+
+#[test]
+fn deserialize_borrowed_partial_record() {
+    let json = r#"{"name":"click","payload":"abc","note":"a\nb","tag":"t"}"#;
+    let event: Event<select!(event: name, note, raw?, tag~)> = serde_json::from_str(json).unwrap();
+    assert_eq!(event.name, "click");
+    assert!(matches!(event.note, Cow::Owned(ref note) if note == "a\nb"));
+    assert_eq!(event.raw, None);
+    assert_eq!(
+        event.tag,
+        structural_typing::presence::PatchValue::Value("t")
+    );
+}
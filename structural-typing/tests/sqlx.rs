@@ -0,0 +1,314 @@
+#![cfg(feature = "sqlx")]
+
+use sqlx::{FromRow, SqlitePool};
+use structural_typing::presence::PatchValue;
+use structural_typing::{select, structural};
+
+/// Not `Decode`: only readable when Absent.
+#[derive(Debug, PartialEq)]
+struct Settings;
+
+#[structural]
+#[derive(FromRow, Debug, PartialEq)]
+struct User {
+    id: i64,
+    name: String,
+    #[sqlx(rename = "email_address")]
+    email: String,
+    settings: Settings,
+}
+
+#[structural]
+#[derive(FromRow, Debug, PartialEq)]
+struct Team {
+    id: i64,
+    name: String,
+}
+
+#[structural]
+#[derive(FromRow, Debug, PartialEq)]
+struct Member<T: team::Fields = select!(team: all)> {
+    id: i64,
+    #[sqlx(prefix = "team_")]
+    team: Team<T>,
+}
+
+#[structural]
+#[derive(FromRow, Debug, PartialEq)]
+struct Profile {
+    id: i64,
+    bio: Option<String>,
+}
+
+async fn pool() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    sqlx::query(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, email_address TEXT);
+         INSERT INTO users (id, name, email_address) VALUES (1, 'Alice', 'alice@example.com'), (2, 'Bob', NULL);",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn query_as_reads_selected_columns() {
+    let pool = pool().await;
+    let user: User<select!(user: id, name)> =
+        sqlx::query_as("SELECT id, name FROM users WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(user, user::empty().id(1).name("Alice".to_owned()));
+}
+
+#[tokio::test]
+async fn optional_reads_nullable_and_missing_columns() {
+    let pool = pool().await;
+    let users: Vec<User<select!(user: id, email?)>> =
+        sqlx::query_as("SELECT id, email_address FROM users ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(users[0].email.as_deref(), Some("alice@example.com"));
+    assert_eq!(users[1].email, None);
+
+    let user: User<select!(user: id, email?)> = sqlx::query_as("SELECT id FROM users WHERE id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(user.email, None);
+}
+
+#[tokio::test]
+async fn patch_keeps_null_apart_from_missing_column() {
+    let pool = pool().await;
+    let user: User<select!(user: id, email~)> =
+        sqlx::query_as("SELECT id, email_address FROM users WHERE id = 2")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(user.email, PatchValue::Null);
+
+    let user: User<select!(user: id, email~)> = sqlx::query_as("SELECT id FROM users WHERE id = 2")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(user.email, PatchValue::Missing);
+}
+
+#[tokio::test]
+async fn present_fails_on_missing_or_null_column() {
+    let pool = pool().await;
+    let err = sqlx::query_as::<_, User<select!(user: id, name)>>("SELECT id FROM users")
+        .fetch_one(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, sqlx::Error::ColumnNotFound(ref column) if column == "name"));
+
+    let err = sqlx::query_as::<_, User<select!(user: id, email)>>(
+        "SELECT id, email_address FROM users WHERE id = 2",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap_err();
+    assert!(matches!(err, sqlx::Error::ColumnDecode { .. }));
+}
+
+#[tokio::test]
+async fn present_option_reads_null_column() {
+    let pool = pool().await;
+    let profiles: Vec<Profile<select!(profile: all)>> =
+        sqlx::query_as("SELECT id, email_address AS bio FROM users ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(profiles[0].bio.as_deref(), Some("alice@example.com"));
+    assert_eq!(profiles[1].bio, None);
+
+    let err = sqlx::query_as::<_, Profile<select!(profile: all)>>("SELECT id FROM users")
+        .fetch_one(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, sqlx::Error::ColumnNotFound(ref column) if column == "bio"));
+}
+
+#[tokio::test]
+async fn nested_reads_prefixed_columns() {
+    let pool = pool().await;
+    let member: Member<select!(member: all)> =
+        sqlx::query_as("SELECT 3 AS id, 7 AS team_id, 'Core' AS team_name")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(member.team, team::empty().id(7).name("Core".to_owned()));
+
+    let member: Member<select!(member: all), select!(team: name)> =
+        sqlx::query_as("SELECT 3 AS id, 'Core' AS team_name")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(member.team.name, "Core");
+}
+
+#[tokio::test]
+async fn optional_nested_is_none_without_its_columns() {
+    let pool = pool().await;
+    let member: Member<select!(member: id, team?)> = sqlx::query_as("SELECT 3 AS id")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(member.team, None);
+
+    let member: Member<select!(member: id, team?)> =
+        sqlx::query_as("SELECT 3 AS id, NULL AS team_id, NULL AS team_name")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(member.team, None);
+}
+
+#[test]
+fn from_row_is_implemented_for_every_selection() {
+    fn assert_from_row<T: for<'r> FromRow<'r, sqlx::sqlite::SqliteRow>>() {}
+    assert_from_row::<User<select!(user: id)>>();
+    assert_from_row::<User<select!(user: id, name?, email~)>>();
+    assert_from_row::<Member<select!(member: all), select!(team: id)>>();
+}
+
+fn names(columns: &[structural_typing::sqlx::Column]) -> Vec<String> {
+    columns.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn columns_follow_selection() {
+    assert_eq!(
+        names(&user::columns::<select!(user: id, name)>()),
+        ["id", "name"]
+    );
+    assert_eq!(
+        names(&user::columns::<select!(user: id, email?)>()),
+        ["id", "email_address"]
+    );
+    assert_eq!(
+        names(&member::columns::<select!(member: all), select!(team: name)>()),
+        ["id", "team_name"]
+    );
+    assert_eq!(
+        names(&member::columns::<select!(member: id), select!(team: all)>()),
+        ["id"]
+    );
+}
+
+#[test]
+fn select_list_renders_table_and_nested_aliases() {
+    type Fields = select!(member: id, team?);
+    assert_eq!(
+        member::select_list::<Fields, select!(team: all)>().to_string(),
+        "id, team_id, team_name"
+    );
+    assert_eq!(
+        member::select_list::<Fields, select!(team: all)>()
+            .table("m")
+            .to_string(),
+        "m.id, m.team_id, m.team_name"
+    );
+    assert_eq!(
+        member::select_list::<Fields, select!(team: all)>()
+            .table("m")
+            .nested("team_", "t")
+            .to_string(),
+        "m.id, t.id AS team_id, t.name AS team_name"
+    );
+}
+
+#[tokio::test]
+async fn select_list_matches_from_row() {
+    let pool = pool().await;
+    type Contact = User<select!(user: id, email?)>;
+    let sql = format!(
+        "SELECT {} FROM users u ORDER BY u.id",
+        user::select_list::<select!(user: id, email?)>().table("u")
+    );
+    let users: Vec<Contact> = sqlx::query_as(&sql).fetch_all(&pool).await.unwrap();
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[1].email, None);
+}
+
+#[tokio::test]
+async fn insert_query_writes_present_and_some_optional_fields() {
+    let pool = pool().await;
+    let user: User<select!(user: id, name, email?)> =
+        user::empty().id(3).name("Carol".to_owned()).email(None);
+    let mut query = user.insert_query::<sqlx::Sqlite>("users");
+    assert_eq!(query.sql(), "INSERT INTO users (id, name) VALUES (?, ?)");
+    query.build().execute(&pool).await.unwrap();
+
+    let user = user::empty()
+        .id(4)
+        .name("Dan".to_owned())
+        .email(Some("dan@example.com".to_owned()));
+    let mut query = user.insert_query::<sqlx::Sqlite>("users");
+    assert_eq!(
+        query.sql(),
+        "INSERT INTO users (id, name, email_address) VALUES (?, ?, ?)"
+    );
+    query.build().execute(&pool).await.unwrap();
+
+    let users: Vec<User<select!(user: id, name, email?)>> =
+        sqlx::query_as("SELECT id, name, email_address FROM users WHERE id > 2 ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(users[0].email, None);
+    assert_eq!(users[1].email.as_deref(), Some("dan@example.com"));
+}
+
+#[tokio::test]
+async fn update_query_sets_only_written_fields() {
+    let pool = pool().await;
+    let patch: User<select!(user: name~, email~)> = user::empty()
+        .name(PatchValue::Value("Alicia".to_owned()))
+        .email(PatchValue::Null);
+    let mut query = patch.update_query::<sqlx::Sqlite>("users").unwrap();
+    query.push(" WHERE id = ").push_bind(1);
+    assert_eq!(
+        query.sql(),
+        "UPDATE users SET name = ?, email_address = ? WHERE id = ?"
+    );
+    query.build().execute(&pool).await.unwrap();
+
+    let user: User<select!(user: name, email?)> =
+        sqlx::query_as("SELECT name, email_address FROM users WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(user, user::empty().name("Alicia".to_owned()).email(None));
+
+    let patch: User<select!(user: name~, email~)> = user::empty()
+        .name(PatchValue::Missing)
+        .email(PatchValue::Missing);
+    assert!(patch.update_query::<sqlx::Sqlite>("users").is_none());
+}
+
+#[test]
+fn queries_use_postgres_placeholders() {
+    let member: Member<select!(member: all), select!(team: name)> = member::empty()
+        .id(3)
+        .team(team::empty().name("Core".to_owned()));
+    assert_eq!(
+        member.insert_query::<sqlx::Postgres>("members").sql(),
+        "INSERT INTO members (id, team_name) VALUES ($1, $2)"
+    );
+
+    let user: User<select!(user: name, email?)> = user::empty()
+        .name("Alice".to_owned())
+        .email(Some("alice@example.com".to_owned()));
+    let mut query = user.update_query::<sqlx::Postgres>("users").unwrap();
+    query.push(" WHERE id = ").push_bind(1_i64);
+    assert_eq!(
+        query.sql(),
+        "UPDATE users SET name = $1, email_address = $2 WHERE id = $3"
+    );
+}
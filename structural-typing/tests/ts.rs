@@ -0,0 +1,164 @@
+#![cfg(all(feature = "ts", feature = "serde"))]
+
+use serde::Deserialize;
+use structural_typing::ts::{TsType, alias};
+use structural_typing::{select, structural};
+
+/// A registered user.
+#[structural(ts)]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct User {
+    id: u32,
+    /// Full name.
+    display_name: String,
+    #[serde(rename = "mail")]
+    email: String,
+    tags: Vec<String>,
+    bio: Option<String>,
+}
+
+#[structural(ts)]
+#[derive(Deserialize)]
+struct Project {
+    id: u32,
+    name: String,
+    description: String,
+}
+
+#[structural(ts)]
+#[derive(Deserialize)]
+struct Todo<P: project::Fields = select!(project: all)> {
+    id: u32,
+    title: String,
+    completed: bool,
+    project: Project<P>,
+}
+
+#[structural(ts)]
+#[derive(Deserialize)]
+struct Audit {
+    created_at: u64,
+    created_by: String,
+}
+
+#[structural(ts)]
+#[derive(Deserialize)]
+struct Report<A: audit::Fields = select!(audit: all)> {
+    title: String,
+    #[serde(flatten)]
+    audit: Audit<A>,
+}
+
+/// Written and read under different names.
+#[structural(ts)]
+#[derive(Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+struct Account {
+    #[serde(rename(serialize = "accountId", deserialize = "id"))]
+    account_id: u32,
+    display_name: String,
+}
+
+#[test]
+fn interface_lists_every_field_by_serialized_name() {
+    assert_eq!(
+        user::ts_interface(),
+        "/** A registered user. */
+export interface User {
+  id: number;
+  /** Full name. */
+  displayName: string;
+  mail: string;
+  tags: string[];
+  bio: string | null;
+}
+"
+    );
+}
+
+#[test]
+fn selections_pick_or_omit_fields() {
+    assert_eq!(<User<select!(user: all)>>::ts_type(), "User");
+    assert_eq!(
+        alias::<User<select!(user: display_name, email?)>>("CreateUser"),
+        "export type CreateUser = Pick<User, \"displayName\"> & { mail?: User[\"mail\"] | null };\n"
+    );
+    assert_eq!(
+        <User<select!(user: id, display_name, email, tags)>>::ts_type(),
+        "Omit<User, \"bio\">"
+    );
+    assert_eq!(
+        <User<select!(user: id, display_name~, email, tags)>>::ts_type(),
+        "Omit<User, \"displayName\" | \"bio\"> & { displayName?: User[\"displayName\"] | null }"
+    );
+    assert_eq!(
+        <User<select!(user: email?)>>::ts_type(),
+        "{ mail?: User[\"mail\"] | null }"
+    );
+}
+
+#[test]
+fn patch_fields_may_be_missing_or_null() {
+    assert_eq!(
+        <User<select!(user: id, display_name~, bio~)>>::ts_type(),
+        "Pick<User, \"id\"> & { displayName?: User[\"displayName\"] | null; bio?: User[\"bio\"] | null }"
+    );
+}
+
+#[test]
+fn nested_fields_spell_out_their_selection() {
+    assert_eq!(
+        todo::ts_interface(),
+        "export interface Todo {
+  id: number;
+  title: string;
+  completed: boolean;
+  project: Project;
+}
+"
+    );
+    assert_eq!(<Todo<select!(todo: all)>>::ts_type(), "Todo");
+    assert_eq!(
+        <Todo<select!(todo: id, title, project), select!(project: id, name)>>::ts_type(),
+        "Pick<Todo, \"id\" | \"title\"> & { project: Omit<Project, \"description\"> }"
+    );
+    assert_eq!(
+        <Todo<select!(todo: title, project?)>>::ts_type(),
+        "Pick<Todo, \"title\"> & { project?: Todo[\"project\"] | null }"
+    );
+}
+
+#[test]
+fn flattened_fields_extend_the_interface() {
+    assert_eq!(
+        report::ts_interface(),
+        "export interface Report extends Audit {\n  title: string;\n}\n"
+    );
+    assert_eq!(<Report<select!(report: all)>>::ts_type(), "Report");
+    assert_eq!(
+        <Report<select!(report: all), select!(audit: created_at, created_by?)>>::ts_type(),
+        "Pick<Report, \"title\"> & Pick<Audit, \"created_at\"> & { created_by?: Audit[\"created_by\"] | null }"
+    );
+    assert_eq!(
+        <Report<select!(report: title)>>::ts_type(),
+        "Pick<Report, \"title\">"
+    );
+}
+
+#[test]
+fn split_renames_use_the_serialized_name() {
+    assert_eq!(
+        account::ts_interface(),
+        "/** Written and read under different names. */
+export interface Account {
+  accountId: number;
+  displayName: string;
+}
+"
+    );
+    assert_eq!(
+        <Account<select!(account: account_id)>>::ts_type(),
+        "Pick<Account, \"accountId\">"
+    );
+}
@@ -0,0 +1,16 @@
+use serde::Serialize;
+use structural_typing::structural;
+
+#[structural(compact)]
+#[derive(Serialize)]
+struct Event {
+    name: String,
+    #[serde(serialize_with = "serialize_stamp")]
+    stamp: u64,
+}
+
+fn serialize_stamp<S: serde::Serializer>(stamp: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&stamp.to_string())
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use structural_typing::structural;
+
+#[structural(compact)]
+#[derive(Debug)]
+struct Profile {
+    name: String,
+    bio: String,
+}
+
+fn main() {}
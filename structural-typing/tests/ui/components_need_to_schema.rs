@@ -0,0 +1,8 @@
+use structural_typing::structural;
+
+#[structural(components(UserBasic))]
+struct User {
+    id: u32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use structural_typing::{select, structural};
+
+#[structural]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn main() {
+    let _: User<select!(user: id, name<Option<String>>)> = user::empty().id(1);
+}
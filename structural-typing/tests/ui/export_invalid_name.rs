@@ -0,0 +1,8 @@
+use structural_typing::structural;
+
+#[structural(export = "select-user")]
+struct User {
+    id: u32,
+}
+
+fn main() {}
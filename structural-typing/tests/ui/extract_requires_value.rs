@@ -0,0 +1,12 @@
+use structural_typing::{select, structural};
+
+#[structural]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn main() {
+    let user = user::empty().id(1).name(Some("Alice".to_owned()));
+    let _ = user.extract::<select!(user: name)>();
+}
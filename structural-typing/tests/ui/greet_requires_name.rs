@@ -5,8 +5,11 @@ struct User {
     name: String,
 }
 
-impl<F: user::Fields<name = Present>> User<F> {
-    pub fn greet(&self) -> String {
+impl<F: user::Fields> User<F> {
+    pub fn greet(&self) -> String
+    where
+        F: user::field::name<Present>,
+    {
         format!("Hello, {}!", self.name)
     }
 }
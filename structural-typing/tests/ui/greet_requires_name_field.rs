@@ -0,0 +1,15 @@
+use structural_typing::{presence::Present, structural};
+
+#[structural]
+struct User {
+    name: String,
+}
+
+fn greet<F: user::field::name<Present>>(user: &User<F>) -> String {
+    format!("Hello, {}!", user.name)
+}
+
+fn main() {
+    let user = user::empty();
+    greet(&user);
+}
@@ -0,0 +1,18 @@
+use structural_typing::{presence::Present, structural};
+
+#[structural]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn greet<F: user::field::name<Present>>(user: &User<F>) -> String {
+    format!("Hello, {}!", user.name)
+}
+
+fn main() {
+    let merged = user::empty()
+        .id(1)
+        .merge(user::empty().name(None::<String>));
+    greet(&merged);
+}
@@ -0,0 +1,18 @@
+use structural_typing::{select, structural};
+
+#[structural]
+struct Address {
+    city: String,
+    zip: String,
+}
+
+#[structural]
+struct Company<A: address::Fields = select!(address: all-)> {
+    name: String,
+    #[nested(address: city, zip)]
+    address: Address<A>,
+}
+
+fn main() {
+    let _ = company::empty().address_city("Paris".to_owned());
+}
@@ -0,0 +1,19 @@
+use structural_typing::{select, structural};
+
+#[structural]
+struct Address {
+    city: String,
+    zip: String,
+}
+
+#[structural]
+struct Company<A: address::Fields = select!(address: all-)> {
+    name: String,
+    #[nested(address: city, zip)]
+    address: Address<A>,
+}
+
+fn main() {
+    let company = company::empty().address(address::empty());
+    let _ = company.address_city(42);
+}
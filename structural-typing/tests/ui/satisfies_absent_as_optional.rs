@@ -0,0 +1,11 @@
+use structural_typing::{assert_satisfies, select, structural};
+
+#[structural]
+struct User {
+    id: u32,
+    name: String,
+}
+
+assert_satisfies!(user: select!(user: id), select!(user: id, name?));
+
+fn main() {}
@@ -0,0 +1,11 @@
+use structural_typing::structural;
+
+#[structural]
+#[derive(JsonSchema)]
+struct User {
+    id: u32,
+    #[schemars(length(min = 1))]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use structural_typing::{select, structural};
+
+#[structural]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn main() {
+    type Invalid = select!(user: id, nmae, emial?);
+}
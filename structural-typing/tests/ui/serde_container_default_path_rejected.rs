@@ -3,7 +3,7 @@ use structural_typing::structural;
 
 #[structural]
 #[derive(Deserialize)]
-#[serde(default)]
+#[serde(default = "User::defaults")]
 struct User {
     name: String,
     email: String,
@@ -3,10 +3,10 @@ use structural_typing::structural;
 
 #[structural]
 #[derive(Deserialize)]
-struct User {
-    name: String,
-    #[serde(default)]
-    email: String,
+struct Product {
+    #[structural(deserialize_option)]
+    price: u32,
+    code: String,
 }
 
 fn main() {}
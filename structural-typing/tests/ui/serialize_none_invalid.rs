@@ -0,0 +1,9 @@
+use structural_typing::structural;
+
+#[structural(serialize_none = "skip")]
+struct Profile {
+    name: String,
+    bio: String,
+}
+
+fn main() {}
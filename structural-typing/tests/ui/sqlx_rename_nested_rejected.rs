@@ -0,0 +1,18 @@
+use structural_typing::structural;
+
+#[structural]
+#[derive(FromRow)]
+struct Project {
+    id: i64,
+    name: String,
+}
+
+#[structural]
+#[derive(FromRow)]
+struct Todo {
+    id: i64,
+    #[sqlx(rename = "proj", flatten)]
+    project: Project,
+}
+
+fn main() {}
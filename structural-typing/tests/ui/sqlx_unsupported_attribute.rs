@@ -0,0 +1,11 @@
+use structural_typing::structural;
+
+#[structural]
+#[derive(FromRow)]
+struct User {
+    id: i64,
+    #[sqlx(default)]
+    name: String,
+}
+
+fn main() {}
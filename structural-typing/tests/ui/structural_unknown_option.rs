@@ -0,0 +1,9 @@
+use structural_typing::structural;
+
+#[structural(deny_absent_inputs)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+fn main() {}
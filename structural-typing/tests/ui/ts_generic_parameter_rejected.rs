@@ -0,0 +1,8 @@
+use structural_typing::structural;
+
+#[structural(ts)]
+struct Wrapper<T> {
+    value: T,
+}
+
+fn main() {}
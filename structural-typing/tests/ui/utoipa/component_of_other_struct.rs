@@ -0,0 +1,17 @@
+use structural_typing::{select, structural};
+use utoipa::ToSchema;
+
+#[structural]
+struct Project {
+    id: u32,
+}
+
+#[structural(components(ProjectBasic))]
+#[derive(ToSchema)]
+struct User {
+    id: u32,
+}
+
+type ProjectBasic = Project<select!(project: id)>;
+
+fn main() {}
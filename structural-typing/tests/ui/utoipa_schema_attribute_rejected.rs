@@ -0,0 +1,11 @@
+use structural_typing::structural;
+
+#[structural]
+#[derive(ToSchema)]
+struct User {
+    id: u32,
+    #[schema(example = "Alice")]
+    name: String,
+}
+
+fn main() {}
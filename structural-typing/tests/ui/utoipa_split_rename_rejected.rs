@@ -0,0 +1,11 @@
+use structural_typing::structural;
+
+#[structural]
+#[derive(ToSchema)]
+struct User {
+    id: u32,
+    #[serde(rename(serialize = "fullName", deserialize = "name"))]
+    name: String,
+}
+
+fn main() {}
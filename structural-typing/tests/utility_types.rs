@@ -0,0 +1,95 @@
+use core::marker::PhantomData;
+
+use structural_typing::{
+    presence::{Absent, PatchValue, Present},
+    select, structural,
+};
+
+#[structural]
+#[derive(Clone, Debug, PartialEq)]
+struct User {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+type Create = select!(user: name, email);
+
+fn assert_same<A, B>()
+where
+    A: SameAs<B>,
+{
+}
+
+trait SameAs<T> {}
+impl<T> SameAs<T> for T {}
+
+#[test]
+fn partial_makes_present_optional() {
+    assert_same::<user::ops::Partial<Create>, select!(user: name?, email?)>();
+    assert_same::<user::ops::Partial<select!(user: id~, name?)>, select!(user: id~, name?)>();
+
+    let edit: User<user::ops::Partial<Create>> =
+        user::empty().name(Some("Alice".to_owned())).email(None);
+    assert_eq!(edit.name.as_deref(), Some("Alice"));
+}
+
+#[test]
+fn required_makes_optional_and_patch_present() {
+    assert_same::<user::ops::Required<select!(user: id?, name~)>, select!(user: id, name)>();
+    assert_same::<user::ops::Required<user::ops::Partial<Create>>, Create>();
+}
+
+#[test]
+fn intersect_keeps_shared_fields() {
+    type Left = select!(user: id, name?);
+    type Right = select!(user: name, email);
+    assert_same::<user::ops::Intersect<Left, Right>, select!(user: name?)>();
+}
+
+#[test]
+fn difference_removes_fields() {
+    assert_same::<user::ops::Difference<select!(user: all), Create>, select!(user: id)>();
+}
+
+#[test]
+fn pick_and_omit_in_select() {
+    type Full = select!(user: id, name?, email~);
+    assert_same::<select!(user: Pick<Full>, id, name), select!(user: id, name?)>();
+    assert_same::<select!(user: Omit<Full>, email,), select!(user: id, name?)>();
+
+    let picked: User<select!(user: Pick<Full>, email)> =
+        user::empty().email(PatchValue::<String>::Null);
+    let _: PhantomData<u32> = picked.id;
+}
+
+#[test]
+fn utilities_in_generic_context() {
+    fn loosen<F: user::Fields<id = Present, name = Present, email = Absent>>(
+        user: User<F>,
+    ) -> User<user::ops::Partial<F>> {
+        user.extract::<user::ops::Partial<F>>().0
+    }
+
+    let user = loosen(user::empty().id(1).name("Bob".to_owned()));
+    assert_eq!(user.id, Some(1));
+    assert_eq!(user.name, Some("Bob".to_owned()));
+    let _: PhantomData<String> = user.email;
+}
+
+/// Named like a field-set utility, which must not shadow it inside the schema module.
+#[derive(Debug, PartialEq)]
+struct Required;
+
+#[structural]
+struct Rule {
+    name: String,
+    rule: Required,
+}
+
+#[test]
+fn utilities_do_not_shadow_user_types() {
+    let rule = rule::empty().name("strict".to_owned()).rule(Required);
+    assert_eq!(rule.rule, Required);
+    assert_same::<rule::ops::Required<select!(rule: name?)>, select!(rule: name)>();
+}
@@ -0,0 +1,208 @@
+#![cfg(all(feature = "utoipa", feature = "serde"))]
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+use structural_typing::{select, structural};
+use utoipa::{OpenApi, PartialSchema, ToSchema};
+
+/// Not `ToSchema`: only describable when Absent.
+#[derive(Debug)]
+struct Connection;
+
+/// A registered user.
+#[structural]
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct User {
+    id: u32,
+    /// Full name.
+    display_name: String,
+    #[serde(rename = "mail")]
+    email: String,
+    #[serde(default)]
+    verified: bool,
+    connection: Connection,
+}
+
+#[structural(components(CreateProject, ProjectBasic))]
+#[derive(Deserialize, ToSchema)]
+struct Project {
+    id: u32,
+    name: String,
+    description: String,
+}
+
+#[structural]
+#[derive(Deserialize, ToSchema)]
+struct Todo<P: project::Fields = select!(project: all)> {
+    title: String,
+    project: Project<P>,
+}
+
+#[structural]
+#[derive(Deserialize, ToSchema)]
+struct Audit {
+    created_at: u64,
+    created_by: String,
+}
+
+#[structural]
+#[derive(Deserialize, ToSchema)]
+struct Report<A: audit::Fields = select!(audit: all)> {
+    title: String,
+    #[serde(flatten)]
+    audit: Audit<A>,
+}
+
+type CreateProject = Project<select!(project: name, description?)>;
+type ProjectBasic = Project<select!(project: id, name)>;
+type TodoWithProject = Todo<select!(todo: all), select!(project: id, name)>;
+
+fn schema<T: PartialSchema>() -> Value {
+    serde_json::to_value(T::schema()).unwrap()
+}
+
+#[test]
+fn present_fields_are_required_and_optional_fields_nullable() {
+    assert_eq!(
+        schema::<User<select!(user: id, display_name, email?, verified~)>>(),
+        json!({
+            "type": "object",
+            "description": "A registered user.",
+            "properties": {
+                "id": { "type": "integer", "format": "int32", "minimum": 0 },
+                "displayName": { "type": "string", "description": "Full name." },
+                "mail": { "type": ["string", "null"] },
+                "verified": { "type": ["boolean", "null"] },
+            },
+            "required": ["id", "displayName"],
+        })
+    );
+}
+
+#[test]
+fn defaulted_present_field_is_not_required() {
+    let schema = schema::<User<select!(user: id, verified)>>();
+    assert_eq!(schema["required"], json!(["id"]));
+    assert_eq!(
+        schema["properties"]["verified"],
+        json!({ "type": "boolean" })
+    );
+}
+
+#[test]
+fn names_follow_selection() {
+    assert_eq!(
+        <User<select!(user: id, email?)>>::name(),
+        "User-id.email-opt"
+    );
+    assert_eq!(
+        <Todo<select!(todo: all), select!(project: id, name)>>::name(),
+        "Todo-title.project-id.name"
+    );
+    assert_eq!(
+        <Todo<select!(todo: title), select!(project: id)>>::name(),
+        "Todo-title"
+    );
+}
+
+#[test]
+fn nested_fields_reference_their_selection() {
+    assert_eq!(
+        schema::<TodoWithProject>()["properties"]["project"],
+        json!({ "$ref": "#/components/schemas/ProjectBasic" })
+    );
+
+    let mut schemas = Vec::new();
+    TodoWithProject::schemas(&mut schemas);
+    let names: Vec<_> = schemas.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["ProjectBasic"]);
+    assert_eq!(
+        serde_json::to_value(&schemas[0].1).unwrap(),
+        json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "format": "int32", "minimum": 0 },
+                "name": { "type": "string" },
+            },
+            "required": ["id", "name"],
+        })
+    );
+}
+
+#[test]
+fn flattened_fields_share_the_object() {
+    let schema = schema::<Report<select!(report: all), select!(audit: created_at, created_by?)>>();
+    assert_eq!(
+        schema["properties"],
+        json!({
+            "title": { "type": "string" },
+            "created_at": { "type": "integer", "format": "int64", "minimum": 0 },
+            "created_by": { "type": ["string", "null"] },
+        })
+    );
+    assert_eq!(schema["required"], json!(["title", "created_at"]));
+}
+
+#[utoipa::path(post, path = "/projects", request_body = CreateProject, responses((status = 201, body = ProjectBasic)))]
+#[allow(dead_code)]
+fn create_project() {}
+
+#[utoipa::path(get, path = "/projects", responses((status = 200, body = [ProjectBasic])))]
+#[allow(dead_code)]
+fn list_projects() {}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(create_project, list_projects),
+    components(schemas(CreateProject, TodoWithProject))
+)]
+struct ApiDoc;
+
+#[test]
+fn aliases_name_their_components() {
+    assert_eq!(<CreateProject as ToSchema>::name(), "CreateProject");
+    assert_eq!(<ProjectBasic as ToSchema>::name(), "ProjectBasic");
+
+    assert_eq!(
+        <Project<select!(project: id)> as ToSchema>::name(),
+        "Project-id"
+    );
+
+    let basic: ProjectBasic = project::empty().id(2).name("Work".to_owned());
+    assert_eq!(basic.id, 2);
+}
+
+#[test]
+fn documents_use_alias_names() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let schemas = doc["components"]["schemas"].as_object().unwrap();
+    let names: Vec<_> = schemas.keys().map(String::as_str).collect();
+    assert_eq!(
+        names,
+        [
+            "CreateProject",
+            "ProjectBasic",
+            "Todo-title.project-id.name"
+        ]
+    );
+    assert_eq!(schemas["CreateProject"]["required"], json!(["name"]));
+    assert_eq!(
+        schemas["Todo-title.project-id.name"]["properties"]["project"],
+        json!({ "$ref": "#/components/schemas/ProjectBasic" })
+    );
+
+    let operation = &doc["paths"]["/projects"]["post"];
+    assert_eq!(
+        operation["requestBody"]["content"]["application/json"]["schema"],
+        json!({ "$ref": "#/components/schemas/CreateProject" })
+    );
+    assert_eq!(
+        operation["responses"]["201"]["content"]["application/json"]["schema"],
+        json!({ "$ref": "#/components/schemas/ProjectBasic" })
+    );
+    assert_eq!(
+        doc["paths"]["/projects"]["get"]["responses"]["200"]["content"]["application/json"]["schema"],
+        json!({ "type": "array", "items": { "$ref": "#/components/schemas/ProjectBasic" } })
+    );
+}
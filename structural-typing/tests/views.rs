@@ -0,0 +1,98 @@
+use core::marker::PhantomData;
+
+use structural_typing::{presence::PatchValue, select, structural};
+
+#[structural(views)]
+#[derive(Clone, Debug, PartialEq)]
+struct User {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+#[structural(views)]
+struct Borrowing<'a> {
+    label: &'a str,
+    count: usize,
+}
+
+#[test]
+fn as_ref_keeps_presence() {
+    let user = user::empty()
+        .id(1)
+        .name(Some("Alice".to_owned()))
+        .email(PatchValue::<String>::Missing);
+
+    let view = user.as_ref();
+    let id: &u32 = view.id;
+    let name: Option<&String> = view.name;
+    assert_eq!(*id, 1);
+    assert_eq!(name.map(String::as_str), Some("Alice"));
+    assert!(view.email.is_missing());
+
+    let partial = user::empty().id(2);
+    let _: PhantomData<&String> = partial.as_ref().name;
+}
+
+#[test]
+fn extract_from_view_without_consuming() {
+    let user = user::empty()
+        .id(7)
+        .name("Bob".to_owned())
+        .email("bob@example.com".to_owned());
+
+    let (selected, rest) = user.as_ref().extract::<select!(user: id, name)>();
+    assert_eq!(*selected.id, 7);
+    assert_eq!(selected.name, "Bob");
+    let _: PhantomData<&String> = selected.email;
+    assert_eq!(rest.email, "bob@example.com");
+
+    // The original is still usable
+    assert_eq!(user.name, "Bob");
+}
+
+#[test]
+fn view_is_copy() {
+    let user = user::empty().id(3).name("Carol".to_owned());
+    let view = user.as_ref();
+    let (ids, _) = view.extract::<select!(user: id)>();
+    let (names, _) = view.extract::<select!(user: name?)>();
+    assert_eq!(*ids.id, 3);
+    assert_eq!(names.name.map(String::as_str), Some("Carol"));
+}
+
+#[test]
+fn as_mut_updates_fields() {
+    let mut user = user::empty()
+        .id(1)
+        .name("Dave".to_owned())
+        .email(Some("old@example.com".to_owned()));
+
+    let view = user.as_mut();
+    *view.id += 1;
+    view.name.push('!');
+    if let Some(email) = view.email {
+        *email = "new@example.com".to_owned();
+    }
+
+    assert_eq!(user.id, 2);
+    assert_eq!(user.name, "Dave!");
+    assert_eq!(user.email, Some("new@example.com".to_owned()));
+}
+
+#[test]
+fn as_mut_extract_subset() {
+    let mut user = user::empty().id(1).name("Eve".to_owned());
+    let (names, _) = user.as_mut().extract::<select!(user: name)>();
+    names.name.make_ascii_uppercase();
+    assert_eq!(user.name, "EVE");
+}
+
+#[test]
+fn views_with_user_lifetimes() {
+    let text = String::from("label");
+    let value = borrowing::empty().label(text.as_str()).count(2);
+    let view = value.as_ref();
+    assert_eq!(*view.label, "label");
+    assert_eq!(*view.count, 2);
+}